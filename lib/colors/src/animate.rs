@@ -0,0 +1,77 @@
+//! Frame based terminal animation.
+//!
+//! Frames are drawn in place by moving the cursor back up over the previous
+//! frame, wrapped in synchronized updates to avoid flicker. Frames may contain
+//! any number of lines and escape sequences.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let frames = ["[=   ]", "[ =  ]", "[  = ]", "[   =]"];
+//! colors::animate(&ansi, &frames, 8)?;
+//! ```
+
+use std::io::{self, Write};
+
+use crate::signal::Interrupt;
+use crate::stats::{self, Source};
+use crate::{cursor, sync, Codes, RenderBuf, Ticker};
+
+/// Play frames once at `fps` frames per second, limited by [`max_fps`], and
+/// return `false` if stopped early by Ctrl-C, cursor and last drawn frame are
+/// left in clean state. Frames identical to previous frame are not redrawn.
+///
+/// If colors are disabled in `codes` or animations are disabled, see
+/// [`Ticker`], only the last frame is printed.
+///
+/// [`max_fps`]: crate::max_fps
+pub fn animate<S: AsRef<str>>(codes: &Codes, frames: &[S], fps: u32) -> io::Result<bool> {
+    let mut stdout = io::stdout().lock();
+
+    let mut ticker = Ticker::new(fps);
+    if !codes.is_enabled() || !ticker.is_enabled() {
+        if let Some(last) = frames.last() {
+            writeln!(stdout, "{}", last.as_ref())?;
+        }
+        return Ok(true);
+    }
+
+    let interrupt = Interrupt::catch();
+    let mut height = 0;
+    let mut completed = true;
+    let mut output = RenderBuf::new();
+
+    write!(stdout, "{}", cursor::HIDE)?;
+    for frame in frames {
+        let frame = frame.as_ref();
+        if ticker.should_draw(frame) {
+            output.clear();
+            output.push_str(sync::BEGIN);
+            output.push_str(&cursor::up(height));
+            output.push('\r');
+            for line in frame.lines() {
+                output.push_str(cursor::CLEAR_LINE);
+                output.push_str(line);
+                output.push_str(&codes.attr.reset);
+                output.push('\n');
+            }
+            output.push_str(cursor::CLEAR_DOWN);
+            output.push_str(sync::END);
+            stdout.write_all(output.as_bytes())?;
+            stats::record(Source::Animation, output.as_bytes(), true);
+            stdout.flush()?;
+            height = frame.lines().count();
+        }
+
+        ticker.wait();
+        if interrupt.triggered() {
+            completed = false;
+            break;
+        }
+    }
+    write!(stdout, "{}", cursor::SHOW)?;
+    stdout.flush()?;
+
+    Ok(completed)
+}
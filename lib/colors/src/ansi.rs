@@ -0,0 +1,551 @@
+//! Escape sequence scanning.
+//!
+//! Splits strings into plain text and complete escape sequences so that
+//! effects and measurements never cut a sequence in half. Recognized forms are
+//! CSI (`ESC [`), OSC (`ESC ]`), string sequences (`ESC P`, `ESC X`, `ESC ^`,
+//! `ESC _`) terminated by BEL or `ESC \`, and `ESC x` sequences with optional
+//! intermediate bytes, e.g. `ESC ( B`.
+//! Unterminated sequences at the end of input are returned as is.
+//!
+//! [`strip`] and [`strip_bytes`] remove all escape sequences, [`transcode`]
+//! renders SGR sequences again for other [`Codes`].
+//!
+//! [`parse`] validates sequences byte by byte for untrusted input, either
+//! failing on the first malformed sequence or skipping or preserving it.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! for token in colors::ansi::parse(&untrusted, colors::ansi::Mode::Skip) {
+//!     let token = token?;
+//!     println!("{} {:?} {:?}", token.offset, token.kind, token.text);
+//! }
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt;
+
+use std::str::Split;
+
+use crate::convert::{index_to_16, rgb_to_16};
+use crate::{Attr, Codes, Color, Style};
+
+/// Part of scanned string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Segment<'a> {
+    Text(&'a str),
+    Escape(&'a str),
+}
+
+/// Iterator over [`Segment`] values of string.
+pub(crate) struct Segments<'a> {
+    input: &'a str,
+}
+
+/// Return iterator splitting input into text and escape sequences.
+pub(crate) fn segments(input: &str) -> Segments<'_> {
+    Segments { input }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Segment<'a>> {
+        if self.input.is_empty() {
+            return None;
+        }
+
+        let bytes = self.input.as_bytes();
+        let len = if bytes[0] == 0x1B {
+            escape_len(bytes)
+        } else {
+            bytes.iter().position(|&b| b == 0x1B).unwrap_or(bytes.len())
+        };
+        let (head, tail) = self.input.split_at(len);
+        self.input = tail;
+
+        if bytes[0] == 0x1B {
+            Some(Segment::Escape(head))
+        } else {
+            Some(Segment::Text(head))
+        }
+    }
+}
+
+/// Return byte length of escape sequence at start of input.
+fn escape_len(bytes: &[u8]) -> usize {
+    match bytes.get(1) {
+        None => 1,
+        Some(b'[') => {
+            // Parameter and intermediate bytes followed by final byte.
+            match bytes[2..].iter().position(|b| (0x40..=0x7E).contains(b)) {
+                Some(i) => i + 3,
+                None => bytes.len(),
+            }
+        }
+        Some(b']') | Some(b'P') | Some(b'X') | Some(b'^') | Some(b'_') => {
+            let mut i = 2;
+            while i < bytes.len() {
+                match bytes[i] {
+                    0x07 => return i + 1,
+                    0x1B if bytes.get(i + 1) == Some(&b'\\') => return i + 2,
+                    _ => i += 1,
+                }
+            }
+            bytes.len()
+        }
+        Some(0x20..=0x2F) => {
+            // Intermediate bytes followed by final byte, e.g. `ESC ( B`.
+            let i = 1 + bytes[1..]
+                .iter()
+                .take_while(|b| (0x20..=0x2F).contains(*b))
+                .count();
+            match bytes.get(i) {
+                Some(&b) if b < 0x80 => i + 1,
+                _ => i,
+            }
+        }
+        Some(&b) if b < 0x80 => 2,
+        // Never split multi-byte character following lone ESC.
+        Some(_) => 1,
+    }
+}
+
+/// Return length of input without escape sequence cut off at its end, e.g.
+/// to keep it until rest of sequence arrives.
+pub(crate) fn complete_len(input: &str) -> usize {
+    match input.rfind('\x1B') {
+        Some(start) => match validate(&input.as_bytes()[start..]) {
+            Err((_, ErrorKind::Unterminated)) => start,
+            _ => input.len(),
+        },
+        None => input.len(),
+    }
+}
+
+/// Return input with all escape sequences removed, e.g. before measuring
+/// width or logging styled output to plain files.
+pub fn strip(input: &str) -> String {
+    segments(input)
+        .filter_map(|segment| match segment {
+            Segment::Text(text) => Some(text),
+            Segment::Escape(_) => None,
+        })
+        .collect()
+}
+
+/// Return text with styles of SGR sequences rendered again using `codes`,
+/// one sequence per run of equally styled text, e.g. to pass output of a
+/// child process on to a destination with other capabilities. SGR sequences
+/// are dropped if colors are disabled, other escape sequences are kept.
+/// Colors beyond the 16 ANSI colors are not kept.
+pub fn transcode(codes: &Codes, input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let (mut style, mut run) = (Style::new(), String::new());
+    let flush = |output: &mut String, style: Style, run: &mut String| {
+        if !run.is_empty() {
+            output.push_str(&style.paint(codes, run));
+            run.clear();
+        }
+    };
+    for segment in segments(input) {
+        match segment {
+            Segment::Escape(seq) if seq.starts_with("\x1B[") && seq.ends_with('m') => {
+                let next = apply_sgr(style, seq);
+                if next != style {
+                    flush(&mut output, style, &mut run);
+                    style = next;
+                }
+            }
+            Segment::Escape(seq) => {
+                flush(&mut output, style, &mut run);
+                output.push_str(seq);
+            }
+            Segment::Text(text) => run.push_str(text),
+        }
+    }
+    flush(&mut output, style, &mut run);
+    output
+}
+
+/// Return bytes with all escape sequences removed, see [`strip`]. Input does
+/// not need to be valid UTF-8, e.g. raw output of child process.
+pub fn strip_bytes(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut rest = input;
+    while !rest.is_empty() {
+        let len = if rest[0] == 0x1B {
+            escape_len(rest)
+        } else {
+            let len = rest.iter().position(|&b| b == 0x1B).unwrap_or(rest.len());
+            output.extend_from_slice(&rest[..len]);
+            len
+        };
+        rest = &rest[len..];
+    }
+    output
+}
+
+/// Return 16 color palette entry for SGR color offset, e.g. 1 for red.
+fn palette(offset: u16, bright: bool) -> Option<Color> {
+    const COLORS: [Color; 8] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+    const BRIGHT: [Color; 8] = [
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+    let table = if bright { &BRIGHT } else { &COLORS };
+    table.get(usize::from(offset)).copied()
+}
+
+/// Parameter of SGR sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Param {
+    /// Code without arguments, e.g. 1 for bold.
+    Code(u16),
+    /// Extended color code 38, 48 or 58 with its color reduced to 16 colors,
+    /// or `None` if arguments are invalid.
+    Extended(u16, Option<Color>),
+}
+
+/// Return number of parameter, empty meaning 0, or `None` if invalid.
+fn number(param: &str) -> Option<u16> {
+    if param.is_empty() {
+        Some(0)
+    } else {
+        param.parse().ok()
+    }
+}
+
+/// Return color of extended color arguments, e.g. `5;196` or `2;255;0;0`,
+/// reduced to 16 colors.
+fn extended(args: &[Option<u16>]) -> Option<Color> {
+    let byte = |i: usize| {
+        args.get(i)
+            .copied()
+            .flatten()
+            .and_then(|n| u8::try_from(n).ok())
+    };
+    match args.first().copied().flatten()? {
+        5 => byte(1).map(index_to_16),
+        2 => Some(rgb_to_16(byte(1)?, byte(2)?, byte(3)?)),
+        _ => None,
+    }
+}
+
+/// Iterator over [`Param`] values of SGR parameters, see [`sgr_params`].
+pub(crate) struct SgrParams<'a> {
+    fields: Split<'a, char>,
+}
+
+impl Iterator for SgrParams<'_> {
+    type Item = Param;
+
+    fn next(&mut self) -> Option<Param> {
+        loop {
+            let field = self.fields.next()?;
+            let mut parts = field.split(':');
+            // Subparameters are ignored unless they belong to extended colors.
+            let code = match parts.next().and_then(number) {
+                Some(code) => code,
+                None => continue,
+            };
+            if !matches!(code, 38 | 48 | 58) {
+                return Some(Param::Code(code));
+            }
+            let mut args: Vec<Option<u16>> = parts.map(number).collect();
+            if field.contains(':') {
+                // Color space identifier may precede RGB components.
+                if args.first() == Some(&Some(2)) && args.len() > 4 {
+                    args.remove(1);
+                }
+            } else {
+                let count = match self.fields.clone().next().and_then(number) {
+                    Some(5) => 2,
+                    Some(2) => 4,
+                    _ => 1,
+                };
+                args = self.fields.by_ref().take(count).map(number).collect();
+            }
+            return Some(Param::Extended(code, extended(&args)));
+        }
+    }
+}
+
+/// Return iterator over parameters of SGR sequence without `ESC [` and `m`,
+/// with arguments of extended colors in either `;` or `:` form consumed and
+/// invalid parameters skipped.
+pub(crate) fn sgr_params(params: &str) -> SgrParams<'_> {
+    SgrParams {
+        fields: params.split(';'),
+    }
+}
+
+/// Return style after applying escape sequence, which is returned unchanged
+/// unless sequence is SGR. Extended colors are reduced to 16 colors.
+pub(crate) fn apply_sgr(style: Style, seq: &str) -> Style {
+    let params = match seq.strip_prefix("\x1B[").and_then(|s| s.strip_suffix('m')) {
+        Some(params) => params,
+        None => return style,
+    };
+
+    let mut style = style;
+    for param in sgr_params(params) {
+        let n = match param {
+            Param::Code(n) => n,
+            Param::Extended(38, Some(color)) => {
+                style.fg = Some(color);
+                continue;
+            }
+            Param::Extended(48, Some(color)) => {
+                style.bg = Some(color);
+                continue;
+            }
+            Param::Extended(..) => continue,
+        };
+        match n {
+            0 => style = Style::new(),
+            1 => style = style.attr(Attr::Bold),
+            2 => style = style.attr(Attr::Dim),
+            3 => style = style.attr(Attr::Italic),
+            4 => style = style.attr(Attr::Underline),
+            5 => style = style.attr(Attr::Blink),
+            7 => style = style.attr(Attr::Reverse),
+            8 => style = style.attr(Attr::Hidden),
+            9 => style = style.attr(Attr::Strikethrough),
+            21 => style = style.attr(Attr::DoubleUnderline),
+            22 => style = style.without(Attr::Bold).without(Attr::Dim),
+            23 => style = style.without(Attr::Italic),
+            24 => {
+                style = style
+                    .without(Attr::Underline)
+                    .without(Attr::DoubleUnderline)
+            }
+            25 => style = style.without(Attr::Blink),
+            27 => style = style.without(Attr::Reverse),
+            28 => style = style.without(Attr::Hidden),
+            29 => style = style.without(Attr::Strikethrough),
+            53 => style = style.attr(Attr::Overline),
+            55 => style = style.without(Attr::Overline),
+            30..=37 => style.fg = palette(n - 30, false),
+            39 => style.fg = None,
+            40..=47 => style.bg = palette(n - 40, false),
+            49 => style.bg = None,
+            90..=97 => style.fg = palette(n - 90, true),
+            100..=107 => style.bg = palette(n - 100, true),
+            _ => (),
+        }
+    }
+    style
+}
+
+/// Handling of malformed escape sequences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Return error for first malformed sequence and stop.
+    Strict,
+    /// Drop malformed sequences.
+    Skip,
+    /// Return malformed sequences as [`TokenKind::Malformed`] tokens.
+    Preserve,
+}
+
+/// Kind of parsed token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Text,
+    Escape,
+    Malformed,
+}
+
+/// Part of parsed input starting at byte `offset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Token<'a> {
+    pub offset: usize,
+    pub kind: TokenKind,
+    pub text: &'a str,
+}
+
+/// Reason escape sequence is malformed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Input ended before sequence was terminated.
+    Unterminated,
+    /// Byte not allowed at this position of sequence.
+    InvalidByte(u8),
+}
+
+/// Malformed escape sequence starting at byte `start`, with problem found at
+/// byte `offset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Error {
+    pub start: usize,
+    pub offset: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Unterminated => {
+                write!(f, "unterminated escape sequence at byte {}", self.start)
+            }
+            ErrorKind::InvalidByte(b) => write!(
+                f,
+                "invalid byte 0x{:02X} at byte {} in escape sequence at byte {}",
+                b, self.offset, self.start
+            ),
+        }
+    }
+}
+
+impl StdError for Error {}
+
+/// Return length of valid escape sequence at start of input, or position and
+/// kind of problem. Sequences with invalid bytes end before that byte.
+fn validate(bytes: &[u8]) -> Result<usize, (usize, ErrorKind)> {
+    let invalid = |i: usize| Err((i, ErrorKind::InvalidByte(bytes[i])));
+    let unterminated = Err((bytes.len(), ErrorKind::Unterminated));
+    match bytes.get(1) {
+        None => unterminated,
+        Some(b'[') => {
+            let mut i = 2;
+            // Parameter bytes, then intermediate bytes, then final byte.
+            while i < bytes.len() && (0x30..=0x3F).contains(&bytes[i]) {
+                i += 1;
+            }
+            while i < bytes.len() && (0x20..=0x2F).contains(&bytes[i]) {
+                i += 1;
+            }
+            match bytes.get(i) {
+                None => unterminated,
+                Some(0x40..=0x7E) => Ok(i + 1),
+                Some(_) => invalid(i),
+            }
+        }
+        Some(b']') | Some(b'P') | Some(b'X') | Some(b'^') | Some(b'_') => {
+            let mut i = 2;
+            while i < bytes.len() {
+                match bytes[i] {
+                    0x07 => return Ok(i + 1),
+                    0x1B if bytes.get(i + 1) == Some(&b'\\') => return Ok(i + 2),
+                    0x00..=0x1F | 0x7F => return invalid(i),
+                    _ => i += 1,
+                }
+            }
+            unterminated
+        }
+        Some(_) => {
+            // Intermediate bytes followed by final byte, e.g. `ESC ( B`.
+            let mut i = 1;
+            while i < bytes.len() && (0x20..=0x2F).contains(&bytes[i]) {
+                i += 1;
+            }
+            match bytes.get(i) {
+                None => unterminated,
+                Some(0x30..=0x7E) => Ok(i + 1),
+                Some(_) => invalid(i),
+            }
+        }
+    }
+}
+
+/// Iterator over [`Token`] values of input, see [`parse`].
+pub struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+    mode: Mode,
+    failed: bool,
+}
+
+/// Return iterator validating and splitting input into tokens, handling
+/// malformed escape sequences according to mode.
+pub fn parse(input: &str, mode: Mode) -> Parser<'_> {
+    Parser {
+        input,
+        offset: 0,
+        mode,
+        failed: false,
+    }
+}
+
+impl<'a> Parser<'a> {
+    /// Consume and return token of `len` bytes.
+    fn take(&mut self, len: usize, kind: TokenKind) -> Token<'a> {
+        let (head, tail) = self.input.split_at(len);
+        let token = Token {
+            offset: self.offset,
+            kind,
+            text: head,
+        };
+        self.input = tail;
+        self.offset += len;
+        token
+    }
+}
+
+impl<'a> Iterator for Parser<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Result<Token<'a>, Error>> {
+        loop {
+            if self.failed || self.input.is_empty() {
+                return None;
+            }
+
+            let bytes = self.input.as_bytes();
+            if bytes[0] != 0x1B {
+                let len = bytes.iter().position(|&b| b == 0x1B).unwrap_or(bytes.len());
+                return Some(Ok(self.take(len, TokenKind::Text)));
+            }
+
+            let (len, problem) = match validate(bytes) {
+                Ok(len) => return Some(Ok(self.take(len, TokenKind::Escape))),
+                Err((i, kind)) => (i, kind),
+            };
+            match self.mode {
+                Mode::Strict => {
+                    self.failed = true;
+                    return Some(Err(Error {
+                        start: self.offset,
+                        offset: self.offset + len,
+                        kind: problem,
+                    }));
+                }
+                Mode::Skip => {
+                    self.take(len, TokenKind::Malformed);
+                }
+                Mode::Preserve => return Some(Ok(self.take(len, TokenKind::Malformed))),
+            }
+        }
+    }
+}
+
+/// Return input with all escape sequences removed, handling malformed
+/// sequences according to mode. Malformed sequences are kept as text with
+/// [`Mode::Preserve`].
+pub fn strip_with(input: &str, mode: Mode) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    for token in parse(input, mode) {
+        let token = token?;
+        if token.kind != TokenKind::Escape {
+            output.push_str(token.text);
+        }
+    }
+    Ok(output)
+}
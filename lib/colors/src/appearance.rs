@@ -0,0 +1,199 @@
+//! Light or dark appearance of terminal or operating system.
+//!
+//! Terminal background is detected from `COLORFGBG`, set by e.g. rxvt and
+//! Konsole, or by asking the terminal for its background color (OSC 11) with
+//! a short timeout if standard output is a TTY. Known terminal defaults and
+//! the operating system preference are used as fallback.
+//!
+//! The operating system preference is only read with feature `os-appearance`
+//! enabled, since it runs external commands: `defaults` on macOS, `reg` on
+//! Windows and `gsettings` elsewhere. It is a hint for choosing styles when
+//! the terminal itself can not be asked, e.g. inside IDE embedded terminals.
+//! [`Theme::detect`](crate::Theme::detect) follows the terminal background.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let muted = match colors::BackgroundKind::detect() {
+//!     colors::BackgroundKind::Light => Style::new().fg(Color::BrightBlack),
+//!     _ => Style::new().attr(Attr::Dim),
+//! };
+//! ```
+
+use std::env;
+use std::sync::OnceLock;
+
+use crate::{contrast_fg, is_tty, term, Color};
+
+static BACKGROUND: OnceLock<BackgroundKind> = OnceLock::new();
+
+/// Light or dark background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    /// Return appearance preferred by operating system, or `None` if
+    /// unknown, see [`os_appearance`]. Terminal background is detected by
+    /// [`BackgroundKind::detect`].
+    pub fn detect() -> Option<Appearance> {
+        os_appearance()
+    }
+}
+
+/// Light, dark or undetectable terminal background.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BackgroundKind {
+    Light,
+    Dark,
+    #[default]
+    Unknown,
+}
+
+impl BackgroundKind {
+    /// Return detected terminal background. Detection runs once, later calls
+    /// return the same result.
+    pub fn detect() -> BackgroundKind {
+        *BACKGROUND.get_or_init(|| {
+            let appearance = colorfgbg()
+                .or_else(query)
+                .or_else(terminal_default)
+                .or_else(os_appearance);
+            BackgroundKind::from(appearance)
+        })
+    }
+
+    /// Return appearance, or `None` if unknown.
+    pub fn appearance(self) -> Option<Appearance> {
+        match self {
+            BackgroundKind::Light => Some(Appearance::Light),
+            BackgroundKind::Dark => Some(Appearance::Dark),
+            BackgroundKind::Unknown => None,
+        }
+    }
+}
+
+impl From<Option<Appearance>> for BackgroundKind {
+    fn from(appearance: Option<Appearance>) -> BackgroundKind {
+        match appearance {
+            Some(Appearance::Light) => BackgroundKind::Light,
+            Some(Appearance::Dark) => BackgroundKind::Dark,
+            None => BackgroundKind::Unknown,
+        }
+    }
+}
+
+/// Return appearance of background RGB color.
+fn from_rgb(rgb: (u8, u8, u8)) -> Appearance {
+    if contrast_fg(rgb) == Color::Black {
+        Appearance::Light
+    } else {
+        Appearance::Dark
+    }
+}
+
+/// Return appearance from background color index in `COLORFGBG`, e.g. `15;0`
+/// or `0;default;15`. Indexes 7 and 9 to 15 are light.
+fn colorfgbg() -> Option<Appearance> {
+    let value = env::var("COLORFGBG").ok()?;
+    let index: u8 = value.rsplit(';').next()?.parse().ok()?;
+    match index {
+        7 | 9..=15 => Some(Appearance::Light),
+        0..=6 | 8 => Some(Appearance::Dark),
+        _ => None,
+    }
+}
+
+/// Return appearance of background color reported by terminal.
+fn query() -> Option<Appearance> {
+    if !is_tty() {
+        return None;
+    }
+    term::background_color().ok().map(from_rgb)
+}
+
+/// Return appearance of default profile of known terminals.
+fn terminal_default() -> Option<Appearance> {
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+    if program == "Apple_Terminal" {
+        Some(Appearance::Light)
+    } else if term == "linux" || env::var_os("WT_SESSION").is_some() {
+        Some(Appearance::Dark)
+    } else {
+        None
+    }
+}
+
+/// Return appearance preferred by operating system, or `None` if unknown or
+/// feature `os-appearance` is disabled.
+pub fn os_appearance() -> Option<Appearance> {
+    #[cfg(feature = "os-appearance")]
+    {
+        os::appearance()
+    }
+    #[cfg(not(feature = "os-appearance"))]
+    {
+        None
+    }
+}
+
+#[cfg(feature = "os-appearance")]
+mod os {
+    use std::process::{Command, Stdio};
+
+    use super::Appearance;
+
+    /// Return standard output of command, or `None` if it could not be run.
+    fn output(program: &str, args: &[&str]) -> Option<(bool, String)> {
+        let output = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Some((output.status.success(), stdout))
+    }
+
+    /// Read `AppleInterfaceStyle`, which is only set in dark mode.
+    #[cfg(target_os = "macos")]
+    pub(super) fn appearance() -> Option<Appearance> {
+        let (ok, value) = output("defaults", &["read", "-g", "AppleInterfaceStyle"])?;
+        if ok && value == "Dark" {
+            Some(Appearance::Dark)
+        } else {
+            Some(Appearance::Light)
+        }
+    }
+
+    /// Read `AppsUseLightTheme` from registry.
+    #[cfg(windows)]
+    pub(super) fn appearance() -> Option<Appearance> {
+        let key = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+        let (ok, value) = output("reg", &["query", key, "/v", "AppsUseLightTheme"])?;
+        if !ok {
+            return None;
+        }
+        if value.trim_end().ends_with("0x0") {
+            Some(Appearance::Dark)
+        } else {
+            Some(Appearance::Light)
+        }
+    }
+
+    /// Read GNOME `color-scheme` setting.
+    #[cfg(not(any(target_os = "macos", windows)))]
+    pub(super) fn appearance() -> Option<Appearance> {
+        let args = ["get", "org.gnome.desktop.interface", "color-scheme"];
+        let (ok, value) = output("gsettings", &args)?;
+        match value.trim_matches('\'') {
+            _ if !ok => None,
+            "prefer-dark" => Some(Appearance::Dark),
+            "prefer-light" | "default" => Some(Appearance::Light),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! Rendering of classic ANSI art files.
+//!
+//! `.ans` files are CP437 text with SGR and cursor sequences written for DOS
+//! terminals, optionally followed by a SAUCE record describing title, author
+//! and width. Rendering replays the file on a grid of the declared width,
+//! 80 columns by default, and returns lines of UTF-8 text styled through
+//! [`Codes`] for modern terminals. Bold foreground selects bright colors as on
+//! DOS, blink selects bright backgrounds if the SAUCE record asks for iCE
+//! colors.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! print!("{}", colors::render_ans(&ansi, &fs::read("splash.ans")?));
+//! ```
+
+use crate::ansi;
+use crate::screen::{Cell, Snapshot};
+use crate::{Attr, Codes, Color, Style};
+
+/// Size of SAUCE record at end of file.
+const SAUCE_LEN: usize = 128;
+
+/// End of file marker preceding SAUCE record.
+const SUB: u8 = 0x1A;
+
+/// Maximum number of rows rendered, cursor movement in untrusted files must
+/// not allocate unbounded grids.
+const MAX_ROWS: usize = 10_000;
+
+/// Maximum canvas width, larger SAUCE widths are clamped.
+const MAX_COLUMNS: usize = 1000;
+
+/// Maximum number of cells allocated for whole canvas, characters needing
+/// more are dropped.
+const MAX_CELLS: usize = 1_000_000;
+
+/// Unicode characters of CP437 bytes 0x00 to 0x1F.
+#[rustfmt::skip]
+const CP437_LOW: [char; 32] = [
+    ' ', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼',
+    '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+];
+
+/// Unicode characters of CP437 bytes 0x80 to 0xFF.
+#[rustfmt::skip]
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+/// Return Unicode character of CP437 byte.
+fn cp437(byte: u8) -> char {
+    match byte {
+        0x00..=0x1F => CP437_LOW[usize::from(byte)],
+        0x7F => '⌂',
+        0x80..=0xFF => CP437_HIGH[usize::from(byte - 0x80)],
+        _ => char::from(byte),
+    }
+}
+
+/// Return CP437 text as string without padding spaces and NUL bytes.
+fn cp437_field(bytes: &[u8]) -> String {
+    let text: String = bytes.iter().map(|&b| cp437(b)).collect();
+    text.trim_end_matches([' ', '\u{A0}']).to_string()
+}
+
+/// Metadata record appended to ANSI art files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sauce {
+    pub title: String,
+    pub author: String,
+    pub group: String,
+    /// Creation date as `CCYYMMDD`.
+    pub date: String,
+    /// Width in columns, `0` if not declared.
+    pub width: u16,
+    /// Height in lines, `0` if not declared.
+    pub height: u16,
+    /// Blink selects bright background colors instead of blinking.
+    pub ice_colors: bool,
+}
+
+impl Sauce {
+    /// Parse SAUCE record at end of data, or return `None` if there is none.
+    pub fn parse(data: &[u8]) -> Option<Sauce> {
+        let record = data.get(data.len().checked_sub(SAUCE_LEN)?..)?;
+        if &record[..7] != b"SAUCE00" {
+            return None;
+        }
+        let number = |i: usize| u16::from_le_bytes([record[i], record[i + 1]]);
+        // Width and height only apply to character based files.
+        let character = record[94] == 1;
+        Some(Sauce {
+            title: cp437_field(&record[7..42]),
+            author: cp437_field(&record[42..62]),
+            group: cp437_field(&record[62..82]),
+            date: cp437_field(&record[82..90]),
+            width: if character { number(96) } else { 0 },
+            height: if character { number(98) } else { 0 },
+            ice_colors: record[105] & 1 != 0,
+        })
+    }
+}
+
+/// Grid growing downwards as art is replayed.
+struct Canvas {
+    width: usize,
+    rows: Vec<Vec<Cell>>,
+    cells: usize,
+    x: usize,
+    y: usize,
+    saved: (usize, usize),
+    style: Style,
+    ice_colors: bool,
+}
+
+impl Canvas {
+    /// Return style shown for current SGR state, following DOS conventions.
+    fn display_style(&self) -> Style {
+        let mut style = self.style;
+        if style.has(Attr::Bold) {
+            style = style.without(Attr::Bold);
+            style.fg = Some(style.fg.unwrap_or(Color::White).bright());
+        }
+        if self.ice_colors && style.has(Attr::Blink) {
+            style = style.without(Attr::Blink);
+            style.bg = Some(style.bg.unwrap_or(Color::Black).bright());
+        }
+        style
+    }
+
+    /// Return row, adding rows below if needed.
+    fn row(&mut self, y: usize) -> &mut Vec<Cell> {
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, Vec::new());
+        }
+        &mut self.rows[y]
+    }
+
+    /// Move cursor down by `n` rows, at most to first row past
+    /// [`MAX_ROWS`].
+    fn down(&mut self, n: usize) {
+        self.y = self.y.saturating_add(n).min(MAX_ROWS);
+    }
+
+    /// Put character at cursor and advance, wrapping at canvas width.
+    /// Characters below [`MAX_ROWS`] or exceeding [`MAX_CELLS`] are dropped.
+    fn put(&mut self, ch: char) {
+        if self.x >= self.width {
+            self.x = 0;
+            self.down(1);
+        }
+        if self.y >= MAX_ROWS {
+            return;
+        }
+        let (x, y, style) = (self.x, self.y, self.display_style());
+        let len = self.rows.get(y).map_or(0, Vec::len);
+        if len <= x {
+            if self.cells + (x + 1 - len) > MAX_CELLS {
+                return;
+            }
+            self.cells += x + 1 - len;
+            self.row(y).resize(x + 1, Cell::default());
+        }
+        self.rows[y][x] = Cell { ch, style };
+        // Pending wrap, next character starts new line.
+        self.x += 1;
+    }
+
+    /// Interpret CSI sequence without `ESC [` prefix.
+    fn csi(&mut self, body: &str) {
+        let (params, last) = body.split_at(body.len() - 1);
+        if params.starts_with('?') {
+            return;
+        }
+        let args: Vec<usize> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        let arg = |i: usize| match args.get(i) {
+            Some(&0) | None => 1,
+            Some(&n) => n,
+        };
+        let max_x = self.width - 1;
+        match last {
+            "A" => self.y = self.y.saturating_sub(arg(0)),
+            "B" => self.down(arg(0)),
+            "C" => self.x = self.x.saturating_add(arg(0)).min(max_x),
+            "D" => self.x = self.x.min(max_x).saturating_sub(arg(0)),
+            "H" | "f" => (self.y, self.x) = ((arg(0) - 1).min(MAX_ROWS), (arg(1) - 1).min(max_x)),
+            "s" => self.saved = (self.x, self.y),
+            "u" => (self.x, self.y) = self.saved,
+            "J" if args.first() == Some(&2) => {
+                self.rows.clear();
+                self.cells = 0;
+                (self.x, self.y) = (0, 0);
+            }
+            "K" if self.y < MAX_ROWS => {
+                let (x, y) = (self.x, self.y);
+                let row = self.row(y);
+                let removed = row.len().saturating_sub(x);
+                row.truncate(x);
+                self.cells -= removed;
+            }
+            "m" => self.style = ansi::apply_sgr(self.style, &format!("\x1B[{}", body)),
+            _ => (),
+        }
+    }
+}
+
+/// Return ANSI art file rendered as styled UTF-8 lines, using width from
+/// SAUCE record if present, at most [`MAX_COLUMNS`], and 80 columns
+/// otherwise.
+pub fn render_ans(codes: &Codes, data: &[u8]) -> String {
+    let sauce = Sauce::parse(data);
+    let end = data.iter().position(|&b| b == SUB).unwrap_or(match sauce {
+        Some(_) => data.len() - SAUCE_LEN,
+        None => data.len(),
+    });
+    let width = match &sauce {
+        Some(sauce) if sauce.width > 0 => usize::from(sauce.width).min(MAX_COLUMNS),
+        _ => 80,
+    };
+    let mut canvas = Canvas {
+        width,
+        rows: Vec::new(),
+        cells: 0,
+        x: 0,
+        y: 0,
+        saved: (0, 0),
+        style: Style::new(),
+        ice_colors: sauce.as_ref().is_some_and(|sauce| sauce.ice_colors),
+    };
+
+    let mut rest = &data[..end];
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'\r' => canvas.x = 0,
+            b'\n' => {
+                canvas.x = 0;
+                canvas.down(1);
+            }
+            0x1B if rest.first() == Some(&b'[') => {
+                let len = match rest[1..].iter().position(|b| (0x40..=0x7E).contains(b)) {
+                    Some(i) => i + 2,
+                    None => break,
+                };
+                if let Ok(body) = std::str::from_utf8(&rest[1..len]) {
+                    canvas.csi(body);
+                }
+                rest = &rest[len..];
+            }
+            0x1B => rest = rest.get(1..).unwrap_or_default(),
+            byte => canvas.put(cp437(byte)),
+        }
+    }
+
+    Snapshot::from_rows(canvas.rows.iter().map(Vec::as_slice)).render(codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_off, init_on_with, ColorSupport};
+
+    /// Return SAUCE record with title and width.
+    fn sauce(title: &str, width: u16, ice_colors: bool) -> Vec<u8> {
+        let mut record = vec![b' '; SAUCE_LEN];
+        record[..7].copy_from_slice(b"SAUCE00");
+        record[7..7 + title.len()].copy_from_slice(title.as_bytes());
+        record[82..90].copy_from_slice(b"20240101");
+        record[94] = 1;
+        record[96..98].copy_from_slice(&width.to_le_bytes());
+        record[98..100].copy_from_slice(&2u16.to_le_bytes());
+        record[105] = u8::from(ice_colors);
+        record
+    }
+
+    #[test]
+    fn parse_sauce() {
+        let mut data = b"hi".to_vec();
+        data.push(SUB);
+        data.extend(sauce("Title", 40, true));
+        let sauce = Sauce::parse(&data).unwrap();
+        assert_eq!(sauce.title, "Title");
+        assert_eq!(sauce.author, "");
+        assert_eq!(sauce.date, "20240101");
+        assert_eq!((sauce.width, sauce.height), (40, 2));
+        assert!(sauce.ice_colors);
+        assert_eq!(Sauce::parse(b"no record"), None);
+    }
+
+    #[test]
+    fn render_cp437_and_wrap() {
+        let mut data = vec![0xDB, 0xB0, b'a', b'b', b'c'];
+        data.push(SUB);
+        data.extend(sauce("", 3, false));
+        assert_eq!(render_ans(&init_off(), &data), "█░a\nbc\n");
+    }
+
+    #[test]
+    fn render_bold_as_bright() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let output = render_ans(&codes, b"\x1B[1;31mX\x1B[0m");
+        assert_eq!(output, "\x1B[91mX\x1B[0m\n");
+    }
+
+    #[test]
+    fn cursor_movement() {
+        let output = render_ans(&init_off(), b"ab\x1B[1;1Hc\x1B[2Bd\x1B[sx\x1B[uy");
+        assert_eq!(output, "cb\n\n dy\n");
+    }
+
+    #[test]
+    fn huge_cursor_movement_is_bounded() {
+        let codes = init_off();
+        // Unparsable count moves by one row, maximum count off the canvas.
+        let output = render_ans(
+            &codes,
+            b"\x1B[99999999999999999999Bx\x1B[18446744073709551615By",
+        );
+        assert_eq!(output, "\nx\n");
+        let output = render_ans(&codes, b"\x1B[4294967295;4294967295Hx");
+        assert!(output.is_empty());
+        let output = render_ans(&codes, b"\x1B[9999Hx");
+        assert_eq!(output.lines().count(), 9999);
+    }
+
+    #[test]
+    fn huge_width_is_bounded() {
+        let codes = init_off();
+        let mut data = b"\x1B[1;65535Hx".to_vec();
+        data.extend(sauce("", u16::MAX, false));
+        let output = render_ans(&codes, &data);
+        assert_eq!(output, format!("{}x\n", " ".repeat(MAX_COLUMNS - 1)));
+
+        // Characters at end of every row need more cells than allowed.
+        let mut data = Vec::new();
+        for y in 1..=MAX_ROWS {
+            data.extend(format!("\x1B[{};{}Hx", y, MAX_COLUMNS).bytes());
+        }
+        data.extend(sauce("", u16::MAX, false));
+        let output = render_ans(&codes, &data);
+        assert_eq!(output.matches('x').count(), MAX_CELLS / MAX_COLUMNS);
+    }
+}
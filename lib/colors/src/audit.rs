@@ -0,0 +1,204 @@
+//! Audit of rendered output.
+//!
+//! Finds sequences leaving the terminal in a modified state after output
+//! ends, e.g. styles without final reset, hidden cursor or alternate screen.
+//! Intended for tests of tools built on this crate.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let issues = colors::audit(&rendered);
+//! assert!(issues.is_empty(), "{:?}", issues);
+//! ```
+
+use std::fmt;
+
+use crate::ansi::{apply_sgr, segments, sgr_params, Param, Segment};
+use crate::Style;
+
+/// Kind of problem found in output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IssueKind {
+    /// Escape sequence cut off at end of output.
+    UnterminatedSequence,
+    /// Style is still active at end of output.
+    MissingReset,
+    /// OSC 8 hyperlink opened but never closed.
+    UnclosedHyperlink,
+    /// Cursor hidden but never shown again.
+    CursorHidden,
+    /// Alternate screen entered but never left.
+    AlternateScreen,
+    /// Synchronized update started but never ended.
+    SyncUpdate,
+}
+
+/// Problem found in output, `offset` is byte offset of sequence causing it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub offset: usize,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            IssueKind::UnterminatedSequence => "unterminated escape sequence",
+            IssueKind::MissingReset => "style not reset at end of output",
+            IssueKind::UnclosedHyperlink => "hyperlink not closed",
+            IssueKind::CursorHidden => "cursor left hidden",
+            IssueKind::AlternateScreen => "alternate screen left active",
+            IssueKind::SyncUpdate => "synchronized update not ended",
+        };
+        write!(f, "{} at byte {}", message, self.offset)
+    }
+}
+
+/// Check if escape sequence is complete.
+fn is_terminated(seq: &str) -> bool {
+    let bytes = seq.as_bytes();
+    match bytes.get(1) {
+        None => false,
+        Some(b'[') => bytes.len() > 2 && (0x40..=0x7E).contains(&bytes[bytes.len() - 1]),
+        Some(b']') | Some(b'P') | Some(b'X') | Some(b'^') | Some(b'_') => {
+            seq.ends_with('\x07') || (seq.len() > 3 && seq.ends_with("\x1B\\"))
+        }
+        Some(_) => true,
+    }
+}
+
+/// Return private mode set (`true`) or reset (`false`) by sequence, e.g.
+/// `\x1B[?25l`.
+fn private_mode(seq: &str) -> Option<(&str, bool)> {
+    let body = seq.strip_prefix("\x1B[?")?;
+    if let Some(modes) = body.strip_suffix('h') {
+        Some((modes, true))
+    } else {
+        body.strip_suffix('l').map(|modes| (modes, false))
+    }
+}
+
+/// Return issues found in output, ordered by offset.
+pub fn audit(output: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut style = Style::new();
+    let mut underline_color = false;
+    let mut styled = None;
+    let mut link = None;
+    let mut hidden = None;
+    let mut alternate = None;
+    let mut sync = None;
+
+    let mut offset = 0;
+    for segment in segments(output) {
+        let seq = match segment {
+            Segment::Text(text) => {
+                offset += text.len();
+                continue;
+            }
+            Segment::Escape(seq) => seq,
+        };
+
+        if !is_terminated(seq) {
+            issues.push(Issue {
+                kind: IssueKind::UnterminatedSequence,
+                offset,
+            });
+        } else if let Some(params) = seq.strip_prefix("\x1B[").and_then(|s| s.strip_suffix('m')) {
+            // Style has no underline color, so it is tracked separately.
+            for param in sgr_params(params) {
+                match param {
+                    Param::Code(0) | Param::Code(59) => underline_color = false,
+                    Param::Extended(58, _) => underline_color = true,
+                    _ => (),
+                }
+            }
+            style = apply_sgr(style, seq);
+            if style.is_plain() && !underline_color {
+                styled = None;
+            } else if styled.is_none() {
+                styled = Some(offset);
+            }
+        } else if let Some(url) = seq.strip_prefix("\x1B]8;") {
+            let url = url.split_once(';').map(|(_, url)| url).unwrap_or_default();
+            if url.trim_end_matches(['\x07', '\x1B', '\\']).is_empty() {
+                link = None;
+            } else {
+                link = Some(offset);
+            }
+        } else if let Some((modes, set)) = private_mode(seq) {
+            let state = if set { Some(offset) } else { None };
+            for mode in modes.split(';') {
+                match mode {
+                    "25" => hidden = if set { None } else { Some(offset) },
+                    "47" | "1047" | "1049" => alternate = state,
+                    "2026" => sync = state,
+                    _ => (),
+                }
+            }
+        }
+        offset += seq.len();
+    }
+
+    let open = [
+        (styled, IssueKind::MissingReset),
+        (link, IssueKind::UnclosedHyperlink),
+        (hidden, IssueKind::CursorHidden),
+        (alternate, IssueKind::AlternateScreen),
+        (sync, IssueKind::SyncUpdate),
+    ];
+    for (state, kind) in open {
+        if let Some(offset) = state {
+            issues.push(Issue { kind, offset });
+        }
+    }
+    issues.sort_by_key(|issue| issue.offset);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return kinds of issues found in output.
+    fn kinds(output: &str) -> Vec<IssueKind> {
+        audit(output).iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn clean_output() {
+        assert_eq!(kinds("\x1B[1;38;5;0mX\x1B[0m"), []);
+        assert_eq!(kinds("\x1B[?25l\x1B[?1049hX\x1B[?1049l\x1B[?25h"), []);
+        assert_eq!(kinds("\x1B]8;;https://example.com\x07X\x1B]8;;\x07"), []);
+    }
+
+    #[test]
+    fn unreset_extended_colors() {
+        assert_eq!(
+            audit("a\x1B[38;2;255;0;0mX"),
+            [Issue {
+                kind: IssueKind::MissingReset,
+                offset: 1,
+            }]
+        );
+        assert_eq!(kinds("\x1B[38;5;0mX"), [IssueKind::MissingReset]);
+        assert_eq!(kinds("\x1B[38:5:0mX"), [IssueKind::MissingReset]);
+        // Resetting foreground leaves extended background open.
+        assert_eq!(kinds("\x1B[48;5;0mX\x1B[39m"), [IssueKind::MissingReset]);
+        assert_eq!(kinds("\x1B[58;5;1mX\x1B[39;49m"), [IssueKind::MissingReset]);
+        assert_eq!(kinds("\x1B[58;5;1;48;5;0mX\x1B[59;49m"), []);
+    }
+
+    #[test]
+    fn open_modes() {
+        assert_eq!(
+            kinds("\x1B[?25l\x1B[?2026hX\x1B]8;;https://example.com\x07\x1B[1"),
+            [
+                IssueKind::CursorHidden,
+                IssueKind::SyncUpdate,
+                IssueKind::UnclosedHyperlink,
+                IssueKind::UnterminatedSequence,
+            ]
+        );
+    }
+}
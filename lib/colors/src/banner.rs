@@ -0,0 +1,123 @@
+//! Large text banners.
+//!
+//! Renders text with an embedded font where every glyph "pixel" is drawn as a
+//! colored full block character. Falls back to plain `#` characters without
+//! escape sequences if colors are disabled or the locale is not UTF-8, and to
+//! the text itself for screen readers.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let style = colors::Style::new().fg(colors::Color::Cyan);
+//! print!("{}", colors::banner(&ansi, "Skrot", colors::Font::Block, &style));
+//! ```
+
+use crate::caps::screen_reader_env;
+use crate::{Charset, Codes, Style};
+
+/// Number of rows in every glyph.
+const HEIGHT: usize = 5;
+
+#[rustfmt::skip]
+const BLOCK: &[(char, [&str; HEIGHT])] = &[
+    ('A', [" ### ", "#   #", "#####", "#   #", "#   #"]),
+    ('B', ["#### ", "#   #", "#### ", "#   #", "#### "]),
+    ('C', [" ####", "#    ", "#    ", "#    ", " ####"]),
+    ('D', ["#### ", "#   #", "#   #", "#   #", "#### "]),
+    ('E', ["#####", "#    ", "#### ", "#    ", "#####"]),
+    ('F', ["#####", "#    ", "#### ", "#    ", "#    "]),
+    ('G', [" ####", "#    ", "#  ##", "#   #", " ####"]),
+    ('H', ["#   #", "#   #", "#####", "#   #", "#   #"]),
+    ('I', ["###", " # ", " # ", " # ", "###"]),
+    ('J', ["    #", "    #", "    #", "#   #", " ### "]),
+    ('K', ["#   #", "#  # ", "###  ", "#  # ", "#   #"]),
+    ('L', ["#    ", "#    ", "#    ", "#    ", "#####"]),
+    ('M', ["#   #", "## ##", "# # #", "#   #", "#   #"]),
+    ('N', ["#   #", "##  #", "# # #", "#  ##", "#   #"]),
+    ('O', [" ### ", "#   #", "#   #", "#   #", " ### "]),
+    ('P', ["#### ", "#   #", "#### ", "#    ", "#    "]),
+    ('Q', [" ### ", "#   #", "# # #", "#  # ", " ## #"]),
+    ('R', ["#### ", "#   #", "#### ", "#  # ", "#   #"]),
+    ('S', [" ####", "#    ", " ### ", "    #", "#### "]),
+    ('T', ["#####", "  #  ", "  #  ", "  #  ", "  #  "]),
+    ('U', ["#   #", "#   #", "#   #", "#   #", " ### "]),
+    ('V', ["#   #", "#   #", "#   #", " # # ", "  #  "]),
+    ('W', ["#   #", "#   #", "# # #", "## ##", "#   #"]),
+    ('X', ["#   #", " # # ", "  #  ", " # # ", "#   #"]),
+    ('Y', ["#   #", " # # ", "  #  ", "  #  ", "  #  "]),
+    ('Z', ["#####", "   # ", "  #  ", " #   ", "#####"]),
+    ('0', [" ### ", "#  ##", "# # #", "##  #", " ### "]),
+    ('1', [" # ", "## ", " # ", " # ", "###"]),
+    ('2', ["#### ", "    #", " ### ", "#    ", "#####"]),
+    ('3', ["#### ", "    #", " ### ", "    #", "#### "]),
+    ('4', ["#   #", "#   #", "#####", "    #", "    #"]),
+    ('5', ["#####", "#    ", "#### ", "    #", "#### "]),
+    ('6', [" ### ", "#    ", "#### ", "#   #", " ### "]),
+    ('7', ["#####", "    #", "   # ", "  #  ", "  #  "]),
+    ('8', [" ### ", "#   #", " ### ", "#   #", " ### "]),
+    ('9', [" ### ", "#   #", " ####", "    #", " ### "]),
+    (' ', ["   ", "   ", "   ", "   ", "   "]),
+    ('!', ["#", "#", "#", " ", "#"]),
+    ('.', [" ", " ", " ", " ", "#"]),
+    (':', [" ", "#", " ", "#", " "]),
+    ('-', ["   ", "   ", "###", "   ", "   "]),
+    ('_', ["     ", "     ", "     ", "     ", "#####"]),
+    ('/', ["    #", "   # ", "  #  ", " #   ", "#    "]),
+    ('?', ["### ", "   #", " ## ", "    ", " #  "]),
+];
+
+/// Embedded banner fonts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Font {
+    /// Five rows high font with uppercase letters, digits and basic
+    /// punctuation. Lowercase letters are rendered as uppercase.
+    Block,
+}
+
+impl Font {
+    /// Return glyph rows for character, using `?` for unknown characters.
+    fn glyph(self, c: char) -> &'static [&'static str; HEIGHT] {
+        let table = match self {
+            Font::Block => BLOCK,
+        };
+        let c = c.to_ascii_uppercase();
+        let find = |c| table.iter().find(|(k, _)| *k == c).map(|(_, g)| g);
+        find(c).or_else(|| find('?')).unwrap()
+    }
+}
+
+/// Return text rendered as large banner, one line per glyph row.
+///
+/// Glyph pixels are drawn as full blocks painted with `style`. If colors are
+/// disabled in `codes` or the locale is not UTF-8 plain `#` is used instead.
+/// If screen reader output is requested text is returned as single line.
+pub fn banner(codes: &Codes, text: &str, font: Font, style: &Style) -> String {
+    if screen_reader_env() {
+        return format!("{}\n", text);
+    }
+
+    let fancy = codes.is_enabled() && Charset::detect() == Charset::Unicode;
+    let pixel = if fancy { "█" } else { "#" };
+
+    let glyphs: Vec<_> = text.chars().map(|c| font.glyph(c)).collect();
+    let mut output = String::new();
+    for row in 0..HEIGHT {
+        let mut line = String::new();
+        for (i, glyph) in glyphs.iter().enumerate() {
+            if i > 0 {
+                line.push(' ');
+            }
+            line.push_str(&glyph[row].replace('#', pixel));
+        }
+        let line = line.trim_end();
+
+        if fancy {
+            output.push_str(&style.paint(codes, line));
+        } else {
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+    output
+}
@@ -0,0 +1,280 @@
+//! Comparison of benchmark results between runs.
+//!
+//! Metrics of current run are listed with their baseline values, relative
+//! change as [`delta_badge`](crate::delta_badge) and a bar scaled to the
+//! change, drawn with glyphs of the given capabilities. Changes within noise
+//! threshold are shown as unchanged, others as regressions or improvements in
+//! colors of the given theme. By default lower values are better, as for
+//! durations.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let baseline = [("parse", 12.1), ("render", 3.4)];
+//! let current = [("parse", 10.8), ("render", 3.9)];
+//! let (caps, theme) = (colors::Capabilities::detect(), colors::Theme::detect());
+//! print!("{}", colors::bench::compare(&ansi, &caps, &theme, &baseline, &current));
+//! ```
+
+use crate::delta::direction;
+use crate::width::display_width;
+use crate::{fmt_float, Capabilities, Codes, Theme};
+
+/// Width of change bars in columns.
+const BAR_WIDTH: usize = 10;
+
+/// Thresholds deciding how changes are shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thresholds {
+    /// Changes up to this many percent are noise and shown as unchanged.
+    pub noise: f64,
+    /// Change in percent drawn as full bar, larger changes are clamped.
+    pub full_bar: f64,
+    /// Higher values are better, e.g. throughput instead of durations.
+    pub higher_is_better: bool,
+}
+
+impl Thresholds {
+    /// Return default thresholds: 2% noise, full bar at 50% change and lower
+    /// values being better.
+    pub const fn new() -> Thresholds {
+        Thresholds {
+            noise: 2.0,
+            full_bar: 50.0,
+            higher_is_better: false,
+        }
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Thresholds {
+        Thresholds::new()
+    }
+}
+
+/// Return bar of `fraction` of [`BAR_WIDTH`] using partial blocks if
+/// available, empty if fraction rounds to nothing.
+fn bar(caps: &Capabilities, fraction: f64) -> String {
+    let glyphs = caps.glyphs();
+    let steps = glyphs.block_partial.len() + 1;
+    let total = (fraction.clamp(0.0, 1.0) * (BAR_WIDTH * steps) as f64).round() as usize;
+    let mut bar = glyphs.block_full.repeat(total / steps);
+    if let Some(index) = (total % steps).checked_sub(1) {
+        bar.push_str(glyphs.block_partial[index]);
+    }
+    bar
+}
+
+/// Return text padded with spaces to visible width, on the left if
+/// `right` aligned.
+fn align(text: &str, width: usize, right: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(display_width(text)));
+    if right {
+        format!("{}{}", padding, text)
+    } else {
+        format!("{}{}", text, padding)
+    }
+}
+
+/// Return comparison table with default thresholds, see [`compare_with`].
+pub fn compare(
+    codes: &Codes,
+    caps: &Capabilities,
+    theme: &Theme,
+    baseline: &[(&str, f64)],
+    current: &[(&str, f64)],
+) -> String {
+    compare_with(codes, caps, theme, baseline, current, &Thresholds::new())
+}
+
+/// Return table comparing named metrics of current run to baseline, one
+/// line per metric followed by metrics only found in baseline.
+pub fn compare_with(
+    codes: &Codes,
+    caps: &Capabilities,
+    theme: &Theme,
+    baseline: &[(&str, f64)],
+    current: &[(&str, f64)],
+    thresholds: &Thresholds,
+) -> String {
+    let previous = |name: &str| baseline.iter().find(|(n, _)| *n == name).map(|&(_, v)| v);
+
+    let mut rows = vec![[
+        "metric".to_string(),
+        "baseline".to_string(),
+        "current".to_string(),
+        "change".to_string(),
+        String::new(),
+    ]];
+    for &(name, value) in current {
+        let row = match previous(name) {
+            Some(base) if base != 0.0 => {
+                let change = (value - base) / base.abs() * 100.0;
+                let shown = if change.abs() <= thresholds.noise {
+                    0.0
+                } else {
+                    change
+                };
+                let (arrow, style) =
+                    direction(caps.glyphs(), theme, shown, thresholds.higher_is_better);
+                let sign = if change > 0.0 { "+" } else { "" };
+                let text = format!("{} {}{}%", arrow, sign, fmt_float(change, 1));
+                let bar = bar(caps, shown.abs() / thresholds.full_bar);
+                [
+                    name.to_string(),
+                    fmt_float(base, 2),
+                    fmt_float(value, 2),
+                    style.paint(codes, &text),
+                    if bar.is_empty() {
+                        bar
+                    } else {
+                        style.paint(codes, &bar)
+                    },
+                ]
+            }
+            base => [
+                name.to_string(),
+                base.map_or_else(String::new, |base| fmt_float(base, 2)),
+                fmt_float(value, 2),
+                theme
+                    .muted
+                    .paint(codes, if base.is_some() { "n/a" } else { "new" }),
+                String::new(),
+            ],
+        };
+        rows.push(row);
+    }
+    for &(name, base) in baseline {
+        if !current.iter().any(|(n, _)| *n == name) {
+            rows.push([
+                name.to_string(),
+                fmt_float(base, 2),
+                String::new(),
+                theme.muted.paint(codes, "removed"),
+                String::new(),
+            ]);
+        }
+    }
+
+    let mut widths = [0; 5];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+    let mut output = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| align(cell, width, column == 1 || column == 2))
+            .collect();
+        let line = cells.join("  ");
+        let line = line.trim_end();
+        if index == 0 {
+            output.push_str(&theme.header.paint(codes, line));
+        } else {
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::degradation::Variant;
+    use crate::{init_off, init_on_with, Charset, ColorSupport, Style};
+
+    /// Return capabilities of color terminal with charset.
+    fn caps(charset: Charset) -> Capabilities {
+        Variant {
+            color: ColorSupport::Ansi16,
+            charset,
+            tty: true,
+        }
+        .capabilities()
+    }
+
+    #[test]
+    fn bar_fractions() {
+        for charset in [Charset::Unicode, Charset::Ascii] {
+            let caps = caps(charset);
+            let full = caps.glyphs().block_full;
+            assert_eq!(bar(&caps, 0.0), "");
+            assert_eq!(bar(&caps, 0.5), full.repeat(BAR_WIDTH / 2));
+            assert_eq!(bar(&caps, 1.0), full.repeat(BAR_WIDTH));
+            assert_eq!(bar(&caps, 3.0), full.repeat(BAR_WIDTH));
+            assert_eq!(display_width(&bar(&caps, 0.36)), 4);
+        }
+    }
+
+    #[test]
+    fn plain_table() {
+        let output = compare(
+            &init_off(),
+            &caps(Charset::Ascii),
+            &Theme::new(),
+            &[("a", 1.0)],
+            &[("a", 1.0)],
+        );
+        assert_eq!(
+            output,
+            format!(
+                "metric  baseline  current  change\na           {}     {}  = {}%\n",
+                fmt_float(1.0, 2),
+                fmt_float(1.0, 2),
+                fmt_float(0.0, 1)
+            )
+        );
+    }
+
+    #[test]
+    fn classify_changes() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let caps = caps(Charset::Unicode);
+        let glyphs = caps.glyphs();
+        let theme = Theme::new();
+        let baseline = [
+            ("parse", 10.0),
+            ("render", 10.0),
+            ("idle", 10.0),
+            ("zero", 0.0),
+            ("gone", 1.0),
+        ];
+        let current = [
+            ("parse", 15.0),
+            ("render", 5.0),
+            ("idle", 10.1),
+            ("zero", 1.0),
+            ("fresh", 2.0),
+        ];
+        let badge = |style: Style, arrow: &str, change: f64, sign: &str| {
+            style.paint(
+                &codes,
+                &format!("{} {}{}%", arrow, sign, fmt_float(change, 1)),
+            )
+        };
+        let output = compare(&codes, &caps, &theme, &baseline, &current);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[1].contains(&badge(theme.error, glyphs.up, 50.0, "+")));
+        assert!(lines[2].contains(&badge(theme.success, glyphs.down, -50.0, "")));
+        assert!(lines[3].contains(&badge(theme.muted, glyphs.flat, 1.0, "+")));
+        assert!(lines[4].contains(&theme.muted.paint(&codes, "n/a")));
+        assert!(lines[5].contains(&theme.muted.paint(&codes, "new")));
+        assert!(lines[6].starts_with("gone"));
+        assert!(lines[6].contains(&theme.muted.paint(&codes, "removed")));
+
+        let thresholds = Thresholds {
+            higher_is_better: true,
+            ..Thresholds::new()
+        };
+        let output = compare_with(&codes, &caps, &theme, &baseline, &current, &thresholds);
+        assert!(output.contains(&badge(theme.success, glyphs.up, 50.0, "+")));
+        assert!(output.contains(&badge(theme.error, glyphs.down, -50.0, "")));
+    }
+}
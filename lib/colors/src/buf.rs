@@ -0,0 +1,106 @@
+//! Reusable render buffers.
+//!
+//! Renderers called once per frame write into a [`RenderBuf`] instead of
+//! returning new strings, so repeated rendering reuses the same allocation.
+//! Callers without a buffer of their own get a thread local one through
+//! [`with_buf`].
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::{Codes, Style};
+
+thread_local! {
+    static BUF: RefCell<RenderBuf> = RefCell::new(RenderBuf::new());
+}
+
+/// Run function with cleared thread local buffer, falling back to new buffer
+/// if already in use further up the stack.
+pub(crate) fn with_buf<T, F: FnOnce(&mut RenderBuf) -> T>(f: F) -> T {
+    BUF.with(|cell| match cell.try_borrow_mut() {
+        Ok(mut buf) => {
+            buf.clear();
+            f(&mut buf)
+        }
+        Err(_) => f(&mut RenderBuf::new()),
+    })
+}
+
+/// Growable text buffer keeping its allocation between renders.
+#[derive(Clone, Debug, Default)]
+pub struct RenderBuf {
+    buf: String,
+}
+
+impl RenderBuf {
+    /// Return empty buffer.
+    pub fn new() -> RenderBuf {
+        RenderBuf { buf: String::new() }
+    }
+
+    /// Return empty buffer with room for `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> RenderBuf {
+        RenderBuf {
+            buf: String::with_capacity(capacity),
+        }
+    }
+
+    /// Remove content but keep allocation.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+
+    /// Return content.
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    /// Append text.
+    pub fn push_str(&mut self, text: &str) {
+        self.buf.push_str(text);
+    }
+
+    /// Append character.
+    pub fn push(&mut self, c: char) {
+        self.buf.push(c);
+    }
+
+    /// Append combined SGR sequence of style, nothing is appended if colors
+    /// are disabled in `codes` or style is plain.
+    pub fn push_style(&mut self, codes: &Codes, style: &Style) {
+        if codes.is_enabled() && !style.is_plain() {
+            self.buf.push_str(&codes.wrap(style.to_sgr().as_str()));
+        }
+    }
+
+    /// Append text wrapped in style and reset, see [`Style::paint`].
+    pub fn push_painted(&mut self, codes: &Codes, style: &Style, text: &str) {
+        self.push_style(codes, style);
+        self.buf.push_str(text);
+        if codes.is_enabled() && !style.is_plain() {
+            self.buf.push_str(&codes.attr.reset);
+        }
+    }
+}
+
+impl Deref for RenderBuf {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.buf
+    }
+}
+
+impl fmt::Write for RenderBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.buf.push_str(s);
+        Ok(())
+    }
+}
+
+impl fmt::Display for RenderBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.buf)
+    }
+}
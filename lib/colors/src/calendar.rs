@@ -0,0 +1,187 @@
+//! Month calendars with styled days.
+//!
+//! Days are laid out in weeks starting on Monday, each day styled by a
+//! callback, e.g. background colors scaled to activity for heatmaps. Month
+//! title uses header style and weekday names muted style of the given theme.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let busy = colors::Style::new().bg(colors::Color::Green);
+//! let theme = colors::Theme::detect();
+//! print!("{}", colors::calendar(&ansi, &theme, 2024, 2, |day| busy.when(commits[day] > 0)));
+//! ```
+
+use crate::{Codes, Style, Theme};
+
+/// Month names in English.
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Weekday header, Monday first.
+const WEEKDAYS: &str = "Mo Tu We Th Fr Sa Su";
+
+/// Width of week row in columns.
+const WIDTH: usize = WEEKDAYS.len();
+
+/// Check if year is leap year in Gregorian calendar.
+fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Return number of days in month from 1 to 12.
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Return weekday of date, 0 for Monday, using Sakamoto's method.
+fn weekday(year: i32, month: u32, day: u32) -> u32 {
+    const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let year = if month < 3 { year - 1 } else { year };
+    let sunday_first = (year + year.div_euclid(4) - year.div_euclid(100)
+        + year.div_euclid(400)
+        + OFFSETS[month as usize - 1]
+        + day as i32)
+        .rem_euclid(7);
+    (sunday_first as u32 + 6) % 7
+}
+
+/// Return calendar of month from 1 to 12 with every day painted in style
+/// returned by `style` for day of month, or empty string if month is
+/// invalid. Title is centered above weekday header.
+pub fn calendar<F: FnMut(u32) -> Style>(
+    codes: &Codes,
+    theme: &Theme,
+    year: i32,
+    month: u32,
+    mut style: F,
+) -> String {
+    if !(1..=12).contains(&month) {
+        return String::new();
+    }
+    let title = format!("{} {}", MONTHS[month as usize - 1], year);
+    let indent = " ".repeat(WIDTH.saturating_sub(title.len()) / 2);
+
+    let mut output = format!("{}{}\n", indent, theme.header.paint(codes, &title));
+    output.push_str(&theme.muted.paint(codes, WEEKDAYS));
+    output.push('\n');
+
+    let first = weekday(year, month, 1);
+    let mut cells: Vec<String> = vec!["  ".to_string(); first as usize];
+    for day in 1..=days_in_month(year, month) {
+        cells.push(style(day).paint(codes, &format!("{:>2}", day)));
+        if cells.len() == 7 {
+            output.push_str(&cells.join(" "));
+            output.push('\n');
+            cells.clear();
+        }
+    }
+    if !cells.is_empty() {
+        output.push_str(&cells.join(" "));
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_off, init_on_with, Color, ColorSupport};
+
+    #[test]
+    fn known_weekdays() {
+        assert_eq!(weekday(1970, 1, 1), 3);
+        assert_eq!(weekday(2000, 2, 29), 1);
+        assert_eq!(weekday(2024, 1, 1), 0);
+        assert_eq!(weekday(2024, 12, 29), 6);
+    }
+
+    #[test]
+    fn leap_years() {
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2023, 2), 28);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2024, 4), 30);
+        assert_eq!(days_in_month(2024, 12), 31);
+    }
+
+    #[test]
+    fn month_grid() {
+        assert_eq!(
+            calendar(&init_off(), &Theme::new(), 2024, 9, |_| Style::new()),
+            concat!(
+                "   September 2024\n",
+                "Mo Tu We Th Fr Sa Su\n",
+                "                   1\n",
+                " 2  3  4  5  6  7  8\n",
+                " 9 10 11 12 13 14 15\n",
+                "16 17 18 19 20 21 22\n",
+                "23 24 25 26 27 28 29\n",
+                "30\n",
+            )
+        );
+    }
+
+    #[test]
+    fn style_each_day() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let busy = Style::new().bg(Color::Green);
+        let mut days = Vec::new();
+        let output = calendar(&codes, &Theme::new(), 2021, 2, |day| {
+            days.push(day);
+            busy.when(day == 14)
+        });
+        assert_eq!(days, (1..=28).collect::<Vec<_>>());
+        assert!(output.contains(&busy.paint(&codes, "14")));
+        assert!(output.contains(" 1  2  3  4  5  6  7\n"));
+        assert!(!output.contains(&busy.paint(&codes, "13")));
+    }
+
+    #[test]
+    fn invalid_month() {
+        assert_eq!(
+            calendar(&init_off(), &Theme::new(), 2024, 0, |_| Style::new()),
+            ""
+        );
+        assert_eq!(
+            calendar(&init_off(), &Theme::new(), 2024, 13, |_| Style::new()),
+            ""
+        );
+    }
+
+    #[test]
+    fn theme_styles() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let theme = Theme {
+            header: Style::new().fg(Color::Magenta),
+            muted: Style::new().fg(Color::Cyan),
+            ..Theme::new()
+        };
+        let output = calendar(&codes, &theme, 2024, 1, |_| Style::new());
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("    {}", theme.header.paint(&codes, "January 2024"))
+        );
+        assert_eq!(lines[1], theme.muted.paint(&codes, WEEKDAYS));
+    }
+}
@@ -0,0 +1,347 @@
+//! Terminal capabilities.
+//!
+//! Collects what the output can display, so widgets degrade consistently
+//! instead of each one doing its own detection.
+//!
+//! Setting `COLORS_SCREEN_READER` to a value other than `0` requests screen
+//! reader friendly output: animations and repaints are replaced by their
+//! final state, banners by plain text and decorative glyphs by words.
+
+use std::env;
+
+use crate::profile;
+use crate::terminfo;
+use crate::{config, is_tty_fd, utf8_env, ColorChoice, Profile, Stream};
+
+/// Number of colors output can display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSupport {
+    #[default]
+    NoColor,
+    /// 8 colors without bright variants.
+    Ansi8,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Return color support of terminal, assuming colors are enabled.
+    ///
+    /// `COLORTERM` set to `truecolor` or `24bit` means 24-bit RGB, otherwise
+    /// `depth` in user configuration is used if set. `TERM` set to `dumb`, or
+    /// not set outside of Windows, means no colors. `TERM` containing
+    /// `256color` means 256 colors, otherwise `colors` of terminfo entry is
+    /// used and 16 colors if there is none.
+    pub fn detect() -> ColorSupport {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+        if let Some(depth) = config().depth {
+            return depth;
+        }
+
+        let term = match env::var("TERM") {
+            Ok(term) if !term.is_empty() => term,
+            _ if cfg!(windows) => return ColorSupport::Ansi16,
+            _ => return ColorSupport::NoColor,
+        };
+        if term == "dumb" {
+            return ColorSupport::NoColor;
+        }
+        if term.contains("256color") {
+            return ColorSupport::Ansi256;
+        }
+        match terminfo::colors(&term) {
+            Some(n) if n >= 0x100_0000 => ColorSupport::TrueColor,
+            Some(n) if n >= 256 => ColorSupport::Ansi256,
+            Some(n) if n >= 16 => ColorSupport::Ansi16,
+            Some(n) if n >= 8 => ColorSupport::Ansi8,
+            Some(_) => ColorSupport::NoColor,
+            None => ColorSupport::Ansi16,
+        }
+    }
+}
+
+/// Program hosting terminal, for embedded terminals with known limitations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Host {
+    /// Regular terminal emulator.
+    Terminal,
+    /// Visual Studio Code integrated terminal.
+    VsCode,
+    /// JetBrains IDE terminal.
+    JetBrains,
+    /// Emacs shell buffer (comint), terminal emulators inside Emacs like
+    /// vterm count as [`Host::Terminal`].
+    Emacs,
+}
+
+impl Host {
+    /// Return host detected from `INSIDE_EMACS`, `TERM_PROGRAM` and
+    /// `TERMINAL_EMULATOR`.
+    pub fn detect() -> Host {
+        if let Ok(emacs) = env::var("INSIDE_EMACS") {
+            if !emacs.contains("term") {
+                return Host::Emacs;
+            }
+        }
+        if env::var("TERM_PROGRAM").is_ok_and(|v| v == "vscode") {
+            return Host::VsCode;
+        }
+        if env::var("TERMINAL_EMULATOR").is_ok_and(|v| v.starts_with("JetBrains")) {
+            return Host::JetBrains;
+        }
+        Host::Terminal
+    }
+
+    /// Check if host supports cursor movement and in place repaints.
+    pub fn supports_cursor(self) -> bool {
+        self != Host::Emacs
+    }
+
+    /// Check if host supports OSC 8 hyperlinks.
+    pub fn supports_hyperlinks(self) -> bool {
+        matches!(self, Host::Terminal | Host::VsCode)
+    }
+}
+
+/// Set of characters output can be trusted to display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Charset {
+    Unicode,
+    Ascii,
+}
+
+impl Charset {
+    /// Return [`Charset::Unicode`] if `charset` in user configuration, or
+    /// locale environment variables if not set, specify UTF-8 encoding and
+    /// output profile allows it, otherwise [`Charset::Ascii`].
+    pub fn detect() -> Charset {
+        let charset = config().charset.unwrap_or_else(|| {
+            if utf8_env() {
+                Charset::Unicode
+            } else {
+                Charset::Ascii
+            }
+        });
+        if profile::allows(Profile::unicode) {
+            charset
+        } else {
+            Charset::Ascii
+        }
+    }
+
+    /// Return glyph table for charset.
+    pub fn glyphs(self) -> &'static Glyphs {
+        match self {
+            Charset::Unicode => &UNICODE,
+            Charset::Ascii => &ASCII,
+        }
+    }
+}
+
+/// Glyphs used by widgets, with Unicode and ASCII variants.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Glyphs {
+    pub horizontal: &'static str,
+    pub vertical: &'static str,
+    pub top_left: &'static str,
+    pub top_right: &'static str,
+    pub bottom_left: &'static str,
+    pub bottom_right: &'static str,
+    pub tee_left: &'static str,
+    pub tee_right: &'static str,
+    pub cross: &'static str,
+
+    pub block_full: &'static str,
+    pub block_partial: &'static [&'static str],
+    pub block_empty: &'static str,
+    pub spinner: &'static [&'static str],
+
+    pub bullet: &'static str,
+    pub check: &'static str,
+    pub fail: &'static str,
+    pub arrow: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    pub flat: &'static str,
+    pub ellipsis: &'static str,
+    pub mask: &'static str,
+}
+
+#[rustfmt::skip]
+static UNICODE: Glyphs = Glyphs {
+    horizontal:   "─",
+    vertical:     "│",
+    top_left:     "┌",
+    top_right:    "┐",
+    bottom_left:  "└",
+    bottom_right: "┘",
+    tee_left:     "├",
+    tee_right:    "┤",
+    cross:        "┼",
+
+    block_full:    "█",
+    block_partial: &["▏", "▎", "▍", "▌", "▋", "▊", "▉"],
+    block_empty:   " ",
+    spinner:       &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+
+    bullet:   "•",
+    check:    "✓",
+    fail:     "✗",
+    arrow:    "→",
+    up:       "▲",
+    down:     "▼",
+    flat:     "▬",
+    ellipsis: "…",
+    mask:     "•",
+};
+
+#[rustfmt::skip]
+static ASCII: Glyphs = Glyphs {
+    horizontal:   "-",
+    vertical:     "|",
+    top_left:     "+",
+    top_right:    "+",
+    bottom_left:  "+",
+    bottom_right: "+",
+    tee_left:     "+",
+    tee_right:    "+",
+    cross:        "+",
+
+    block_full:    "#",
+    block_partial: &[],
+    block_empty:   "-",
+    spinner:       &["|", "/", "-", "\\"],
+
+    bullet:   "*",
+    check:    "+",
+    fail:     "x",
+    arrow:    "->",
+    up:       "^",
+    down:     "v",
+    flat:     "=",
+    ellipsis: "...",
+    mask:     "*",
+};
+
+#[rustfmt::skip]
+static TEXT: Glyphs = Glyphs {
+    horizontal:   "",
+    vertical:     "",
+    top_left:     "",
+    top_right:    "",
+    bottom_left:  "",
+    bottom_right: "",
+    tee_left:     "",
+    tee_right:    "",
+    cross:        "",
+
+    block_full:    "",
+    block_partial: &[],
+    block_empty:   "",
+    spinner:       &[""],
+
+    bullet:   "-",
+    check:    "ok",
+    fail:     "failed",
+    arrow:    "to",
+    up:       "up",
+    down:     "down",
+    flat:     "unchanged",
+    ellipsis: "...",
+    mask:     "*",
+};
+
+/// Kind of file output is written to, terminal, pipe or file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sink {
+    Terminal,
+    /// Pipe or socket, usually read by another program.
+    Pipe,
+    /// Regular file redirection.
+    File,
+    /// Other devices like `/dev/null`, or unknown.
+    Other,
+}
+
+impl Sink {
+    /// Return kind of standard output, see [`Sink::detect_fd`].
+    pub fn detect() -> Sink {
+        Sink::detect_fd(Stream::Stdout.fd())
+    }
+
+    /// Return kind of file descriptor using libc fstat(), or GetFileType() on
+    /// Windows.
+    pub fn detect_fd(fd: libc::c_int) -> Sink {
+        if is_tty_fd(fd) {
+            return Sink::Terminal;
+        }
+        #[cfg(windows)]
+        {
+            crate::windows::sink(fd)
+        }
+        #[cfg(not(windows))]
+        {
+            let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+            if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+                return Sink::Other;
+            }
+            match stat.st_mode & libc::S_IFMT {
+                libc::S_IFIFO | libc::S_IFSOCK => Sink::Pipe,
+                libc::S_IFREG => Sink::File,
+                _ => Sink::Other,
+            }
+        }
+    }
+
+    /// Check if progress output is useful, i.e. output is terminal or pipe
+    /// possibly consumed by wrapper program, but never file.
+    pub fn allows_progress(self) -> bool {
+        matches!(self, Sink::Terminal | Sink::Pipe)
+    }
+}
+
+/// Check if screen reader friendly output is requested by
+/// `COLORS_SCREEN_READER`.
+pub(crate) fn screen_reader_env() -> bool {
+    env::var("COLORS_SCREEN_READER").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Detected output capabilities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// Colors and other escape sequences can be used, same decision as
+    /// [`init_auto`](crate::init_auto).
+    pub color: bool,
+    pub charset: Charset,
+    /// Decorative output should be suppressed for screen readers.
+    pub screen_reader: bool,
+    pub host: Host,
+    pub sink: Sink,
+}
+
+impl Capabilities {
+    /// Detect capabilities of standard output.
+    pub fn detect() -> Capabilities {
+        Capabilities {
+            color: ColorChoice::detect().is_enabled() && profile::allows(Profile::colors),
+            charset: Charset::detect(),
+            screen_reader: screen_reader_env(),
+            host: Host::detect(),
+            sink: Sink::detect(),
+        }
+    }
+
+    /// Return glyph table for detected charset, or table of words without
+    /// decorative glyphs for screen readers.
+    pub fn glyphs(&self) -> &'static Glyphs {
+        if self.screen_reader {
+            &TEXT
+        } else {
+            self.charset.glyphs()
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! User level configuration shared by all programs using the crate.
+//!
+//! Read once from `$XDG_CONFIG_HOME/skrot/colors.toml`, falling back to
+//! `~/.config/skrot/colors.toml`. Environment variable `COLORS_CONFIG` selects
+//! another file, empty value disables configuration. Missing files are
+//! ignored, invalid lines are skipped and returned by [`config_errors`] for
+//! the program to report.
+//! Environment variables like `NO_COLOR`, `FORCE_COLOR`, `COLORTERM` and
+//! `COLORS_HIGH_CONTRAST` take precedence over configuration.
+//!
+//! Format, all keys optional:
+//!
+//! ```toml
+//! color = "auto"             # auto, always or never
+//! depth = "256"              # 8, 16, 256 or truecolor
+//! theme-variant = "default"  # default or high-contrast
+//! charset = "unicode"        # unicode or ascii
+//! animations = true
+//!
+//! [theme]                    # roles, see Theme::parse_config
+//! error = "bright_red bold"
+//! ```
+//!
+//! Only flat `key = value` lines with optionally quoted values and `[table]`
+//! headers of TOML are understood. Tables other than top level are read by
+//! their own parsers and skipped here.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::{Charset, ColorChoice, ColorMode, ColorSupport};
+
+/// Configuration loaded by [`config`] or set with [`set_config`].
+static CONFIG: Mutex<Option<Config>> = Mutex::new(None);
+
+/// Errors of invalid lines found when [`config`] loaded configuration.
+static ERRORS: Mutex<Vec<ParseConfigError>> = Mutex::new(Vec::new());
+
+/// Output preferences, `None` leaves the choice to detection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    /// [`ColorChoice::Forced`] for `always`, [`ColorChoice::Disabled`] for
+    /// `never`.
+    pub color: Option<ColorChoice>,
+    pub depth: Option<ColorSupport>,
+    /// High contrast theme requested.
+    pub high_contrast: Option<bool>,
+    pub charset: Option<Charset>,
+    pub animations: Option<bool>,
+}
+
+impl Config {
+    /// Parse configuration from text using flat `key = value` TOML subset,
+    /// failing on first invalid line.
+    pub fn parse(text: &str) -> Result<Config, ParseConfigError> {
+        let (config, mut errors) = Config::parse_lossy(text);
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors.remove(0))
+        }
+    }
+
+    /// Parse configuration from text like [`Config::parse`], skipping invalid
+    /// lines. Return configuration from valid lines and errors of the others.
+    pub fn parse_lossy(text: &str) -> (Config, Vec<ParseConfigError>) {
+        let mut config = Config::default();
+        let mut errors = Vec::new();
+        for entry in entries(text, "") {
+            if let Err(e) = entry.and_then(|(line, key, value)| config.set(line, key, value)) {
+                errors.push(e);
+            }
+        }
+        (config, errors)
+    }
+
+    /// Set option from entry on line of config file.
+    fn set(&mut self, line: usize, key: &str, value: &str) -> Result<(), ParseConfigError> {
+        let error = |message: &str| ParseConfigError {
+            line,
+            message: message.to_string(),
+        };
+        let invalid = || error(&format!("invalid value '{}' for {}", value, key));
+        match key {
+            "color" => {
+                self.color = match value.parse().map_err(|_| invalid())? {
+                    ColorMode::Auto => None,
+                    ColorMode::Always => Some(ColorChoice::Forced),
+                    ColorMode::Never => Some(ColorChoice::Disabled),
+                }
+            }
+            "depth" => {
+                self.depth = Some(match value {
+                    "8" => ColorSupport::Ansi8,
+                    "16" => ColorSupport::Ansi16,
+                    "256" => ColorSupport::Ansi256,
+                    "truecolor" | "24bit" => ColorSupport::TrueColor,
+                    _ => return Err(invalid()),
+                })
+            }
+            "theme-variant" => {
+                self.high_contrast = Some(match value {
+                    "default" => false,
+                    "high-contrast" => true,
+                    _ => return Err(invalid()),
+                })
+            }
+            "charset" => {
+                self.charset = Some(match value {
+                    "unicode" => Charset::Unicode,
+                    "ascii" => Charset::Ascii,
+                    _ => return Err(invalid()),
+                })
+            }
+            "animations" => {
+                self.animations = Some(match value {
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err(invalid()),
+                })
+            }
+            key => return Err(error(&format!("unknown key '{}'", key))),
+        }
+        Ok(())
+    }
+
+    /// Return configuration read from user config file, or default
+    /// configuration if file is missing, and errors of skipped invalid lines,
+    /// see [`Config::parse_lossy`].
+    pub fn load() -> (Config, Vec<ParseConfigError>) {
+        match Config::path().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(text) => Config::parse_lossy(&text),
+            None => (Config::default(), Vec::new()),
+        }
+    }
+
+    /// Return path of user config file, or `None` if disabled or home
+    /// directory is unknown.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("COLORS_CONFIG") {
+            return if path.is_empty() {
+                None
+            } else {
+                Some(PathBuf::from(path))
+            };
+        }
+        let base = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("skrot").join("colors.toml"))
+    }
+}
+
+/// Error returned when parsing invalid configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseConfigError {
+    /// Line number starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseConfigError {}
+
+/// Return line numbers starting from 1, keys and unquoted values of flat
+/// `key = value` lines in table, empty name for top level, or errors of lines
+/// without `=`, skipping blank lines, comments and other tables.
+pub(crate) fn entries<'a>(
+    text: &'a str,
+    table: &str,
+) -> Vec<Result<(usize, &'a str, &'a str), ParseConfigError>> {
+    let mut entries = Vec::new();
+    let mut current = "";
+    for (index, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name.trim();
+            continue;
+        }
+        if line.is_empty() || current != table {
+            continue;
+        }
+        entries.push(match line.split_once('=') {
+            Some((key, value)) => Ok((index + 1, key.trim(), unquote(value.trim()))),
+            None => Err(ParseConfigError {
+                line: index + 1,
+                message: "expected key = value".to_string(),
+            }),
+        });
+    }
+    entries
+}
+
+/// Return text before `#` comment outside of quoted string.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => (),
+        }
+    }
+    line
+}
+
+/// Return value without surrounding double quotes.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Replace configuration for the rest of the program, e.g. from settings of
+/// the program itself.
+pub fn set_config(config: Config) {
+    *CONFIG.lock().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+/// Return configuration set with [`set_config`], or load it with
+/// [`Config::load`] on first use.
+pub fn config() -> Config {
+    *CONFIG
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(|| {
+            let (config, errors) = Config::load();
+            *ERRORS.lock().unwrap_or_else(|e| e.into_inner()) = errors;
+            config
+        })
+}
+
+/// Return errors of invalid lines skipped when [`config`] loaded user config
+/// file at [`Config::path`], e.g. to report them once at startup.
+pub fn config_errors() -> Vec<ParseConfigError> {
+    config();
+    ERRORS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_all_keys() {
+        let text = "# comment\n\ncolor = \"always\"\ndepth = 256 # trailing\ntheme-variant = \"high-contrast\"\ncharset = ascii\nanimations = false\n";
+        assert_eq!(
+            Config::parse(text),
+            Ok(Config {
+                color: Some(ColorChoice::Forced),
+                depth: Some(ColorSupport::Ansi256),
+                high_contrast: Some(true),
+                charset: Some(Charset::Ascii),
+                animations: Some(false),
+            })
+        );
+        assert_eq!(Config::parse("color = auto"), Ok(Config::default()));
+    }
+
+    #[test]
+    fn parse_reports_first_error() {
+        let error = Config::parse("depth = 16\ncolor = sometimes\nfoo = 1").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            error.to_string(),
+            "line 2: invalid value 'sometimes' for color"
+        );
+    }
+
+    #[test]
+    fn parse_lossy_keeps_valid_lines() {
+        let text = "depth = 16\nbogus line\nfoo = 1\ncharset = \"unicode # not comment\"\nanimations = true";
+        let (config, errors) = Config::parse_lossy(text);
+        assert_eq!(
+            config,
+            Config {
+                depth: Some(ColorSupport::Ansi16),
+                animations: Some(true),
+                ..Config::default()
+            }
+        );
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "line 2: expected key = value",
+                "line 3: unknown key 'foo'",
+                "line 4: invalid value 'unicode # not comment' for charset",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_skips_other_tables() {
+        let text = "depth = 8\n[theme]\nerror = \"red bold\"\n[ other ]\ncolor = never\n";
+        assert_eq!(
+            Config::parse(text),
+            Ok(Config {
+                depth: Some(ColorSupport::Ansi8),
+                ..Config::default()
+            })
+        );
+        let theme: Vec<_> = entries(text, "theme")
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(theme, [(3, "error", "red bold")]);
+    }
+}
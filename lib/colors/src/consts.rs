@@ -0,0 +1,288 @@
+//! Allocation free codes with `&'static str` values.
+//!
+//! Mirrors [`Codes`](crate::Codes) with the same field names, so switching is
+//! mostly a matter of replacing the constructor. Values are plain sequences
+//! known at compile time, i.e. there is no detection of color support and no
+//! wrapping for terminal multiplexers.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! static ANSI: colors::consts::Codes = colors::consts::ON;
+//!
+//! let ansi = colors::consts::auto();
+//! println!("{}Hello, 世界{}", ansi.fg.red, ansi.attr.reset);
+//! ```
+
+use crate::{auto_enabled, Stream};
+
+/// Codes with all values set.
+pub const ON: Codes = on();
+
+/// Codes with all values empty.
+pub const OFF: Codes = off();
+
+/// Terminal style attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attributes {
+    pub blink: &'static str,
+    pub bold: &'static str,
+    pub dim: &'static str,
+    pub double_underline: &'static str,
+    pub hidden: &'static str,
+    pub italic: &'static str,
+    pub overline: &'static str,
+    pub reset: &'static str,
+    pub reverse: &'static str,
+    pub strikethrough: &'static str,
+    pub underline: &'static str,
+
+    pub no_blink: &'static str,
+    pub no_bold: &'static str,
+    pub no_dim: &'static str,
+    pub no_hidden: &'static str,
+    pub no_italic: &'static str,
+    pub no_overline: &'static str,
+    pub no_reverse: &'static str,
+    pub no_strikethrough: &'static str,
+    pub no_underline: &'static str,
+}
+
+/// Terminal background & foreground colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Colors {
+    pub black: &'static str,
+    pub blue: &'static str,
+    pub cyan: &'static str,
+    pub green: &'static str,
+    pub magenta: &'static str,
+    pub red: &'static str,
+    pub white: &'static str,
+    pub yellow: &'static str,
+
+    pub bright_black: &'static str,
+    pub bright_blue: &'static str,
+    pub bright_cyan: &'static str,
+    pub bright_green: &'static str,
+    pub bright_magenta: &'static str,
+    pub bright_red: &'static str,
+    pub bright_white: &'static str,
+    pub bright_yellow: &'static str,
+
+    pub reset: &'static str,
+}
+
+/// Data structure containing all attributes and colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Codes {
+    pub attr: Attributes,
+    pub bg: Colors,
+    pub fg: Colors,
+}
+
+/// Return struct of owned strings with same field values.
+macro_rules! to_strings {
+    ($value:expr, $ty:ident { $($field:ident),* $(,)? }) => {
+        crate::$ty { $($field: $value.$field.to_string()),* }
+    };
+}
+
+impl Attributes {
+    /// Return values as [`Attributes`](crate::Attributes).
+    pub(crate) fn to_strings(self) -> crate::Attributes {
+        to_strings!(
+            self,
+            Attributes {
+                blink,
+                bold,
+                dim,
+                double_underline,
+                hidden,
+                italic,
+                overline,
+                reset,
+                reverse,
+                strikethrough,
+                underline,
+                no_blink,
+                no_bold,
+                no_dim,
+                no_hidden,
+                no_italic,
+                no_overline,
+                no_reverse,
+                no_strikethrough,
+                no_underline,
+            }
+        )
+    }
+}
+
+impl Colors {
+    /// Return values as [`Colors`](crate::Colors).
+    pub(crate) fn to_strings(self) -> crate::Colors {
+        to_strings!(
+            self,
+            Colors {
+                black,
+                blue,
+                cyan,
+                green,
+                magenta,
+                red,
+                white,
+                yellow,
+                bright_black,
+                bright_blue,
+                bright_cyan,
+                bright_green,
+                bright_magenta,
+                bright_red,
+                bright_white,
+                bright_yellow,
+                reset,
+            }
+        )
+    }
+}
+
+impl Codes {
+    /// Check if attribute and color values are set, i.e. data structure is
+    /// [`ON`].
+    pub const fn is_enabled(&self) -> bool {
+        !self.attr.reset.is_empty()
+    }
+}
+
+/// Return [`ON`] or [`OFF`] for standard output, detected like
+/// [`init_auto`](crate::init_auto).
+pub fn auto() -> &'static Codes {
+    if auto_enabled(Stream::Stdout.fd()) {
+        &ON
+    } else {
+        &OFF
+    }
+}
+
+/// Return data structure with preset attribute and color values.
+pub const fn on() -> Codes {
+    Codes {
+        #[rustfmt::skip]
+        attr: Attributes {
+            reset:            "\x1B[0m",
+            bold:             "\x1B[1m",
+            dim:              "\x1B[2m",
+            italic:           "\x1B[3m",
+            underline:        "\x1B[4m",
+            blink:            "\x1B[5m",
+            reverse:          "\x1B[7m",
+            hidden:           "\x1B[8m",
+            strikethrough:    "\x1B[9m",
+            double_underline: "\x1B[21m",
+            overline:         "\x1B[53m",
+
+            no_bold:          "\x1B[22m",
+            no_dim:           "\x1B[22m",
+            no_italic:        "\x1B[23m",
+            no_underline:     "\x1B[24m",
+            no_blink:         "\x1B[25m",
+            no_reverse:       "\x1B[27m",
+            no_hidden:        "\x1B[28m",
+            no_strikethrough: "\x1B[29m",
+            no_overline:      "\x1B[55m",
+        },
+        #[rustfmt::skip]
+        bg: Colors {
+            black:   "\x1B[40m",
+            red:     "\x1B[41m",
+            green:   "\x1B[42m",
+            yellow:  "\x1B[43m",
+            blue:    "\x1B[44m",
+            magenta: "\x1B[45m",
+            cyan:    "\x1B[46m",
+            white:   "\x1B[47m",
+
+            bright_black:   "\x1B[100m",
+            bright_red:     "\x1B[101m",
+            bright_green:   "\x1B[102m",
+            bright_yellow:  "\x1B[103m",
+            bright_blue:    "\x1B[104m",
+            bright_magenta: "\x1B[105m",
+            bright_cyan:    "\x1B[106m",
+            bright_white:   "\x1B[107m",
+
+            reset: "\x1B[49m",
+        },
+        #[rustfmt::skip]
+        fg: Colors {
+            black:   "\x1B[30m",
+            red:     "\x1B[31m",
+            green:   "\x1B[32m",
+            yellow:  "\x1B[33m",
+            blue:    "\x1B[34m",
+            magenta: "\x1B[35m",
+            cyan:    "\x1B[36m",
+            white:   "\x1B[37m",
+
+            bright_black:   "\x1B[90m",
+            bright_red:     "\x1B[91m",
+            bright_green:   "\x1B[92m",
+            bright_yellow:  "\x1B[93m",
+            bright_blue:    "\x1B[94m",
+            bright_magenta: "\x1B[95m",
+            bright_cyan:    "\x1B[96m",
+            bright_white:   "\x1B[97m",
+
+            reset: "\x1B[39m",
+        },
+    }
+}
+
+/// Return data structure with empty attribute and color values.
+pub const fn off() -> Codes {
+    const EMPTY: Colors = Colors {
+        black: "",
+        blue: "",
+        cyan: "",
+        green: "",
+        magenta: "",
+        red: "",
+        white: "",
+        yellow: "",
+        bright_black: "",
+        bright_blue: "",
+        bright_cyan: "",
+        bright_green: "",
+        bright_magenta: "",
+        bright_red: "",
+        bright_white: "",
+        bright_yellow: "",
+        reset: "",
+    };
+    Codes {
+        attr: Attributes {
+            blink: "",
+            bold: "",
+            dim: "",
+            double_underline: "",
+            hidden: "",
+            italic: "",
+            overline: "",
+            reset: "",
+            reverse: "",
+            strikethrough: "",
+            underline: "",
+            no_blink: "",
+            no_bold: "",
+            no_dim: "",
+            no_hidden: "",
+            no_italic: "",
+            no_overline: "",
+            no_reverse: "",
+            no_strikethrough: "",
+            no_underline: "",
+        },
+        bg: EMPTY,
+        fg: EMPTY,
+    }
+}
@@ -0,0 +1,38 @@
+//! Readable foreground colors for arbitrary backgrounds.
+//!
+//! Black or white foreground is picked by relative luminance of background,
+//! whichever gives higher WCAG contrast, for badges and labels on colors that
+//! are not known in advance.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let (r, g, b) = label_color;
+//! let fg = colors::contrast_fg(label_color);
+//! println!("{}{} bug {}", ansi.bg_rgb(r, g, b), ansi.fg.get(fg), ansi.attr.reset);
+//! ```
+
+use crate::convert::{index_to_rgb, luminance};
+use crate::Color;
+
+/// Luminance above which black text is more readable than white text, where
+/// contrast against both is equal.
+const LUMINANCE_THRESHOLD: f64 = 0.179;
+
+/// Return [`Color::Black`] or [`Color::BrightWhite`], whichever is more
+/// readable on RGB background.
+pub fn contrast_fg(bg: (u8, u8, u8)) -> Color {
+    let (r, g, b) = bg;
+    if luminance(r, g, b) > LUMINANCE_THRESHOLD {
+        Color::Black
+    } else {
+        Color::BrightWhite
+    }
+}
+
+/// Return readable foreground on 256 color palette background, see
+/// [`contrast_fg`]. ANSI 16 colors use xterm default values.
+pub fn contrast_fg_256(bg: u8) -> Color {
+    contrast_fg(index_to_rgb(bg))
+}
@@ -0,0 +1,132 @@
+//! Color conversion between RGB and palettes.
+//!
+//! Colors can be defined once in 24-bit RGB and quantized to the nearest
+//! 256 color palette index or ANSI 16 color, using xterm default palette
+//! values. [`Codes::fg_rgb`](crate::Codes::fg_rgb) and friends do this
+//! automatically for the detected color support.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! use colors::convert;
+//!
+//! let index = convert::rgb_to_256(255, 135, 0);
+//! let color = convert::rgb_to_16(255, 135, 0);
+//! assert_eq!(convert::index_to_rgb(index), (255, 135, 0));
+//! ```
+
+use crate::Color;
+
+/// Approximate RGB values of 16 color palette, in [`Color`] order.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+/// Levels of 6x6x6 color cube in 256 color palette.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Return squared distance between colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).unsigned_abs().pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Return index of nearest color cube level.
+fn cube_level(value: u8) -> usize {
+    (0..CUBE.len())
+        .min_by_key(|&i| (i32::from(CUBE[i]) - i32::from(value)).unsigned_abs())
+        .unwrap_or(0)
+}
+
+/// Return RGB value of 256 color palette index.
+pub fn index_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[usize::from(index)].1,
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE[usize::from(i / 36)],
+                CUBE[usize::from(i / 6 % 6)],
+                CUBE[usize::from(i % 6)],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Return nearest 256 color palette index of RGB color, from color cube or
+/// grayscale ramp.
+pub fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    let (ri, gi, bi) = (cube_level(r), cube_level(g), cube_level(b));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let gray = (232 + (average.saturating_sub(3) / 10).min(23)) as u8;
+
+    let target = (r, g, b);
+    if distance(index_to_rgb(gray), target) < distance(index_to_rgb(cube), target) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Return nearest 16 color palette entry of 256 color palette index.
+pub fn index_to_16(index: u8) -> Color {
+    match index {
+        0..=15 => ANSI16[usize::from(index)].0,
+        _ => {
+            let (r, g, b) = index_to_rgb(index);
+            rgb_to_16(r, g, b)
+        }
+    }
+}
+
+/// Return nearest 16 color palette entry of RGB color.
+pub fn rgb_to_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::White)
+}
+
+/// Return RGB color mixed from `a` and `b`, `amount` of 0.0 is `a` and 1.0
+/// is `b`.
+pub fn mix(a: (u8, u8, u8), b: (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    let channel =
+        |x: u8, y: u8| (f64::from(x) + (f64::from(y) - f64::from(x)) * amount).round() as u8;
+    (channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
+
+/// Return relative luminance of sRGB color as defined by WCAG, from 0.0 for
+/// black to 1.0 for white.
+pub fn luminance(r: u8, g: u8, b: u8) -> f64 {
+    let linear = |c: u8| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
@@ -0,0 +1,57 @@
+//! Cursor movement and erase sequences.
+//!
+//! Sequences are returned as is regardless of color settings, it is up to the
+//! caller to only emit them when running inside of interactive TTY.
+
+/// Hide cursor.
+pub const HIDE: &str = "\x1B[?25l";
+/// Show cursor.
+pub const SHOW: &str = "\x1B[?25h";
+/// Save cursor position.
+pub const SAVE: &str = "\x1B7";
+/// Restore cursor position.
+pub const RESTORE: &str = "\x1B8";
+/// Erase whole current line.
+pub const CLEAR_LINE: &str = "\x1B[2K";
+/// Erase from cursor to end of screen.
+pub const CLEAR_DOWN: &str = "\x1B[J";
+/// Erase whole screen.
+pub const CLEAR_SCREEN: &str = "\x1B[2J";
+
+/// Return sequence moving cursor up `n` lines.
+pub fn up(n: usize) -> String {
+    movement(n, 'A')
+}
+
+/// Return sequence moving cursor down `n` lines.
+pub fn down(n: usize) -> String {
+    movement(n, 'B')
+}
+
+/// Return sequence moving cursor forward `n` columns.
+pub fn forward(n: usize) -> String {
+    movement(n, 'C')
+}
+
+/// Return sequence moving cursor back `n` columns.
+pub fn back(n: usize) -> String {
+    movement(n, 'D')
+}
+
+/// Return sequence moving cursor to column, starting from 1.
+pub fn column(col: usize) -> String {
+    format!("\x1B[{}G", col)
+}
+
+/// Return sequence moving cursor to row and column, starting from 1.
+pub fn move_to(row: usize, col: usize) -> String {
+    format!("\x1B[{};{}H", row, col)
+}
+
+/// Return relative movement sequence, or empty string if `n` is zero.
+fn movement(n: usize, direction: char) -> String {
+    if n == 0 {
+        return String::new();
+    }
+    format!("\x1B[{}{}", n, direction)
+}
@@ -0,0 +1,112 @@
+//! Rendering of components under every output capability.
+//!
+//! Components taking [`Codes`] and [`Capabilities`] as arguments, instead of
+//! detecting them on their own, can be rendered for every combination of
+//! color support, charset and TTY versus pipe in one go, so that output can
+//! be reviewed or snapshot tested for all of them.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! for (variant, output) in colors::degradation::render_all(|ansi, caps| status(ansi, caps)) {
+//!     println!("== {}\n{}", variant, output);
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{init_off, init_on_with, Capabilities, Charset, Codes, ColorSupport, Host, Sink};
+
+/// Color support levels of TTY output, from most to least capable.
+const COLOR_LEVELS: [ColorSupport; 5] = [
+    ColorSupport::TrueColor,
+    ColorSupport::Ansi256,
+    ColorSupport::Ansi16,
+    ColorSupport::Ansi8,
+    ColorSupport::NoColor,
+];
+
+/// Combination of capabilities component is rendered under.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Variant {
+    pub color: ColorSupport,
+    pub charset: Charset,
+    /// Output is TTY, piped output never has colors.
+    pub tty: bool,
+}
+
+impl Variant {
+    /// Return codes matching variant.
+    pub fn codes(&self) -> Codes {
+        if self.color == ColorSupport::NoColor {
+            init_off()
+        } else {
+            init_on_with(self.color)
+        }
+    }
+
+    /// Return capabilities matching variant, for a regular terminal without
+    /// screen reader.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            color: self.color != ColorSupport::NoColor,
+            charset: self.charset,
+            screen_reader: false,
+            host: Host::Terminal,
+            sink: if self.tty { Sink::Terminal } else { Sink::Pipe },
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let color = match self.color {
+            ColorSupport::TrueColor => "truecolor",
+            ColorSupport::Ansi256 => "256",
+            ColorSupport::Ansi16 => "16",
+            ColorSupport::Ansi8 => "8",
+            ColorSupport::NoColor => "off",
+        };
+        let charset = match self.charset {
+            Charset::Unicode => "unicode",
+            Charset::Ascii => "ascii",
+        };
+        let output = if self.tty { "tty" } else { "pipe" };
+        write!(f, "{}/{}/{}", color, charset, output)
+    }
+}
+
+/// Return every variant, TTY variants for each color level followed by pipe
+/// variants, each for Unicode and ASCII.
+pub fn variants() -> Vec<Variant> {
+    let mut variants = Vec::new();
+    for charset in [Charset::Unicode, Charset::Ascii] {
+        for color in COLOR_LEVELS {
+            variants.push(Variant {
+                color,
+                charset,
+                tty: true,
+            });
+        }
+        variants.push(Variant {
+            color: ColorSupport::NoColor,
+            charset,
+            tty: false,
+        });
+    }
+    variants
+}
+
+/// Return output of component rendered under every variant, in order of
+/// [`variants`].
+pub fn render_all<F: FnMut(&Codes, &Capabilities) -> String>(
+    mut widget: F,
+) -> Vec<(Variant, String)> {
+    variants()
+        .into_iter()
+        .map(|variant| {
+            let output = widget(&variant.codes(), &variant.capabilities());
+            (variant, output)
+        })
+        .collect()
+}
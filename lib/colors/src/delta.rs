@@ -0,0 +1,106 @@
+//! Badges showing change between runs.
+//!
+//! Increases are shown with an up arrow in success style and decreases with a
+//! down arrow in error style of the given theme, or the other way around if
+//! lower is better, e.g. for durations. Arrows follow the glyphs of the given
+//! capabilities, falling back to ASCII and to words for screen readers, so
+//! direction is readable without colors.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let (caps, theme) = (colors::Capabilities::detect(), colors::Theme::detect());
+//! println!("throughput {}", colors::delta(&ansi, &caps, &theme, 3.2));
+//! let cost = format!("${}", colors::fmt_float(12.5, 2));
+//! println!("cost {}", colors::delta_badge(&ansi, &caps, &theme, 12.5, &cost, false));
+//! ```
+
+use crate::{fmt_float, Capabilities, Codes, Glyphs, Style, Theme};
+
+/// Return arrow and style for sign of value, see [`delta_badge`].
+pub(crate) fn direction(
+    glyphs: &'static Glyphs,
+    theme: &Theme,
+    value: f64,
+    higher_is_better: bool,
+) -> (&'static str, Style) {
+    let (increase, decrease) = if higher_is_better {
+        (theme.success, theme.error)
+    } else {
+        (theme.error, theme.success)
+    };
+    if value > 0.0 {
+        (glyphs.up, increase)
+    } else if value < 0.0 {
+        (glyphs.down, decrease)
+    } else {
+        (glyphs.flat, theme.muted)
+    }
+}
+
+/// Return percent change badge rounded to one decimal, e.g. `▲ +3.2%` or
+/// `▼ -1.4%`, with higher values being better.
+pub fn delta(codes: &Codes, caps: &Capabilities, theme: &Theme, percent: f64) -> String {
+    let rounded = (percent * 10.0).round() / 10.0;
+    let sign = if rounded > 0.0 { "+" } else { "" };
+    let text = format!("{}{}%", sign, fmt_float(rounded, 1));
+    delta_badge(codes, caps, theme, rounded, &text, true)
+}
+
+/// Return badge with arrow for sign of `value` followed by `text`, e.g.
+/// formatted currency amount. Colors follow `higher_is_better`, zero and NaN
+/// are shown as unchanged in muted style.
+pub fn delta_badge(
+    codes: &Codes,
+    caps: &Capabilities,
+    theme: &Theme,
+    value: f64,
+    text: &str,
+    higher_is_better: bool,
+) -> String {
+    let (arrow, style) = direction(caps.glyphs(), theme, value, higher_is_better);
+    style.paint(codes, &format!("{} {}", arrow, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi;
+    use crate::degradation::render_all;
+    use crate::{init_on_with, ColorSupport};
+
+    #[test]
+    fn badge_directions() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let caps = Capabilities::detect();
+        let theme = Theme::new();
+        let glyphs = caps.glyphs();
+        assert_eq!(
+            delta(&codes, &caps, &theme, 3.24),
+            theme.success.paint(&codes, &format!("{} +3.2%", glyphs.up))
+        );
+        assert_eq!(
+            delta_badge(&codes, &caps, &theme, 2.0, "$2", false),
+            theme.error.paint(&codes, &format!("{} $2", glyphs.up))
+        );
+        assert_eq!(
+            delta_badge(&codes, &caps, &theme, f64::NAN, "n/a", true),
+            theme.muted.paint(&codes, &format!("{} n/a", glyphs.flat))
+        );
+    }
+
+    #[test]
+    fn degrade_arrows() {
+        let theme = Theme::new();
+        for (variant, output) in render_all(|codes, caps| delta(codes, caps, &theme, -1.0)) {
+            let glyphs = variant.charset.glyphs();
+            assert_eq!(
+                ansi::strip(&output),
+                format!("{} -1.0%", glyphs.down),
+                "{}",
+                variant
+            );
+        }
+    }
+}
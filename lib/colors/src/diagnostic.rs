@@ -0,0 +1,212 @@
+//! Diagnostics, i.e. errors and warnings tied to a location in a file.
+//!
+//! Diagnostics render as colored text locally and can be exported as SARIF
+//! for code scanning services, or matched in rendered form by a GitHub
+//! Actions problem matcher.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let diag = Diagnostic::new(Level::Error, "unexpected token").at("config.toml", 3, 7);
+//! eprint!("{}", diag.render(&ansi));
+//! ```
+
+use std::path::Path;
+
+use crate::{json, link_location, region, Codes, Color, Level, Style};
+
+/// Message with severity level and optional source location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub code: Option<String>,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl Diagnostic {
+    /// Return diagnostic without location.
+    pub fn new(level: Level, message: &str) -> Diagnostic {
+        Diagnostic {
+            level,
+            code: None,
+            message: message.to_string(),
+            file: None,
+            line: None,
+            column: None,
+        }
+    }
+
+    /// Set code identifying kind of diagnostic, e.g. `E0308`.
+    pub fn with_code(mut self, code: &str) -> Diagnostic {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    /// Set location, line and column start from 1.
+    pub fn at(mut self, file: &str, line: u32, column: u32) -> Diagnostic {
+        self.file = Some(file.to_string());
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    /// Return location formatted as `file:line:column`, if any.
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_ref()?;
+        Some(match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+            (Some(line), None) => format!("{}:{}", file, line),
+            _ => file.to_string(),
+        })
+    }
+
+    /// Return diagnostic rendered as styled text, e.g.
+    /// `error[code]: message` followed by `  --> file:line:column`.
+    pub fn render(&self, codes: &Codes) -> String {
+        let label = match &self.code {
+            Some(code) => format!("{}[{}]", self.level.name(), code),
+            None => self.level.name().to_string(),
+        };
+        let mut output = format!(
+            "{}: {}\n",
+            self.level.style().paint(codes, &label),
+            self.message
+        );
+        if let Some(location) = self.location() {
+            let location = match (&self.file, self.line, self.column) {
+                (Some(file), Some(line), Some(column)) => {
+                    link_location(codes, Path::new(file), line, column, &Style::new())
+                }
+                _ => location,
+            };
+            let arrow = Style::new().fg(Color::BrightBlue).paint(codes, "-->");
+            output.push_str(&format!("  {} {}\n", arrow, location));
+        }
+        region("diagnostic", &output)
+    }
+}
+
+/// Return SARIF level name for diagnostic level.
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Info | Level::Success | Level::Hint | Level::Verbose | Level::Debug => "note",
+    }
+}
+
+/// Return diagnostics as SARIF 2.1.0 log with single run of tool `name`.
+pub fn to_sarif(diagnostics: &[Diagnostic], name: &str) -> String {
+    let results: Vec<String> = diagnostics
+        .iter()
+        .map(|diag| {
+            let message = json::Object::new().string("text", &diag.message).finish();
+            let mut result = json::Object::new()
+                .opt_string("ruleId", diag.code.as_deref())
+                .string("level", sarif_level(diag.level))
+                .raw("message", &message);
+            if let Some(file) = &diag.file {
+                let artifact = json::Object::new().string("uri", file).finish();
+                let region = json::Object::new()
+                    .opt_number("startLine", diag.line)
+                    .opt_number("startColumn", diag.column)
+                    .finish();
+                let mut physical = json::Object::new().raw("artifactLocation", &artifact);
+                if diag.line.is_some() {
+                    physical = physical.raw("region", &region);
+                }
+                let location = json::Object::new()
+                    .raw("physicalLocation", &physical.finish())
+                    .finish();
+                result = result.raw("locations", &format!("[{}]", location));
+            }
+            result.finish()
+        })
+        .collect();
+
+    let driver = json::Object::new().string("name", name).finish();
+    let tool = json::Object::new().raw("driver", &driver).finish();
+    let run = json::Object::new()
+        .raw("tool", &tool)
+        .raw("results", &format!("[{}]", results.join(",")))
+        .finish();
+    json::Object::new()
+        .string("version", "2.1.0")
+        .string("$schema", "https://json.schemastore.org/sarif-2.1.0.json")
+        .raw("runs", &format!("[{}]", run))
+        .finish()
+}
+
+/// Return GitHub Actions problem matchers matching output of
+/// [`Diagnostic::render`] with colors disabled, registered using
+/// `::add-matcher::path/to/file.json`.
+///
+/// Errors and warnings are matched by matcher `owner`, info, success and
+/// hint diagnostics by matcher `owner-notice` with severity `notice`. Line
+/// and column are optional.
+pub fn problem_matcher(owner: &str) -> String {
+    let location = json::Object::new()
+        .string("regexp", r"^  --> (.+?)(?::(\d+))?(?::(\d+))?$")
+        .raw("file", "1")
+        .raw("line", "2")
+        .raw("column", "3")
+        .finish();
+
+    let first = json::Object::new()
+        .string("regexp", r"^(error|warning)(?:\[(.+)\])?: (.*)$")
+        .raw("severity", "1")
+        .raw("code", "2")
+        .raw("message", "3")
+        .finish();
+    let matcher = json::Object::new()
+        .string("owner", owner)
+        .raw("pattern", &format!("[{},{}]", first, location))
+        .finish();
+
+    let first = json::Object::new()
+        .string("regexp", r"^(?:info|success|hint)(?:\[(.+)\])?: (.*)$")
+        .raw("code", "1")
+        .raw("message", "2")
+        .finish();
+    let notice = json::Object::new()
+        .string("owner", &format!("{}-notice", owner))
+        .string("severity", "notice")
+        .raw("pattern", &format!("[{},{}]", first, location))
+        .finish();
+
+    json::Object::new()
+        .raw("problemMatcher", &format!("[{},{}]", matcher, notice))
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_off;
+
+    #[test]
+    fn render_without_colors() {
+        let codes = init_off();
+        let diag = Diagnostic::new(Level::Error, "bad")
+            .with_code("E1")
+            .at("a.rs", 3, 7);
+        assert_eq!(diag.render(&codes), "error[E1]: bad\n  --> a.rs:3:7\n");
+        let mut diag = Diagnostic::new(Level::Hint, "try this");
+        diag.file = Some("b.rs".to_string());
+        assert_eq!(diag.render(&codes), "hint: try this\n  --> b.rs\n");
+    }
+
+    #[test]
+    fn problem_matcher_severities() {
+        let matcher = problem_matcher("skrot");
+        assert!(matcher.contains(r#""owner":"skrot","pattern""#));
+        assert!(matcher.contains(r#"^(error|warning)(?:\\[(.+)\\])?: (.*)$"#));
+        assert!(matcher.contains(r#""owner":"skrot-notice","severity":"notice""#));
+        assert!(matcher.contains(r#"^  --> (.+?)(?::(\\d+))?(?::(\\d+))?$"#));
+        assert!(!matcher.contains("|info|"));
+    }
+}
@@ -0,0 +1,368 @@
+//! Diffs of expected and actual output for test tools.
+//!
+//! Lines are compared by longest common subsequence. Runs of removed lines
+//! directly followed by added lines are paired and compared word by word,
+//! so the changed words stand out in reverse video. Removed lines are
+//! prefixed with `-` in error style and added lines with `+` in success style
+//! of the given theme, markers stay readable without colors.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! if expected != actual {
+//!     let theme = colors::Theme::detect();
+//!     eprint!("{}", colors::expect_diff(&ansi, &theme, &expected, &actual));
+//! }
+//! ```
+
+use std::cmp::Reverse;
+
+use crate::{Attr, Codes, Style, Theme};
+
+/// Edit turning one sequence into another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Return edits turning `a` into `b` using longest common subsequence.
+/// Common prefix and suffix are split off first, as typical diffs of test
+/// output only differ in a few lines.
+fn diff<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<Op<'a>> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let mut ops = Vec::with_capacity(a.len().max(b.len()));
+    ops.extend(a[..prefix].iter().map(|line| Op::Equal(line)));
+    lcs(
+        &a[prefix..a.len() - suffix],
+        &b[prefix..b.len() - suffix],
+        &mut ops,
+    );
+    ops.extend(a[a.len() - suffix..].iter().map(|line| Op::Equal(line)));
+    ops
+}
+
+/// Append edits turning `a` into `b` to ops, splitting at middle of `a` as
+/// in Hirschberg's algorithm so memory stays linear.
+fn lcs<'a>(a: &[&'a str], b: &[&'a str], ops: &mut Vec<Op<'a>>) {
+    if a.is_empty() || b.is_empty() {
+        ops.extend(a.iter().map(|line| Op::Delete(line)));
+        ops.extend(b.iter().map(|line| Op::Insert(line)));
+        return;
+    }
+    if a.len() == 1 {
+        match b.iter().position(|line| *line == a[0]) {
+            Some(j) => {
+                ops.extend(b[..j].iter().map(|line| Op::Insert(line)));
+                ops.push(Op::Equal(a[0]));
+                ops.extend(b[j + 1..].iter().map(|line| Op::Insert(line)));
+            }
+            None => {
+                ops.push(Op::Delete(a[0]));
+                ops.extend(b.iter().map(|line| Op::Insert(line)));
+            }
+        }
+        return;
+    }
+
+    // Split `b` where common subsequences of both halves of `a` are longest,
+    // at first such point so deletions come before insertions.
+    let middle = a.len() / 2;
+    let forward = lengths(a[..middle].iter().copied(), b.iter().copied());
+    let backward = lengths(a[middle..].iter().rev().copied(), b.iter().rev().copied());
+    let split = (0..=b.len())
+        .max_by_key(|&j| (forward[j] + backward[b.len() - j], Reverse(j)))
+        .unwrap_or(0);
+    lcs(&a[..middle], &b[..split], ops);
+    lcs(&a[middle..], &b[split..], ops);
+}
+
+/// Return lengths of longest common subsequence of `a` and each prefix of
+/// `b`, from empty prefix to all of `b`.
+fn lengths<'a>(
+    a: impl Iterator<Item = &'a str>,
+    b: impl Iterator<Item = &'a str> + Clone,
+) -> Vec<usize> {
+    let mut row = vec![0; b.clone().count() + 1];
+    for x in a {
+        let mut diagonal = 0;
+        for (j, y) in b.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if x == y {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+/// Return words and whitespace runs of line.
+fn words(line: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut space = None;
+    for (i, c) in line.char_indices() {
+        let is_space = c.is_whitespace();
+        if space.is_some_and(|s| s != is_space) {
+            words.push(&line[start..i]);
+            start = i;
+        }
+        space = Some(is_space);
+    }
+    if start < line.len() {
+        words.push(&line[start..]);
+    }
+    words
+}
+
+/// Append word to segments, joining it with last segment of same kind.
+fn push(segments: &mut Vec<(bool, String)>, changed: bool, word: &str) {
+    match segments.last_mut() {
+        Some((last, text)) if *last == changed => text.push_str(word),
+        _ => segments.push((changed, word.to_string())),
+    }
+}
+
+/// Return segments painted in style, changed segments in reverse video.
+fn paint(codes: &Codes, segments: &[(bool, String)], style: Style) -> String {
+    segments
+        .iter()
+        .map(|(changed, text)| {
+            if *changed {
+                style.attr(Attr::Reverse).paint(codes, text)
+            } else {
+                style.paint(codes, text)
+            }
+        })
+        .collect()
+}
+
+/// Return removed and added line with words only found in one of them
+/// highlighted.
+fn word_diff(
+    codes: &Codes,
+    old: &str,
+    new: &str,
+    removed: Style,
+    added: Style,
+) -> (String, String) {
+    let (mut old_segments, mut new_segments) = (Vec::new(), Vec::new());
+    for op in diff(&words(old), &words(new)) {
+        match op {
+            Op::Equal(word) => {
+                push(&mut old_segments, false, word);
+                push(&mut new_segments, false, word);
+            }
+            Op::Delete(word) => push(&mut old_segments, true, word),
+            Op::Insert(word) => push(&mut new_segments, true, word),
+        }
+    }
+    (
+        paint(codes, &old_segments, removed),
+        paint(codes, &new_segments, added),
+    )
+}
+
+/// Return diff of expected and actual text with header, one line per line
+/// of both texts marked as unchanged, removed or added.
+pub fn expect_diff(codes: &Codes, theme: &Theme, expected: &str, actual: &str) -> String {
+    let (removed, added) = (theme.error, theme.success);
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let ops = diff(&expected_lines, &actual_lines);
+
+    let mut output = format!(
+        "{}\n{}\n",
+        removed.paint(codes, "- expected"),
+        added.paint(codes, "+ actual")
+    );
+    let mut index = 0;
+    while index < ops.len() {
+        if let Op::Equal(line) = ops[index] {
+            output.push_str(&format!("  {}\n", theme.muted.paint(codes, line)));
+            index += 1;
+            continue;
+        }
+
+        // Collect run of changes and pair removed with added lines.
+        let end = ops[index..]
+            .iter()
+            .position(|op| matches!(op, Op::Equal(_)))
+            .map_or(ops.len(), |n| index + n);
+        let run = &ops[index..end];
+        let old: Vec<&str> = run
+            .iter()
+            .filter_map(|op| match op {
+                Op::Delete(line) => Some(*line),
+                _ => None,
+            })
+            .collect();
+        let new: Vec<&str> = run
+            .iter()
+            .filter_map(|op| match op {
+                Op::Insert(line) => Some(*line),
+                _ => None,
+            })
+            .collect();
+
+        let mut old_lines = Vec::new();
+        let mut new_lines = Vec::new();
+        for (i, line) in old.iter().enumerate() {
+            match new.get(i) {
+                Some(other) => {
+                    let (old_line, new_line) = word_diff(codes, line, other, removed, added);
+                    old_lines.push(old_line);
+                    new_lines.push(new_line);
+                }
+                None => old_lines.push(removed.paint(codes, line)),
+            }
+        }
+        new_lines.extend(
+            new.iter()
+                .skip(old.len())
+                .map(|line| added.paint(codes, line)),
+        );
+
+        for line in old_lines {
+            output.push_str(&format!("{} {}\n", removed.paint(codes, "-"), line));
+        }
+        for line in new_lines {
+            output.push_str(&format!("{} {}\n", added.paint(codes, "+"), line));
+        }
+        index = end;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_off, init_on_with, Color, ColorSupport};
+
+    /// Return length of longest common subsequence using full table.
+    fn lcs_length(a: &[&str], b: &[&str]) -> usize {
+        let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in 0..a.len() {
+            for j in 0..b.len() {
+                table[i + 1][j + 1] = if a[i] == b[j] {
+                    table[i][j] + 1
+                } else {
+                    table[i][j + 1].max(table[i + 1][j])
+                };
+            }
+        }
+        table[a.len()][b.len()]
+    }
+
+    /// Check that ops turn `a` into `b` keeping longest common subsequence.
+    fn check(a: &[&str], b: &[&str]) {
+        let ops = diff(a, b);
+        let old: Vec<&str> = ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Equal(x) | Op::Delete(x) => Some(*x),
+                Op::Insert(_) => None,
+            })
+            .collect();
+        let new: Vec<&str> = ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Equal(x) | Op::Insert(x) => Some(*x),
+                Op::Delete(_) => None,
+            })
+            .collect();
+        assert_eq!(old, a);
+        assert_eq!(new, b);
+        let equal = ops.iter().filter(|op| matches!(op, Op::Equal(_))).count();
+        assert_eq!(equal, lcs_length(a, b), "{:?} -> {:?}", a, b);
+    }
+
+    #[test]
+    fn diff_is_minimal() {
+        let cases: [(&str, &str); 7] = [
+            ("", ""),
+            ("abc", ""),
+            ("", "abc"),
+            ("abcabba", "cbabac"),
+            ("xaxbxcx", "abc"),
+            ("abcdef", "abXdef"),
+            ("the quick brown fox", "a quick brown dog"),
+        ];
+        for (a, b) in cases {
+            let a: Vec<&str> = a.split("").filter(|s| !s.is_empty()).collect();
+            let b: Vec<&str> = b.split("").filter(|s| !s.is_empty()).collect();
+            check(&a, &b);
+        }
+    }
+
+    #[test]
+    fn diff_large_input() {
+        let a: Vec<String> = (0..5000).map(|i| format!("line {}", i)).collect();
+        let mut b = a.clone();
+        b[2500] = "changed".to_string();
+        b.insert(100, "inserted".to_string());
+        let a: Vec<&str> = a.iter().map(String::as_str).collect();
+        let b: Vec<&str> = b.iter().map(String::as_str).collect();
+        let ops = diff(&a, &b);
+        let changes: Vec<&Op> = ops
+            .iter()
+            .filter(|op| !matches!(op, Op::Equal(_)))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                &Op::Insert("inserted"),
+                &Op::Delete("line 2500"),
+                &Op::Insert("changed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn words_keep_whitespace() {
+        assert_eq!(words("  a bc\t d"), ["  ", "a", " ", "bc", "\t ", "d"]);
+        assert!(words("").is_empty());
+    }
+
+    #[test]
+    fn expect_diff_plain() {
+        let codes = init_off();
+        let output = expect_diff(
+            &codes,
+            &Theme::new(),
+            "one\ntwo\nthree\n",
+            "one\n2\nthree\nfour\n",
+        );
+        assert_eq!(
+            output,
+            "- expected\n+ actual\n  one\n- two\n+ 2\n  three\n+ four\n"
+        );
+    }
+
+    #[test]
+    fn expect_diff_highlights_words() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let theme = Theme {
+            error: Style::new().fg(Color::Magenta),
+            ..Theme::new()
+        };
+        let output = expect_diff(&codes, &theme, "a b c", "a x c");
+        let changed = theme.error.attr(Attr::Reverse).paint(&codes, "b");
+        let inserted = theme.success.attr(Attr::Reverse).paint(&codes, "x");
+        assert!(output.contains(&changed), "{:?}", output);
+        assert!(output.contains(&inserted), "{:?}", output);
+        assert!(output.contains(&theme.error.paint(&codes, "a ")));
+    }
+}
@@ -0,0 +1,513 @@
+//! Output of logical events as styled text or JSON lines.
+//!
+//! Programs describe what happened using [`Event`] values and hand them to an
+//! [`Emitter`]. The same events are rendered as styled text for humans or as
+//! one JSON object per line for machines, e.g. selected by `--format=json`,
+//! so styling never forks the output logic.
+//!
+//! On CI services with native annotation support diagnostics can be emitted
+//! as annotations instead, see [`auto`].
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let format: Format = args.format.parse()?;
+//! let mut out = colors::emit::new(format, colors::init_auto(), io::stdout());
+//! out.emit(&Event::Status { level: Level::Success, label: "Built", message: "skrot" })?;
+//! ```
+
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use crate::stats::Counted;
+use crate::{high_contrast_requested, json, markers, Attr, Codes, Color, Diagnostic, Style};
+
+/// Styles set with [`set_semantic`], indexed by level.
+static SEMANTIC: RwLock<[Option<Style>; Level::ALL.len()]> = RwLock::new([None; Level::ALL.len()]);
+
+/// Severity or kind of message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Level {
+    Error,
+    Warning,
+    Info,
+    Success,
+    Hint,
+    Verbose,
+    Debug,
+}
+
+impl Level {
+    /// All levels, from most to least severe.
+    pub const ALL: [Level; 7] = [
+        Level::Error,
+        Level::Warning,
+        Level::Info,
+        Level::Success,
+        Level::Hint,
+        Level::Verbose,
+        Level::Debug,
+    ];
+
+    /// Return lowercase name, e.g. `warning`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Info => "info",
+            Level::Success => "success",
+            Level::Hint => "hint",
+            Level::Verbose => "verbose",
+            Level::Debug => "debug",
+        }
+    }
+
+    /// Return style used for level, i.e. style set with [`set_semantic`] or
+    /// default style, remapped if high contrast is requested.
+    pub fn style(self) -> Style {
+        let semantic = SEMANTIC.read().unwrap_or_else(|e| e.into_inner());
+        let style = semantic[self as usize].unwrap_or_else(|| self.default_style());
+        if high_contrast_requested() {
+            style.high_contrast()
+        } else {
+            style
+        }
+    }
+
+    /// Return built-in default style used for level.
+    pub fn default_style(self) -> Style {
+        match self {
+            Level::Error => Style::new().fg(Color::Red).attr(Attr::Bold),
+            Level::Warning => Style::new().fg(Color::Yellow).attr(Attr::Bold),
+            Level::Info => Style::new().fg(Color::Cyan).attr(Attr::Bold),
+            Level::Success => Style::new().fg(Color::Green).attr(Attr::Bold),
+            Level::Hint => Style::new().fg(Color::Blue),
+            Level::Verbose | Level::Debug => Style::new().attr(Attr::Dim),
+        }
+    }
+}
+
+/// Set style used for level by all components for whole program.
+pub fn set_semantic(level: Level, style: Style) {
+    SEMANTIC.write().unwrap_or_else(|e| e.into_inner())[level as usize] = Some(style);
+}
+
+/// Restore built-in default styles for all levels.
+pub fn reset_semantic() {
+    *SEMANTIC.write().unwrap_or_else(|e| e.into_inner()) = [None; Level::ALL.len()];
+}
+
+/// Logical output event.
+#[derive(Clone, Copy, Debug)]
+pub enum Event<'a> {
+    /// Plain message, e.g. `warning: disk almost full`.
+    Message { level: Level, text: &'a str },
+    /// Progress or result line, e.g. `   Compiling skrot`.
+    Status {
+        level: Level,
+        label: &'a str,
+        message: &'a str,
+    },
+    /// Row of table cells.
+    Row(&'a [&'a str]),
+    /// Error or warning, possibly tied to a source location.
+    Diagnostic(&'a Diagnostic),
+    /// Start of titled section, groups can be nested.
+    GroupStart { title: &'a str },
+    /// End of section started by last [`Event::GroupStart`].
+    GroupEnd,
+}
+
+/// Output format selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+/// Error returned when parsing unknown [`Format`] name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseFormatError(String);
+
+impl fmt::Display for ParseFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown output format '{}', expected text or json",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseFormatError {}
+
+impl FromStr for Format {
+    type Err = ParseFormatError;
+
+    fn from_str(s: &str) -> Result<Format, ParseFormatError> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" | "human" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(ParseFormatError(s.to_string())),
+        }
+    }
+}
+
+/// Destination for output events.
+pub trait Emitter {
+    /// Render and write event.
+    fn emit(&mut self, event: &Event) -> io::Result<()>;
+}
+
+/// Emitter rendering events as styled text.
+///
+/// Content inside groups is indented by two spaces per nesting level. Writes
+/// are counted by [`stats`](crate::stats).
+pub struct Text<W: Write> {
+    codes: Codes,
+    writer: Counted<W>,
+    depth: usize,
+}
+
+impl<W: Write> Text<W> {
+    pub fn new(codes: Codes, writer: W) -> Text<W> {
+        Text {
+            codes,
+            writer: Counted::new(writer),
+            depth: 0,
+        }
+    }
+}
+
+/// Return event rendered as styled text, every line ending with newline.
+pub(crate) fn to_text(codes: &Codes, event: &Event) -> String {
+    match *event {
+        Event::Message { level, text } => match level {
+            Level::Info => format!("{}\n", text),
+            Level::Success | Level::Verbose => {
+                format!("{}\n", level.style().paint(codes, text))
+            }
+            _ => {
+                let name = level.style().paint(codes, level.name());
+                format!("{}: {}\n", name, text)
+            }
+        },
+        Event::Status {
+            level,
+            label,
+            message,
+        } => {
+            let label = format!("{:>12}", label);
+            format!("{} {}\n", level.style().paint(codes, &label), message)
+        }
+        Event::Row(cells) => format!("{}\n", cells.join("  ")),
+        Event::Diagnostic(diag) => diag.render(codes),
+        Event::GroupStart { title } => {
+            let style = Style::new().attr(Attr::Bold);
+            format!("{}\n", style.paint(codes, title))
+        }
+        Event::GroupEnd => String::new(),
+    }
+}
+
+impl<W: Write> Emitter for Text<W> {
+    fn emit(&mut self, event: &Event) -> io::Result<()> {
+        if let Event::GroupEnd = event {
+            self.depth = self.depth.saturating_sub(1);
+            return write!(self.writer, "{}", markers().end("section"));
+        }
+
+        let indent = "  ".repeat(self.depth);
+        if let Event::GroupStart { .. } = event {
+            write!(self.writer, "{}", markers().begin("section"))?;
+        }
+        for line in to_text(&self.codes, event).lines() {
+            writeln!(self.writer, "{}{}", indent, line)?;
+        }
+        if let Event::GroupStart { .. } = event {
+            self.depth += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Emitter rendering events as JSON lines.
+pub struct Json<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Json<W> {
+    pub fn new(writer: W) -> Json<W> {
+        Json { writer }
+    }
+}
+
+/// Return event encoded as single line JSON object.
+pub(crate) fn to_json(event: &Event) -> String {
+    let object = json::Object::new();
+    match *event {
+        Event::Message { level, text } => object
+            .string("type", "message")
+            .string("level", level.name())
+            .string("text", text),
+        Event::Status {
+            level,
+            label,
+            message,
+        } => object
+            .string("type", "status")
+            .string("level", level.name())
+            .string("label", label)
+            .string("message", message),
+        Event::Row(cells) => object
+            .string("type", "row")
+            .raw("cells", &json::string_array(cells)),
+        Event::Diagnostic(diag) => object
+            .string("type", "diagnostic")
+            .string("level", diag.level.name())
+            .opt_string("code", diag.code.as_deref())
+            .string("message", &diag.message)
+            .opt_string("file", diag.file.as_deref())
+            .opt_number("line", diag.line)
+            .opt_number("column", diag.column),
+        Event::GroupStart { title } => object.string("type", "group_start").string("title", title),
+        Event::GroupEnd => object.string("type", "group_end"),
+    }
+    .finish()
+}
+
+impl<W: Write> Emitter for Json<W> {
+    fn emit(&mut self, event: &Event) -> io::Result<()> {
+        writeln!(self.writer, "{}", to_json(event))
+    }
+}
+
+/// Return emitter for format, `codes` is only used by text output.
+pub fn new<W: Write + 'static>(format: Format, codes: Codes, writer: W) -> Box<dyn Emitter> {
+    match format {
+        Format::Text => Box::new(Text::new(codes, writer)),
+        Format::Json => Box::new(Json::new(writer)),
+    }
+}
+
+/// CI services with native annotation support.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CiKind {
+    /// GitHub Actions workflow commands, e.g. `::error file=…::`.
+    GitHub,
+    /// Azure Pipelines logging commands, e.g. `##vso[task.logissue …]`.
+    Azure,
+}
+
+/// Detect CI service from environment variables.
+pub fn detect_ci() -> Option<CiKind> {
+    if env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true") {
+        return Some(CiKind::GitHub);
+    }
+    if env::var_os("TF_BUILD").is_some() {
+        return Some(CiKind::Azure);
+    }
+    None
+}
+
+/// Emitter writing diagnostics as CI annotations and everything else as
+/// styled text.
+///
+/// Outermost groups use native collapsible log groups, nested groups are
+/// rendered as indented text since CI services do not support nesting.
+pub struct Ci<W: Write> {
+    kind: CiKind,
+    text: Text<W>,
+    depth: usize,
+}
+
+impl<W: Write> Ci<W> {
+    pub fn new(kind: CiKind, codes: Codes, writer: W) -> Ci<W> {
+        Ci {
+            kind,
+            text: Text::new(codes, writer),
+            depth: 0,
+        }
+    }
+}
+
+/// Return value escaped for GitHub workflow commands.
+fn github_escape(value: &str, property: bool) -> String {
+    let mut output = String::new();
+    for c in crate::ansi::strip(value).chars() {
+        match c {
+            '%' => output.push_str("%25"),
+            '\r' => output.push_str("%0D"),
+            '\n' => output.push_str("%0A"),
+            ':' if property => output.push_str("%3A"),
+            ',' if property => output.push_str("%2C"),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// Return value escaped for Azure Pipelines logging commands.
+fn azure_escape(value: &str) -> String {
+    let mut output = String::new();
+    for c in crate::ansi::strip(value).chars() {
+        match c {
+            '%' => output.push_str("%AZP25"),
+            ';' => output.push_str("%3B"),
+            ']' => output.push_str("%5D"),
+            '\r' => output.push_str("%0D"),
+            '\n' => output.push_str("%0A"),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// Return diagnostic as annotation line, if kind of level is supported.
+pub(crate) fn annotation(kind: CiKind, diag: &Diagnostic) -> Option<String> {
+    match kind {
+        CiKind::GitHub => {
+            let command = match diag.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+                _ => "notice",
+            };
+            let mut props = Vec::new();
+            if let Some(file) = &diag.file {
+                props.push(format!("file={}", github_escape(file, true)));
+            }
+            if let Some(line) = diag.line {
+                props.push(format!("line={}", line));
+            }
+            if let Some(column) = diag.column {
+                props.push(format!("col={}", column));
+            }
+            let props = if props.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", props.join(","))
+            };
+            let message = github_escape(&diag.message, false);
+            Some(format!("::{}{}::{}", command, props, message))
+        }
+        CiKind::Azure => {
+            let kind = match diag.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+                _ => return None,
+            };
+            let mut props = format!("type={}", kind);
+            if let Some(file) = &diag.file {
+                props.push_str(&format!(";sourcepath={}", azure_escape(file)));
+            }
+            if let Some(line) = diag.line {
+                props.push_str(&format!(";linenumber={}", line));
+            }
+            if let Some(column) = diag.column {
+                props.push_str(&format!(";columnnumber={}", column));
+            }
+            let message = azure_escape(&diag.message);
+            Some(format!("##vso[task.logissue {}]{}", props, message))
+        }
+    }
+}
+
+impl<W: Write> Emitter for Ci<W> {
+    fn emit(&mut self, event: &Event) -> io::Result<()> {
+        match *event {
+            Event::Diagnostic(diag) => {
+                if let Some(line) = annotation(self.kind, diag) {
+                    return writeln!(self.text.writer, "{}", line);
+                }
+            }
+            Event::GroupStart { title } => {
+                self.depth += 1;
+                if self.depth == 1 {
+                    let title = crate::ansi::strip(title);
+                    return match self.kind {
+                        CiKind::GitHub => writeln!(self.text.writer, "::group::{}", title),
+                        CiKind::Azure => writeln!(self.text.writer, "##[group]{}", title),
+                    };
+                }
+            }
+            Event::GroupEnd => {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 {
+                    return match self.kind {
+                        CiKind::GitHub => writeln!(self.text.writer, "::endgroup::"),
+                        CiKind::Azure => writeln!(self.text.writer, "##[endgroup]"),
+                    };
+                }
+            }
+            _ => (),
+        }
+        self.text.emit(event)
+    }
+}
+
+/// Return emitter for format, using CI annotations for text output when
+/// running on supported CI service.
+pub fn auto<W: Write + 'static>(format: Format, codes: Codes, writer: W) -> Box<dyn Emitter> {
+    match (format, detect_ci()) {
+        (Format::Text, Some(kind)) => Box::new(Ci::new(kind, codes, writer)),
+        _ => new(format, codes, writer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_annotation_is_escaped() {
+        let diag = Diagnostic::new(Level::Error, "100% bad\n::warning::x").at("a,b:c.rs", 3, 7);
+        assert_eq!(
+            annotation(CiKind::GitHub, &diag).unwrap(),
+            "::error file=a%2Cb%3Ac.rs,line=3,col=7::100%25 bad%0A::warning::x"
+        );
+    }
+
+    #[test]
+    fn azure_annotation_is_escaped() {
+        let diag = Diagnostic::new(Level::Warning, "50%; done]\r\n##vso[task.complete]").at(
+            "src/a;b].rs",
+            1,
+            2,
+        );
+        assert_eq!(
+            annotation(CiKind::Azure, &diag).unwrap(),
+            "##vso[task.logissue type=warning;sourcepath=src/a%3Bb%5D.rs;linenumber=1;\
+             columnnumber=2]50%AZP25%3B done%5D%0D%0A##vso[task.complete%5D"
+        );
+        assert_eq!(
+            annotation(CiKind::Azure, &Diagnostic::new(Level::Info, "x")),
+            None
+        );
+    }
+
+    #[test]
+    fn verbose_messages_are_muted() {
+        let codes = crate::init_on_with(crate::ColorSupport::Ansi16);
+        let event = Event::Message {
+            level: Level::Verbose,
+            text: "reading config",
+        };
+        assert_eq!(to_text(&codes, &event), "\x1B[2mreading config\x1B[0m\n");
+        assert_eq!(Level::Debug.default_style(), crate::Theme::new().muted);
+    }
+
+    #[test]
+    fn all_levels_in_index_order() {
+        for (i, level) in Level::ALL.iter().enumerate() {
+            assert_eq!(*level as usize, i);
+        }
+        let names: Vec<&str> = Level::ALL.iter().map(|level| level.name()).collect();
+        assert_eq!(
+            names,
+            ["error", "warning", "info", "success", "hint", "verbose", "debug"]
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Text colored with horizontal gradients.
+//!
+//! Colors are interpolated in RGB across columns of the widest line, so all
+//! lines of multi-line text like banners share the same gradient. Colors are
+//! downgraded to 256 or 16 colors as supported, see [`Codes::fg_rgb`].
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let art = colors::banner(&ansi, "skrot", colors::Font::Block, &colors::Style::new());
+//! print!("{}", colors::gradient(&ansi, &art, (255, 95, 0), (175, 0, 255)));
+//! ```
+
+use crate::convert::mix;
+use crate::width::{cluster_width, clusters, text_width};
+use crate::Codes;
+
+/// Return plain text with foreground color interpolated from `from` in first
+/// column to `to` in last column, or text unchanged if colors are disabled in
+/// `codes`. Whitespace is left uncolored.
+pub fn gradient(codes: &Codes, text: &str, from: (u8, u8, u8), to: (u8, u8, u8)) -> String {
+    if !codes.is_enabled() {
+        return text.to_string();
+    }
+    let width = text.lines().map(text_width).max().unwrap_or(0);
+    let last = width.saturating_sub(1).max(1) as f64;
+
+    let mut output = String::with_capacity(text.len() * 8);
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        let mut column = 0;
+        let mut current = String::new();
+        for cluster in clusters(line) {
+            if !cluster.trim().is_empty() {
+                let (r, g, b) = mix(from, to, column as f64 / last);
+                let seq = codes.fg_rgb(r, g, b);
+                // Neighbouring columns often share downgraded colors.
+                if seq != current {
+                    output.push_str(&seq);
+                    current = seq;
+                }
+            }
+            output.push_str(cluster);
+            column += cluster_width(cluster);
+        }
+        if !current.is_empty() {
+            output.push_str(&codes.attr.reset);
+        }
+    }
+    output
+}
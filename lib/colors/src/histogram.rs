@@ -0,0 +1,289 @@
+//! Histograms of value distributions.
+//!
+//! Values are counted into equally wide buckets between smallest and
+//! largest value and drawn as bars with count labels, colored as heatmap from
+//! green for rare to red for most common buckets, e.g. for latency or size
+//! distributions. Bars use glyphs of the given capabilities.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let caps = colors::Capabilities::detect();
+//! print!("{}", colors::histogram(&ansi, &caps, &latencies, 10, colors::Orientation::Horizontal));
+//! ```
+
+use crate::convert::mix;
+use crate::{fmt_float, Capabilities, Codes};
+
+/// Length of longest horizontal bar in columns.
+const BAR_WIDTH: usize = 40;
+
+/// Height of tallest vertical bar in lines.
+const BAR_HEIGHT: usize = 8;
+
+/// Heatmap colors from rare to common.
+const HEAT: [(u8, u8, u8); 3] = [(0, 175, 95), (255, 215, 0), (215, 0, 0)];
+
+/// Direction bars grow in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// One line per bucket, labeled with its range.
+    #[default]
+    Horizontal,
+    /// One column per bucket, labeled with range of all values below.
+    Vertical,
+}
+
+/// Return heatmap color for `amount` from 0.0 to 1.0.
+fn heat(amount: f64) -> (u8, u8, u8) {
+    let scaled = amount.clamp(0.0, 1.0) * (HEAT.len() - 1) as f64;
+    let index = (scaled.floor() as usize).min(HEAT.len() - 2);
+    mix(HEAT[index], HEAT[index + 1], scaled - index as f64)
+}
+
+/// Return text painted in heatmap color.
+fn paint(codes: &Codes, amount: f64, text: &str) -> String {
+    if !codes.is_enabled() || text.is_empty() {
+        return text.to_string();
+    }
+    let (r, g, b) = heat(amount);
+    format!("{}{}{}", codes.fg_rgb(r, g, b), text, codes.attr.reset)
+}
+
+/// Return number of values in each bucket, with smallest and largest value.
+/// NaN values are skipped.
+fn count(values: &[f64], buckets: usize) -> (Vec<usize>, f64, f64) {
+    let values = values.iter().copied().filter(|v| !v.is_nan());
+    let (min, max) = values
+        .clone()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+            (min.min(v), max.max(v))
+        });
+    let mut counts = vec![0; buckets];
+    let width = (max - min) / buckets as f64;
+    for value in values {
+        let index = if width > 0.0 {
+            ((value - min) / width) as usize
+        } else {
+            0
+        };
+        counts[index.min(buckets - 1)] += 1;
+    }
+    (counts, min, max)
+}
+
+/// Return histogram of values counted into `buckets` buckets, or empty
+/// string if there are no values or buckets.
+pub fn histogram(
+    codes: &Codes,
+    caps: &Capabilities,
+    values: &[f64],
+    buckets: usize,
+    orientation: Orientation,
+) -> String {
+    if buckets == 0 || values.iter().all(|v| v.is_nan()) {
+        return String::new();
+    }
+    let (counts, min, max) = count(values, buckets);
+    let peak = counts.iter().copied().max().unwrap_or(0).max(1);
+    let glyphs = caps.glyphs();
+    let mut output = String::new();
+
+    match orientation {
+        Orientation::Horizontal => {
+            let width = (max - min) / buckets as f64;
+            let ranges: Vec<(String, String)> = (0..buckets)
+                .map(|i| {
+                    let start = min + width * i as f64;
+                    (fmt_float(start, 1), fmt_float(start + width, 1))
+                })
+                .collect();
+            let start_width = ranges
+                .iter()
+                .map(|r| r.0.chars().count())
+                .max()
+                .unwrap_or(0);
+            let end_width = ranges
+                .iter()
+                .map(|r| r.1.chars().count())
+                .max()
+                .unwrap_or(0);
+            for ((start, end), &n) in ranges.iter().zip(&counts) {
+                let amount = n as f64 / peak as f64;
+                let length = (amount * BAR_WIDTH as f64).round() as usize;
+                let bar = glyphs.block_full.repeat(length.max(usize::from(n > 0)));
+                let bar = if bar.is_empty() {
+                    n.to_string()
+                } else {
+                    format!("{} {}", paint(codes, amount, &bar), n)
+                };
+                output.push_str(&format!(
+                    "{:>sw$} {} {:>ew$}  {}\n",
+                    start,
+                    glyphs.arrow,
+                    end,
+                    bar,
+                    sw = start_width,
+                    ew = end_width
+                ));
+            }
+        }
+        Orientation::Vertical => {
+            let column = counts
+                .iter()
+                .map(|n| n.to_string().len())
+                .max()
+                .unwrap_or(1)
+                .max(2);
+            let heights: Vec<usize> = counts
+                .iter()
+                .map(|&n| {
+                    let height = (n as f64 / peak as f64 * BAR_HEIGHT as f64).round() as usize;
+                    height.max(usize::from(n > 0))
+                })
+                .collect();
+            let labels: Vec<String> = counts
+                .iter()
+                .map(|n| format!("{:>w$}", n, w = column))
+                .collect();
+            output.push_str(labels.join(" ").trim_end());
+            output.push('\n');
+            for level in (1..=BAR_HEIGHT).rev() {
+                let cells: Vec<String> = counts
+                    .iter()
+                    .zip(&heights)
+                    .map(|(&n, &height)| {
+                        if height >= level {
+                            paint(
+                                codes,
+                                n as f64 / peak as f64,
+                                &glyphs.block_full.repeat(column),
+                            )
+                        } else {
+                            " ".repeat(column)
+                        }
+                    })
+                    .collect();
+                output.push_str(cells.join(" ").trim_end());
+                output.push('\n');
+            }
+            let left = fmt_float(min, 1);
+            let right = fmt_float(max, 1);
+            let total = buckets * (column + 1) - 1;
+            let gap = total.saturating_sub(left.len() + right.len()).max(1);
+            output.push_str(&format!("{}{}{}\n", left, " ".repeat(gap), right));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::degradation::{render_all, Variant};
+    use crate::{init_off, Charset, ColorSupport};
+
+    /// Return capabilities of color terminal with Unicode glyphs.
+    fn unicode() -> Capabilities {
+        Variant {
+            color: ColorSupport::Ansi16,
+            charset: Charset::Unicode,
+            tty: true,
+        }
+        .capabilities()
+    }
+
+    #[test]
+    fn count_buckets() {
+        let values = [0.0, 1.0, 2.5, f64::NAN, 9.0, 10.0];
+        assert_eq!(count(&values, 5), (vec![2, 1, 0, 0, 2], 0.0, 10.0));
+        // All values equal go into first bucket.
+        assert_eq!(count(&[3.0, 3.0], 2), (vec![2, 0], 3.0, 3.0));
+    }
+
+    #[test]
+    fn heat_endpoints() {
+        assert_eq!(heat(0.0), HEAT[0]);
+        assert_eq!(heat(0.5), HEAT[1]);
+        assert_eq!(heat(1.0), HEAT[2]);
+        assert_eq!(heat(7.0), HEAT[2]);
+    }
+
+    #[test]
+    fn horizontal_bars() {
+        let caps = unicode();
+        let glyphs = caps.glyphs();
+        let output = histogram(
+            &init_off(),
+            &caps,
+            &[1.0, 2.0, 2.0, 3.0],
+            2,
+            Orientation::Horizontal,
+        );
+        let bar = |n: usize| glyphs.block_full.repeat(n);
+        assert_eq!(
+            output,
+            format!(
+                "1.0 {a} 2.0  {} 1\n2.0 {a} 3.0  {} 3\n",
+                bar(13),
+                bar(BAR_WIDTH),
+                a = glyphs.arrow
+            )
+        );
+    }
+
+    #[test]
+    fn vertical_bars() {
+        let output = histogram(
+            &init_off(),
+            &unicode(),
+            &[1.0, 3.0, 3.0],
+            2,
+            Orientation::Vertical,
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), BAR_HEIGHT + 2);
+        assert_eq!(lines[0], " 1  2");
+        assert_eq!(lines[BAR_HEIGHT + 1], "1.0 3.0");
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            histogram(&init_off(), &unicode(), &[], 4, Orientation::Horizontal),
+            ""
+        );
+        assert_eq!(
+            histogram(
+                &init_off(),
+                &unicode(),
+                &[f64::NAN],
+                4,
+                Orientation::Vertical
+            ),
+            ""
+        );
+        assert_eq!(
+            histogram(&init_off(), &unicode(), &[1.0], 0, Orientation::Vertical),
+            ""
+        );
+    }
+
+    #[test]
+    fn ascii_fallback() {
+        for (variant, output) in render_all(|codes, caps| {
+            histogram(codes, caps, &[1.0, 2.0], 2, Orientation::Horizontal)
+        }) {
+            let glyphs = variant.charset.glyphs();
+            let plain = crate::ansi::strip(&output);
+            assert!(
+                plain.contains(&format!("1.0 {} 1.5", glyphs.arrow)),
+                "{}",
+                variant
+            );
+            assert!(plain.contains(glyphs.block_full), "{}", variant);
+            assert_eq!(output == plain, variant.color == ColorSupport::NoColor);
+        }
+    }
+}
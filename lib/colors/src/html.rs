@@ -0,0 +1,167 @@
+//! Conversion of styled text to HTML.
+//!
+//! SGR sequences become `<span>` elements with inline styles, all other
+//! escape sequences are dropped. Colors use the built-in palette resembling
+//! common terminal defaults on dark background, see [`Palette::new`], or the
+//! palette of a theme with [`to_html_with`].
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! fs::write("build.html", colors::to_html(&rendered, "Build log"))?;
+//! let theme = colors::Theme::import(&scheme, colors::Format::Base16Yaml)?;
+//! let html = colors::to_html_with(&theme.palette, &rendered, "Build log");
+//! ```
+
+use crate::ansi::{self, Segment};
+use crate::scheme::{hex, Palette};
+use crate::{Attr, Style};
+
+/// Return CSS declarations for style with colors of palette.
+fn css(palette: &Palette, style: &Style) -> String {
+    let color = |color| hex(palette.get(color));
+    let (mut fg, mut bg) = (style.fg.map(color), style.bg.map(color));
+    if style.has(Attr::Reverse) {
+        (fg, bg) = (
+            bg.or_else(|| Some(hex(palette.background))),
+            fg.or_else(|| Some(hex(palette.foreground))),
+        );
+    }
+
+    let mut decls = Vec::new();
+    if let Some(fg) = fg {
+        decls.push(format!("color:{}", fg));
+    }
+    if let Some(bg) = bg {
+        decls.push(format!("background:{}", bg));
+    }
+    if style.has(Attr::Bold) {
+        decls.push("font-weight:bold".to_string());
+    }
+    if style.has(Attr::Dim) {
+        decls.push("opacity:0.6".to_string());
+    }
+    if style.has(Attr::Italic) {
+        decls.push("font-style:italic".to_string());
+    }
+    let mut lines = Vec::new();
+    if style.has(Attr::DoubleUnderline) {
+        lines.push("underline double");
+    } else if style.has(Attr::Underline) {
+        lines.push("underline");
+    }
+    if style.has(Attr::Strikethrough) {
+        lines.push("line-through");
+    }
+    if style.has(Attr::Overline) {
+        lines.push("overline");
+    }
+    if !lines.is_empty() {
+        decls.push(format!("text-decoration:{}", lines.join(" ")));
+    }
+    if style.has(Attr::Hidden) {
+        decls.push("visibility:hidden".to_string());
+    }
+    decls.join(";")
+}
+
+/// Return text with HTML special characters escaped.
+pub(crate) fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// Return styled text as HTML fragment, escaped and with `<span>` elements
+/// for styles. Line breaks are kept, so fragment belongs inside `<pre>`.
+pub fn to_html_fragment(text: &str) -> String {
+    to_html_fragment_with(&Palette::new(), text)
+}
+
+/// Return styled text as HTML fragment like [`to_html_fragment`], with
+/// colors of palette.
+pub fn to_html_fragment_with(palette: &Palette, text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut style = Style::new();
+    for segment in ansi::segments(text) {
+        match segment {
+            Segment::Escape(seq) => style = ansi::apply_sgr(style, seq),
+            Segment::Text(text) if style.is_plain() => output.push_str(&escape(text)),
+            Segment::Text(text) => {
+                output.push_str(&format!(
+                    "<span style=\"{}\">{}</span>",
+                    css(palette, &style),
+                    escape(text)
+                ));
+            }
+        }
+    }
+    output
+}
+
+/// Return styled text as standalone HTML document with title.
+pub fn to_html(text: &str, title: &str) -> String {
+    to_html_with(&Palette::new(), text, title)
+}
+
+/// Return styled text as standalone HTML document like [`to_html`], with
+/// page and text colors of palette, e.g. [`Theme::palette`](crate::Theme).
+pub fn to_html_with(palette: &Palette, text: &str, title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n\
+         <style>\n\
+         body {{ margin: 0; background: {bg}; color: {fg}; }}\n\
+         pre {{ margin: 0; padding: 1em; font-family: monospace; white-space: pre-wrap; }}\n\
+         </style>\n\
+         </head>\n\
+         <body>\n\
+         <pre>{body}</pre>\n\
+         </body>\n\
+         </html>\n",
+        title = escape(title),
+        bg = hex(palette.background),
+        fg = hex(palette.foreground),
+        body = to_html_fragment_with(palette, text),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn fragment_spans() {
+        assert_eq!(
+            to_html_fragment("a<\x1B[1;31mb\x1B[0m&"),
+            format!(
+                "a&lt;<span style=\"color:{};font-weight:bold\">b</span>&amp;",
+                hex(Palette::new().get(Color::Red))
+            )
+        );
+        // Extended colors are shown in nearest palette color.
+        assert!(to_html_fragment("\x1B[38;2;0;0;255mx").contains("<span style=\"color:"));
+    }
+
+    #[test]
+    fn palette_colors() {
+        let mut palette = Palette::new();
+        palette.background = (1, 2, 3);
+        palette.colors[Color::Green as usize] = (0, 0xff, 0);
+        let html = to_html_with(&palette, "\x1B[32mok", "t");
+        assert!(html.contains("background: #010203;"));
+        assert!(html.contains("<span style=\"color:#00ff00\">ok</span>"));
+    }
+}
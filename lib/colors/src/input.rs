@@ -0,0 +1,508 @@
+//! Terminal input events.
+//!
+//! Toggles for terminal reporting modes and parsers turning raw bytes read
+//! from the TTY into events. Parsers take the start of the input buffer and
+//! return the event together with number of bytes consumed, or `None` if the
+//! buffer does not start with a complete sequence of that kind.
+
+/// Enable button, drag and wheel reporting using SGR extended coordinates.
+pub const MOUSE_ENABLE: &str = "\x1B[?1000h\x1B[?1002h\x1B[?1006h";
+/// Disable mouse reporting enabled by [`MOUSE_ENABLE`].
+pub const MOUSE_DISABLE: &str = "\x1B[?1006l\x1B[?1002l\x1B[?1000l";
+
+/// Enable focus in/out reporting.
+pub const FOCUS_ENABLE: &str = "\x1B[?1004h";
+/// Disable focus reporting enabled by [`FOCUS_ENABLE`].
+pub const FOCUS_DISABLE: &str = "\x1B[?1004l";
+
+/// Enable Kitty keyboard protocol with disambiguated escape codes.
+pub const KITTY_ENABLE: &str = "\x1B[>1u";
+/// Restore keyboard mode active before [`KITTY_ENABLE`].
+pub const KITTY_DISABLE: &str = "\x1B[<u";
+/// Query Kitty keyboard protocol flags, answered as `ESC [ ? flags u` by
+/// supporting terminals, see [`parse_kitty_flags`].
+pub const KITTY_QUERY: &str = "\x1B[?u";
+
+/// Modifier keys held during event.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+impl Modifiers {
+    /// Return modifiers from xterm/Kitty parameter, i.e. 1 + bit mask.
+    fn from_param(param: u32) -> Modifiers {
+        let bits = param.saturating_sub(1);
+        Modifiers {
+            shift: bits & 1 != 0,
+            alt: bits & 2 != 0,
+            ctrl: bits & 4 != 0,
+        }
+    }
+}
+
+/// Mouse button or wheel direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    /// Motion without any button pressed.
+    None,
+    WheelUp,
+    WheelDown,
+    WheelLeft,
+    WheelRight,
+    /// Extra buttons, value is raw button number.
+    Other(u16),
+}
+
+/// Kind of mouse event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseAction {
+    Press,
+    Release,
+    Motion,
+}
+
+/// Mouse event with zero based column `x` and row `y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Mouse {
+    pub action: MouseAction,
+    pub button: MouseButton,
+    pub x: u16,
+    pub y: u16,
+    pub modifiers: Modifiers,
+}
+
+/// Parse SGR mouse report, i.e. `ESC [ < b ; x ; y M` or `... m` on release.
+pub fn parse_mouse(input: &[u8]) -> Option<(Mouse, usize)> {
+    let body = input.strip_prefix(b"\x1B[<")?;
+    let end = body.iter().position(|&b| b == b'M' || b == b'm')?;
+    let params = std::str::from_utf8(&body[..end]).ok()?;
+
+    let mut numbers = params.split(';').map(|p| p.parse::<u16>().ok());
+    let code = numbers.next()??;
+    let x = numbers.next()??;
+    let y = numbers.next()??;
+    if numbers.next().is_some() || x == 0 || y == 0 {
+        return None;
+    }
+
+    let modifiers = Modifiers {
+        shift: code & 4 != 0,
+        alt: code & 8 != 0,
+        ctrl: code & 16 != 0,
+    };
+    let button = match (code & 0b1100_0011, code & 64 != 0) {
+        (64, true) => MouseButton::WheelUp,
+        (65, true) => MouseButton::WheelDown,
+        (66, true) => MouseButton::WheelLeft,
+        (67, true) => MouseButton::WheelRight,
+        (0, false) => MouseButton::Left,
+        (1, false) => MouseButton::Middle,
+        (2, false) => MouseButton::Right,
+        (3, false) => MouseButton::None,
+        (other, _) => MouseButton::Other(other),
+    };
+    let action = if body[end] == b'm' {
+        MouseAction::Release
+    } else if code & 32 != 0 {
+        MouseAction::Motion
+    } else {
+        MouseAction::Press
+    };
+
+    let mouse = Mouse {
+        action,
+        button,
+        x: x - 1,
+        y: y - 1,
+        modifiers,
+    };
+    Some((mouse, 3 + end + 1))
+}
+
+/// Key without modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Tab,
+    BackTab,
+    Backspace,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// Function key, starting from 1.
+    F(u8),
+    /// Key without own variant, e.g. media or lone modifier keys, value is
+    /// raw Kitty key code.
+    Other(u32),
+}
+
+/// Key press with modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: Modifiers,
+}
+
+impl Key {
+    /// Return key without modifiers.
+    pub fn new(code: KeyCode) -> Key {
+        Key {
+            code,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn with(code: KeyCode, modifiers: Modifiers) -> Key {
+        Key { code, modifiers }
+    }
+
+    /// Decode key from raw TTY bytes.
+    ///
+    /// Handles control bytes, UTF-8 characters, Alt prefixed characters,
+    /// SS3 and CSI sequences from xterm compatible terminals and `CSI u`
+    /// sequences from Kitty keyboard protocol. Lone ESC is only decoded as
+    /// escape key if nothing follows it in the buffer.
+    pub fn decode(input: &[u8]) -> Option<(Key, usize)> {
+        let first = *input.first()?;
+        if first != 0x1B {
+            return decode_plain(input);
+        }
+
+        match input.get(1) {
+            None => Some((Key::new(KeyCode::Escape), 1)),
+            Some(b'[') => decode_csi(input),
+            Some(b'O') => {
+                let code = match *input.get(2)? {
+                    b'A' => KeyCode::Up,
+                    b'B' => KeyCode::Down,
+                    b'C' => KeyCode::Right,
+                    b'D' => KeyCode::Left,
+                    b'H' => KeyCode::Home,
+                    b'F' => KeyCode::End,
+                    b'P' => KeyCode::F(1),
+                    b'Q' => KeyCode::F(2),
+                    b'R' => KeyCode::F(3),
+                    b'S' => KeyCode::F(4),
+                    _ => return None,
+                };
+                Some((Key::new(code), 3))
+            }
+            Some(0x1B) => Some((Key::new(KeyCode::Escape), 1)),
+            Some(_) => {
+                let (mut key, len) = decode_plain(&input[1..])?;
+                key.modifiers.alt = true;
+                Some((key, len + 1))
+            }
+        }
+    }
+}
+
+/// Decode control byte or UTF-8 character.
+fn decode_plain(input: &[u8]) -> Option<(Key, usize)> {
+    let first = *input.first()?;
+    let ctrl = Modifiers {
+        ctrl: true,
+        ..Modifiers::default()
+    };
+    let key = match first {
+        b'\r' | b'\n' => Key::new(KeyCode::Enter),
+        b'\t' => Key::new(KeyCode::Tab),
+        0x7F | 0x08 => Key::new(KeyCode::Backspace),
+        0x00 => Key::with(KeyCode::Char(' '), ctrl),
+        0x01..=0x1A => Key::with(KeyCode::Char((first - 1 + b'a') as char), ctrl),
+        0x1B => Key::new(KeyCode::Escape),
+        0x1C..=0x1F => Key::with(KeyCode::Char((first - 0x1C + b'4') as char), ctrl),
+        _ => {
+            let len = match first {
+                0x00..=0x7F => 1,
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => return None,
+            };
+            let c = std::str::from_utf8(input.get(..len)?)
+                .ok()?
+                .chars()
+                .next()?;
+            return Some((Key::new(KeyCode::Char(c)), len));
+        }
+    };
+    Some((key, 1))
+}
+
+/// Decode `ESC [ params final` sequence.
+fn decode_csi(input: &[u8]) -> Option<(Key, usize)> {
+    let body = &input[2..];
+    let end = body.iter().position(|b| (0x40..=0x7E).contains(b))?;
+    let len = 2 + end + 1;
+    let params = std::str::from_utf8(&body[..end]).ok()?;
+    let mut numbers = params
+        .split(';')
+        .map(|p| p.split(':').next().unwrap_or("").parse::<u32>().ok());
+    let first = numbers.next().flatten();
+    let modifiers = Modifiers::from_param(numbers.next().flatten().unwrap_or(1));
+
+    let code = match body[end] {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        b'Z' => KeyCode::BackTab,
+        b'~' => match first? {
+            1 | 7 => KeyCode::Home,
+            2 => KeyCode::Insert,
+            3 => KeyCode::Delete,
+            4 | 8 => KeyCode::End,
+            5 => KeyCode::PageUp,
+            6 => KeyCode::PageDown,
+            n @ 11..=15 => KeyCode::F((n - 10) as u8),
+            n @ 17..=21 => KeyCode::F((n - 11) as u8),
+            n @ 23..=24 => KeyCode::F((n - 12) as u8),
+            _ => return None,
+        },
+        // Kitty keyboard protocol, key is Unicode code point.
+        b'u' => match first? {
+            9 => KeyCode::Tab,
+            13 => KeyCode::Enter,
+            27 => KeyCode::Escape,
+            127 => KeyCode::Backspace,
+            n @ KITTY_FUNCTIONAL_START..=KITTY_FUNCTIONAL_END => kitty_functional(n),
+            n => KeyCode::Char(char::from_u32(n)?),
+        },
+        _ => return None,
+    };
+    Some((Key::with(code, modifiers), len))
+}
+
+/// First and last code point of private use area used by Kitty keyboard
+/// protocol for functional keys.
+const KITTY_FUNCTIONAL_START: u32 = 0xE000;
+const KITTY_FUNCTIONAL_END: u32 = 0xF8FF;
+
+/// Return key of Kitty functional key code, keypad keys are decoded as the
+/// keys they produce.
+fn kitty_functional(n: u32) -> KeyCode {
+    match n {
+        57376..=57398 => KeyCode::F((n - 57376 + 13) as u8),
+        57399..=57408 => KeyCode::Char((b'0' + (n - 57399) as u8) as char),
+        57409 => KeyCode::Char('.'),
+        57410 => KeyCode::Char('/'),
+        57411 => KeyCode::Char('*'),
+        57412 => KeyCode::Char('-'),
+        57413 => KeyCode::Char('+'),
+        57414 => KeyCode::Enter,
+        57415 => KeyCode::Char('='),
+        57416 => KeyCode::Char(','),
+        57417 => KeyCode::Left,
+        57418 => KeyCode::Right,
+        57419 => KeyCode::Up,
+        57420 => KeyCode::Down,
+        57421 => KeyCode::PageUp,
+        57422 => KeyCode::PageDown,
+        57423 => KeyCode::Home,
+        57424 => KeyCode::End,
+        57425 => KeyCode::Insert,
+        57426 => KeyCode::Delete,
+        n => KeyCode::Other(n),
+    }
+}
+
+/// Parse reply to [`KITTY_QUERY`] and return enabled protocol flags.
+pub fn parse_kitty_flags(input: &[u8]) -> Option<(u32, usize)> {
+    let body = input.strip_prefix(b"\x1B[?")?;
+    let end = body.iter().position(|&b| b == b'u')?;
+    let flags = std::str::from_utf8(&body[..end]).ok()?.parse().ok()?;
+    Some((flags, 3 + end + 1))
+}
+
+/// Terminal window focus change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Focus {
+    Gained,
+    Lost,
+}
+
+/// Parse focus report, i.e. `ESC [ I` or `ESC [ O`.
+pub fn parse_focus(input: &[u8]) -> Option<(Focus, usize)> {
+    match input.strip_prefix(b"\x1B[")?.first()? {
+        b'I' => Some((Focus::Gained, 3)),
+        b'O' => Some((Focus::Lost, 3)),
+        _ => None,
+    }
+}
+
+/// Decoded input event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    Key(Key),
+    Mouse(Mouse),
+    Focus(Focus),
+}
+
+/// Parse next event from raw TTY bytes.
+pub fn parse(input: &[u8]) -> Option<(Event, usize)> {
+    if let Some((mouse, len)) = parse_mouse(input) {
+        return Some((Event::Mouse(mouse), len));
+    }
+    if let Some((focus, len)) = parse_focus(input) {
+        return Some((Event::Focus(focus), len));
+    }
+    Key::decode(input).map(|(key, len)| (Event::Key(key), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, shift: bool, alt: bool, ctrl: bool) -> Key {
+        Key::with(code, Modifiers { shift, alt, ctrl })
+    }
+
+    #[test]
+    fn decode_plain_keys() {
+        assert_eq!(Key::decode(b"a"), Some((Key::new(KeyCode::Char('a')), 1)));
+        assert_eq!(
+            Key::decode(b"\x03"),
+            Some((key(KeyCode::Char('c'), false, false, true), 1))
+        );
+        assert_eq!(
+            Key::decode(b"\x1Bx"),
+            Some((key(KeyCode::Char('x'), false, true, false), 2))
+        );
+        assert_eq!(
+            Key::decode("界".as_bytes()),
+            Some((Key::new(KeyCode::Char('界')), 3))
+        );
+        assert_eq!(Key::decode(b"\x1B"), Some((Key::new(KeyCode::Escape), 1)));
+    }
+
+    #[test]
+    fn decode_csi_keys() {
+        assert_eq!(Key::decode(b"\x1B[A"), Some((Key::new(KeyCode::Up), 3)));
+        assert_eq!(
+            Key::decode(b"\x1B[1;5C"),
+            Some((key(KeyCode::Right, false, false, true), 6))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[24~"),
+            Some((Key::new(KeyCode::F(12)), 5))
+        );
+        assert_eq!(Key::decode(b"\x1BOP"), Some((Key::new(KeyCode::F(1)), 3)));
+        assert_eq!(
+            Key::decode(b"\x1B[Z"),
+            Some((Key::new(KeyCode::BackTab), 3))
+        );
+    }
+
+    #[test]
+    fn decode_kitty_keys() {
+        assert_eq!(
+            Key::decode(b"\x1B[97;5u"),
+            Some((key(KeyCode::Char('a'), false, false, true), 7))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[97:65;2u"),
+            Some((key(KeyCode::Char('a'), true, false, false), 10))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[27u"),
+            Some((Key::new(KeyCode::Escape), 5))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[57376u"),
+            Some((Key::new(KeyCode::F(13)), 8))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[57399u"),
+            Some((Key::new(KeyCode::Char('0')), 8))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[57414u"),
+            Some((Key::new(KeyCode::Enter), 8))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[57419;3u"),
+            Some((key(KeyCode::Up, false, true, false), 10))
+        );
+        assert_eq!(
+            Key::decode(b"\x1B[57441;2u"),
+            Some((key(KeyCode::Other(57441), true, false, false), 10))
+        );
+        assert_eq!(parse_kitty_flags(b"\x1B[?1u"), Some((1, 5)));
+    }
+
+    #[test]
+    fn parse_mouse_reports() {
+        let (mouse, len) = parse_mouse(b"\x1B[<0;10;5M").unwrap();
+        assert_eq!(len, 10);
+        assert_eq!(
+            mouse,
+            Mouse {
+                action: MouseAction::Press,
+                button: MouseButton::Left,
+                x: 9,
+                y: 4,
+                modifiers: Modifiers::default(),
+            }
+        );
+        let (mouse, _) = parse_mouse(b"\x1B[<2;1;1m").unwrap();
+        assert_eq!(
+            (mouse.action, mouse.button),
+            (MouseAction::Release, MouseButton::Right)
+        );
+        let (mouse, _) = parse_mouse(b"\x1B[<32;3;4M").unwrap();
+        assert_eq!(
+            (mouse.action, mouse.button),
+            (MouseAction::Motion, MouseButton::Left)
+        );
+        let (mouse, _) = parse_mouse(b"\x1B[<81;1;1M").unwrap();
+        assert_eq!(mouse.button, MouseButton::WheelDown);
+        assert!(mouse.modifiers.ctrl);
+        assert_eq!(parse_mouse(b"\x1B[<0;0;1M"), None);
+    }
+
+    #[test]
+    fn parse_focus_reports() {
+        assert_eq!(parse_focus(b"\x1B[I"), Some((Focus::Gained, 3)));
+        assert_eq!(parse_focus(b"\x1B[Ox"), Some((Focus::Lost, 3)));
+        assert_eq!(parse(b"\x1B[O"), Some((Event::Focus(Focus::Lost), 3)));
+        assert_eq!(parse(b"\x1B[I"), Some((Event::Focus(Focus::Gained), 3)));
+    }
+
+    #[test]
+    fn truncated_input() {
+        for input in [
+            &b""[..],
+            b"\x1B[",
+            b"\x1B[1;5",
+            b"\x1B[97;5",
+            b"\x1B[<0;10",
+            b"\x1B[<0;10;5",
+            b"\x1BO",
+            b"\xE2\x82",
+        ] {
+            assert_eq!(parse(input), None, "{:?}", input);
+        }
+    }
+}
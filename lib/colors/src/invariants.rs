@@ -0,0 +1,110 @@
+//! Invariants of rendering functions as predicates.
+//!
+//! Every predicate returns `true` if the invariant holds for the given input.
+//! They are meant for property based tests of code building on this crate,
+//! where inputs can be arbitrary strings including broken escape sequences.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! proptest! {
+//!     #[test]
+//!     fn width(s in ".*") {
+//!         prop_assert!(colors::invariants::width_ignores_escapes(&s));
+//!     }
+//! }
+//! ```
+
+use crate::ansi::{strip, transcode};
+use crate::width::{display_width, text_width, truncate};
+use crate::{reset_lines, Codes, Style};
+
+/// Check that visible width of text equals width of text with escape
+/// sequences stripped.
+pub fn width_ignores_escapes(text: &str) -> bool {
+    display_width(text) == text_width(&strip(text))
+}
+
+/// Check that stripping escape sequences is idempotent.
+pub fn strip_idempotent(text: &str) -> bool {
+    let once = strip(text);
+    strip(&once) == once
+}
+
+/// Check that transcoding styles is idempotent and keeps visible text, see
+/// [`transcode`].
+pub fn transcode_idempotent(codes: &Codes, text: &str) -> bool {
+    let once = transcode(codes, text);
+    transcode(codes, &once) == once && strip(&once) == strip(text)
+}
+
+/// Check that per-line reset rewriting is idempotent and keeps visible text.
+pub fn reset_lines_idempotent(codes: &Codes, text: &str) -> bool {
+    let once = reset_lines(codes, text);
+    reset_lines(codes, &once) == once && strip(&once) == strip(text)
+}
+
+/// Check that style survives rendering to SGR sequence and parsing back.
+pub fn style_round_trip(style: &Style) -> bool {
+    Style::from_sgr(style.to_sgr().as_str()) == *style
+}
+
+/// Check that truncated text fits in `max` columns, provided ellipsis does.
+pub fn truncate_fits(text: &str, max: usize, ellipsis: &str) -> bool {
+    display_width(ellipsis) > max || display_width(&truncate(text, max, ellipsis)) <= max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_off, init_on_with, Attr, Color, ColorSupport};
+
+    const INPUTS: [&str; 8] = [
+        "",
+        "plain",
+        "\x1B[1;31mbold red\x1B[0m plain",
+        "\x1B[31ma\x1B[31mb\x1B[1mc\x1B[22;39md",
+        "\x1B]8;;https://example.com\x1B\\link\x1B]8;;\x1B\\",
+        "\x1B[32mline\none\x1B[m\n",
+        "broken \x1B[31",
+        "wide 世界 \x1B[7m\u{1F600}\x1B[27m",
+    ];
+
+    #[test]
+    fn predicates_hold() {
+        let style = Style::new()
+            .fg(Color::Red)
+            .bg(Color::BrightBlue)
+            .attr(Attr::Bold);
+        assert!(style_round_trip(&style));
+        for codes in [init_off(), init_on_with(ColorSupport::Ansi16)] {
+            for text in INPUTS {
+                assert!(width_ignores_escapes(text), "{:?}", text);
+                assert!(strip_idempotent(text), "{:?}", text);
+                assert!(transcode_idempotent(&codes, text), "{:?}", text);
+                assert!(reset_lines_idempotent(&codes, text), "{:?}", text);
+                assert!(truncate_fits(text, 4, "…"), "{:?}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn transcode_merges_runs() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        assert_eq!(
+            transcode(&codes, "\x1B[31ma\x1B[31mb\x1B[1mc\x1B[0m"),
+            format!(
+                "{}{}",
+                Style::new().fg(Color::Red).paint(&codes, "ab"),
+                Style::new()
+                    .fg(Color::Red)
+                    .attr(Attr::Bold)
+                    .paint(&codes, "c")
+            )
+        );
+        assert_eq!(
+            transcode(&init_off(), "\x1B[31mred\x1B[0m\x1B[2K"),
+            "red\x1B[2K"
+        );
+    }
+}
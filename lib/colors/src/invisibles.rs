@@ -0,0 +1,44 @@
+//! Visualization of control characters.
+//!
+//! Untrusted data, e.g. file contents or network input, may contain control
+//! characters and escape sequences that would otherwise be interpreted by the
+//! terminal. They are replaced with dimmed symbols instead: Unicode control
+//! pictures like `␛` or caret notation like `^[` if the charset is ASCII.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! println!("{}", colors::show_invisibles(&ansi, &untrusted));
+//! ```
+
+use crate::{Attr, Charset, Codes, Color, Style};
+
+/// Return symbol for control character, or `None` for other characters.
+fn symbol(c: char, charset: Charset) -> Option<String> {
+    let code = c as u32;
+    match (code, charset) {
+        (0x00..=0x1F, Charset::Unicode) => char::from_u32(0x2400 + code).map(String::from),
+        (0x7F, Charset::Unicode) => Some("\u{2421}".to_string()),
+        (0x00..=0x1F, Charset::Ascii) => Some(format!("^{}", (code as u8 + 0x40) as char)),
+        (0x7F, Charset::Ascii) => Some("^?".to_string()),
+        (0x80..=0x9F, _) => Some(format!("<{:02X}>", code)),
+        _ => None,
+    }
+}
+
+/// Return text with control characters, except newline, replaced by dimmed
+/// symbols.
+pub fn show_invisibles(codes: &Codes, text: &str) -> String {
+    let charset = Charset::detect();
+    let style = Style::new().fg(Color::BrightBlack).attr(Attr::Dim);
+
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match symbol(c, charset) {
+            Some(symbol) if c != '\n' => output.push_str(&style.paint(codes, &symbol)),
+            _ => output.push(c),
+        }
+    }
+    output
+}
@@ -0,0 +1,129 @@
+//! Lazy styling of iterator items.
+//!
+//! Items of any iterator of displayable values are paired with styles as
+//! they are consumed, without collecting intermediate strings, e.g. to join
+//! lists or build striped table rows.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! use colors::StyleIter;
+//!
+//! let ansi = colors::init_auto();
+//! let bold = colors::Style::new().attr(colors::Attr::Bold);
+//! for name in names.iter().styled(&ansi, bold) {
+//!     print!("{} ", name);
+//! }
+//! let rows: Vec<String> = rows.alternate(&ansi, even, odd).map(|r| r.to_string()).collect();
+//! ```
+
+use std::fmt;
+
+use crate::{Codes, Style};
+
+/// Item paired with style, displayed as is if colors are disabled.
+#[derive(Clone, Copy, Debug)]
+pub struct StyledItem<'a, T> {
+    codes: &'a Codes,
+    style: Style,
+    item: T,
+}
+
+impl<'a, T> StyledItem<'a, T> {
+    /// Return item paired with style.
+    pub fn new(codes: &'a Codes, style: Style, item: T) -> StyledItem<'a, T> {
+        StyledItem { codes, style, item }
+    }
+
+    /// Return item without style.
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for StyledItem<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.codes.is_enabled() || self.style.is_plain() {
+            return self.item.fmt(f);
+        }
+        f.write_str(&self.codes.wrap(self.style.to_sgr().as_str()))?;
+        self.item.fmt(f)?;
+        f.write_str(&self.codes.attr.reset)
+    }
+}
+
+/// Iterator styling every item, see [`StyleIter::styled`].
+#[derive(Clone, Debug)]
+pub struct StyledItems<'a, I> {
+    codes: &'a Codes,
+    style: Style,
+    iter: I,
+}
+
+impl<'a, I: Iterator> Iterator for StyledItems<'a, I> {
+    type Item = StyledItem<'a, I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        Some(StyledItem::new(self.codes, self.style, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator styling items with two alternating styles, see
+/// [`StyleIter::alternate`].
+#[derive(Clone, Debug)]
+pub struct Alternate<'a, I> {
+    codes: &'a Codes,
+    styles: [Style; 2],
+    iter: I,
+    odd: bool,
+}
+
+impl<'a, I: Iterator> Iterator for Alternate<'a, I> {
+    type Item = StyledItem<'a, I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let style = self.styles[usize::from(self.odd)];
+        self.odd = !self.odd;
+        Some(StyledItem::new(self.codes, style, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Adapters styling items of iterators lazily.
+pub trait StyleIter: IntoIterator + Sized {
+    /// Return iterator of items styled with `style`.
+    fn styled(self, codes: &Codes, style: Style) -> StyledItems<'_, Self::IntoIter> {
+        StyledItems {
+            codes,
+            style,
+            iter: self.into_iter(),
+        }
+    }
+
+    /// Return iterator of items styled with `first` and `second` in turn,
+    /// starting with `first`.
+    fn alternate(
+        self,
+        codes: &Codes,
+        first: Style,
+        second: Style,
+    ) -> Alternate<'_, Self::IntoIter> {
+        Alternate {
+            codes,
+            styles: [first, second],
+            iter: self.into_iter(),
+            odd: false,
+        }
+    }
+}
+
+impl<I: IntoIterator> StyleIter for I where I::Item: fmt::Display {}
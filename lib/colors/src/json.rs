@@ -0,0 +1,127 @@
+//! Minimal JSON output helpers.
+//!
+//! Only what is needed for writing machine-readable output: string escaping
+//! and an object writer producing one line per object. Escape sequences are
+//! removed from all string values.
+
+use std::fmt::Write;
+
+use crate::ansi;
+
+/// Return JSON string literal, including quotes.
+pub(crate) fn string(value: &str) -> String {
+    quote(&ansi::strip(value))
+}
+
+/// Return JSON string literal, including quotes, keeping escape sequences.
+pub(crate) fn quote(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// Return JSON array of strings.
+pub(crate) fn string_array<S: AsRef<str>>(values: &[S]) -> String {
+    let values: Vec<String> = values.iter().map(|v| string(v.as_ref())).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// JSON object built field by field.
+pub(crate) struct Object {
+    fields: Vec<String>,
+}
+
+impl Object {
+    pub(crate) fn new() -> Object {
+        Object { fields: Vec::new() }
+    }
+
+    /// Add field with string value.
+    pub(crate) fn string(mut self, key: &str, value: &str) -> Object {
+        self.fields
+            .push(format!("{}:{}", string(key), string(value)));
+        self
+    }
+
+    /// Add field with string value if present.
+    pub(crate) fn opt_string(self, key: &str, value: Option<&str>) -> Object {
+        match value {
+            Some(value) => self.string(key, value),
+            None => self,
+        }
+    }
+
+    /// Add field with number value if present.
+    pub(crate) fn opt_number<N: std::fmt::Display>(
+        mut self,
+        key: &str,
+        value: Option<N>,
+    ) -> Object {
+        if let Some(value) = value {
+            self.fields.push(format!("{}:{}", string(key), value));
+        }
+        self
+    }
+
+    /// Add field with already encoded JSON value.
+    pub(crate) fn raw(mut self, key: &str, value: &str) -> Object {
+        self.fields.push(format!("{}:{}", string(key), value));
+        self
+    }
+
+    /// Return encoded object.
+    pub(crate) fn finish(self) -> String {
+        format!("{{{}}}", self.fields.join(","))
+    }
+}
+
+/// Parse JSON string literal at start of input, return value and rest of
+/// input.
+pub(crate) fn parse_string(input: &str) -> Option<(String, &str)> {
+    let mut chars = input.strip_prefix('"')?.char_indices();
+    let mut output = String::new();
+    let hex = |chars: &mut std::str::CharIndices| -> Option<u32> {
+        let digits: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+        u32::from_str_radix(&digits, 16).ok()
+    };
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((output, &input[i + 2..])),
+            '\\' => match chars.next()?.1 {
+                'n' => output.push('\n'),
+                'r' => output.push('\r'),
+                't' => output.push('\t'),
+                'b' => output.push('\u{8}'),
+                'f' => output.push('\u{c}'),
+                'u' => {
+                    let mut code = hex(&mut chars)?;
+                    if (0xD800..0xDC00).contains(&code) {
+                        // Surrogate pair, next escape holds low half.
+                        chars.next().filter(|(_, c)| *c == '\\')?;
+                        chars.next().filter(|(_, c)| *c == 'u')?;
+                        let low = hex(&mut chars)?;
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low.checked_sub(0xDC00)?);
+                    }
+                    output.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                }
+                c => output.push(c),
+            },
+            c => output.push(c),
+        }
+    }
+    None
+}
@@ -0,0 +1,73 @@
+//! Composition of rendered blocks.
+//!
+//! A block is any multi-line string, e.g. banner or other rendered output.
+//! Blocks are padded using visible width so escape sequences and wide
+//! characters do not break alignment. Padding is painted with a fill style,
+//! use [`Style::new`] for plain spaces.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let fill = colors::Style::new();
+//! let row = colors::layout::hstack(&ansi, &[left, right], 2, &fill);
+//! print!("{}", colors::layout::vstack(&ansi, &[header, row], &fill));
+//! ```
+
+use crate::width::display_width;
+use crate::{Codes, Style};
+
+/// Return lines of block and its widest visible width.
+fn measure(block: &str) -> (Vec<&str>, usize) {
+    let lines: Vec<&str> = block.lines().collect();
+    let width = lines.iter().map(|l| display_width(l)).max().unwrap_or(0);
+    (lines, width)
+}
+
+/// Return `n` spaces painted with fill style.
+fn pad(codes: &Codes, n: usize, fill: &Style) -> String {
+    if n == 0 {
+        return String::new();
+    }
+    fill.paint(codes, &" ".repeat(n))
+}
+
+/// Return blocks placed side by side with `gap` columns between them.
+///
+/// Every block is padded to its widest line and shorter blocks are padded
+/// with empty lines at the bottom.
+pub fn hstack<S: AsRef<str>>(codes: &Codes, blocks: &[S], gap: usize, fill: &Style) -> String {
+    let measured: Vec<_> = blocks.iter().map(|b| measure(b.as_ref())).collect();
+    let height = measured.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+
+    let mut output = String::new();
+    for row in 0..height {
+        for (i, (lines, width)) in measured.iter().enumerate() {
+            if i > 0 {
+                output.push_str(&pad(codes, gap, fill));
+            }
+            let line = lines.get(row).copied().unwrap_or("");
+            output.push_str(line);
+            output.push_str(&pad(codes, width - display_width(line), fill));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Return blocks placed on top of each other with every line padded to the
+/// widest line of all blocks.
+pub fn vstack<S: AsRef<str>>(codes: &Codes, blocks: &[S], fill: &Style) -> String {
+    let measured: Vec<_> = blocks.iter().map(|b| measure(b.as_ref())).collect();
+    let width = measured.iter().map(|(_, w)| *w).max().unwrap_or(0);
+
+    let mut output = String::new();
+    for (lines, _) in &measured {
+        for line in lines {
+            output.push_str(line);
+            output.push_str(&pad(codes, width - display_width(line), fill));
+            output.push('\n');
+        }
+    }
+    output
+}
@@ -0,0 +1,40 @@
+//! Legends describing styles used in charts and heatmaps.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let ok = Style::new().fg(Color::Green);
+//! let failed = Style::new().fg(Color::Red);
+//! println!("{}", colors::legend(&ansi, &[("passed", ok), ("failed", failed)]));
+//! ```
+
+use crate::{Charset, Codes, Style};
+
+/// Return style drawing swatch of style as foreground blocks, background
+/// color is used if style has no foreground color.
+fn swatch_style(style: &Style) -> Style {
+    match (style.fg, style.bg) {
+        (Some(fg), _) => Style::new().fg(fg),
+        (None, Some(bg)) => Style::new().fg(bg),
+        (None, None) => *style,
+    }
+}
+
+/// Return legend on one line with every label preceded by a swatch of its
+/// style, e.g. `██ passed  ██ failed`. Only labels are returned if colors are
+/// disabled in `codes`, since swatches would all look the same.
+pub fn legend(codes: &Codes, items: &[(&str, Style)]) -> String {
+    let block = Charset::detect().glyphs().block_full.repeat(2);
+    let entries: Vec<String> = items
+        .iter()
+        .map(|(label, style)| {
+            if codes.is_enabled() {
+                format!("{} {}", swatch_style(style).paint(codes, &block), label)
+            } else {
+                label.to_string()
+            }
+        })
+        .collect();
+    entries.join("  ")
+}
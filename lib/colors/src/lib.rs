@@ -2,14 +2,23 @@
 //!
 //! Contains functions that generate data structure with preset terminal color
 //! and attribute string values to allow for easy use with standard print
-//! functions. ANSI 16 colors and basic style attributes only. By default all
-//! values are set to empty string if `NO_COLOR` environment variable is set or
-//! if program is not running inside of interactive TTY, i.e. colors are
-//! automatically disabled during redirection or piping.
+//! functions. ANSI 16 colors and basic style attributes, plus xterm 256 color
+//! palette and 24-bit RGB colors through methods like [`Codes::fg_256`] and
+//! [`Codes::fg_rgb`], downgraded to what the terminal supports, see
+//! [`convert`]. By default all values are set to empty string if `NO_COLOR`
+//! environment variable is set or if program is not running inside of
+//! interactive TTY, i.e. colors are automatically disabled during redirection
+//! or piping. `FORCE_COLOR`, `CLICOLOR_FORCE` and `CLICOLOR` are honored as
+//! well, see [`ColorChoice`].
 //!
 //! Use function [`init_auto`] for recommended default behaviour. Functions
 //! [`init_on`] and [`init_off`] can be used to enforce specific behaviour,
-//! e.g. to support implementation of `--color=on/off` argument.
+//! or [`init_with`] to support implementation of `--color=auto/always/never`
+//! argument. Function [`global`] returns codes shared by whole program
+//! instead of passing them around, [`set_global`] overrides its mode. Module
+//! [`consts`] has the same values as `&'static str` for
+//! allocation free and `const` use. Values can also be selected at runtime,
+//! e.g. `Fg(Color::ALL[i]).code(&ansi)`, see [`Fg`], [`Bg`] and [`Attr`].
 //!
 //! Structure:
 //!
@@ -18,10 +27,24 @@
 //! |-- attr
 //! |   |-- blink
 //! |   |-- bold
+//! |   |-- dim
+//! |   |-- double_underline
+//! |   |-- hidden
 //! |   |-- italic
+//! |   |-- overline
 //! |   |-- reset
 //! |   |-- reverse
-//! |   `-- underline
+//! |   |-- strikethrough
+//! |   |-- underline
+//! |   |-- no_blink
+//! |   |-- no_bold
+//! |   |-- no_dim
+//! |   |-- no_hidden
+//! |   |-- no_italic
+//! |   |-- no_overline
+//! |   |-- no_reverse
+//! |   |-- no_strikethrough
+//! |   `-- no_underline
 //! |-- bg
 //! |   |-- black
 //! |   |-- blue
@@ -38,7 +61,8 @@
 //! |   |-- bright_magenta
 //! |   |-- bright_red
 //! |   |-- bright_white
-//! |   `-- bright_yellow
+//! |   |-- bright_yellow
+//! |   `-- reset
 //! `-- fg
 //!     |-- black
 //!     |-- blue
@@ -55,7 +79,8 @@
 //!     |-- bright_magenta
 //!     |-- bright_red
 //!     |-- bright_white
-//!     `-- bright_yellow
+//!     |-- bright_yellow
+//!     `-- reset
 //! ```
 //!
 //! Usage:
@@ -70,20 +95,212 @@
 //! License: BSD 3-Clause
 
 use std::env;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+#[macro_use]
+mod macros;
+
+mod animate;
+mod appearance;
+mod art;
+mod audit;
+mod banner;
+mod buf;
+mod calendar;
+mod caps;
+mod config;
+mod contrast;
+mod delta;
+mod diagnostic;
+mod diff;
+mod gradient;
+mod histogram;
+mod html;
+mod invisibles;
+mod iter;
+mod json;
+mod legend;
+mod lines;
+mod link;
+mod logger;
+mod marker;
+mod markup;
+mod number;
+mod output;
+mod pager;
+mod policy;
+mod preview;
+mod profile;
+mod pty;
+mod record;
+mod replay;
+mod report;
+mod secret;
+mod signal;
+mod stack;
+mod style;
+mod swatch;
+mod terminfo;
+mod theme;
+mod ticker;
+mod timeline;
+mod typewriter;
+mod vterm;
+#[cfg(windows)]
+mod windows;
+mod wrapper;
+mod writer;
+
+pub mod ansi;
+pub mod bench;
+pub mod consts;
+pub mod convert;
+pub mod cursor;
+pub mod degradation;
+pub mod emit;
+pub mod input;
+pub mod invariants;
+pub mod layout;
+pub mod scheme;
+pub mod screen;
+pub mod stats;
+pub mod sync;
+pub mod term;
+pub mod width;
+
+pub use animate::animate;
+pub use ansi::{strip, strip_bytes};
+pub use appearance::{os_appearance, Appearance, BackgroundKind};
+pub use art::{render_ans, Sauce};
+pub use audit::{audit, Issue, IssueKind};
+pub use banner::{banner, Font};
+pub use buf::RenderBuf;
+pub use calendar::calendar;
+pub use caps::{Capabilities, Charset, ColorSupport, Glyphs, Host, Sink};
+pub use config::{config, config_errors, set_config, Config, ParseConfigError};
+pub use contrast::{contrast_fg, contrast_fg_256};
+pub use delta::{delta, delta_badge};
+pub use diagnostic::{problem_matcher, to_sarif, Diagnostic};
+pub use diff::expect_diff;
+pub use emit::{reset_semantic, set_semantic, Level};
+pub use gradient::gradient;
+pub use histogram::{histogram, Orientation};
+pub use html::{to_html, to_html_fragment, to_html_fragment_with, to_html_with};
+pub use invisibles::show_invisibles;
+pub use iter::{Alternate, StyleIter, StyledItem, StyledItems};
+pub use legend::legend;
+pub use lines::reset_lines;
+pub use link::{
+    editor_link, file_url, hyperlinks_supported, link_location, link_path, set_editor_link,
+    EditorLink,
+};
+pub use logger::{component_color, Logger};
+pub use marker::{markers, region, set_markers, Markers};
+pub use markup::markup;
+#[doc(hidden)]
+pub use markup::{markup_literals as __markup_literals, Literal as __Literal};
+pub use number::{fmt_float, fmt_int, sign_style};
+pub use output::{Output, Verbosity};
+pub use pager::{color_pager, color_pager_fd, set_pager_detection};
+pub use policy::{ColorChoice, ColorMode, ParseColorModeError};
+pub use preview::{dry_run_style, preview, preview_text, DRY_RUN_BADGE};
+pub use profile::{profile, set_profile, ParseProfileError, Profile};
+pub use pty::{capture_pty, Captured};
+pub use record::Recorder;
+pub use replay::replay;
+pub use report::Report;
+pub use secret::Secret;
+pub use stack::StyleStack;
+pub use style::{
+    parse_style, Attr, Bg, Color, Fg, ParseAttrError, ParseColorError, ParseStyleError, Sgr, Style,
+    StyleBuilder, Styled,
+};
+pub use swatch::swatch;
+pub use theme::{annotate, high_contrast_requested, Theme};
+pub use ticker::{max_fps, set_max_fps, Ticker};
+pub use timeline::{timeline, timeline_width, TimeSpan};
+pub use typewriter::typewriter;
+pub use vterm::VirtualTerm;
+pub use width::display_width;
+pub use wrapper::CodeWrapper;
+pub use writer::ColoredWriter;
 
 /// Terminal style attributes.
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Attributes {
     pub blink: String,
     pub bold: String,
+    pub dim: String,
+    pub double_underline: String,
+    pub hidden: String,
     pub italic: String,
+    pub overline: String,
     pub reset: String,
     pub reverse: String,
+    pub strikethrough: String,
     pub underline: String,
+
+    /// Turn off single attribute, leaving colors and other attributes as is.
+    /// Bold and dim share `no_bold` and `no_dim`, both underline styles share
+    /// `no_underline`.
+    pub no_blink: String,
+    pub no_bold: String,
+    pub no_dim: String,
+    pub no_hidden: String,
+    pub no_italic: String,
+    pub no_overline: String,
+    pub no_reverse: String,
+    pub no_strikethrough: String,
+    pub no_underline: String,
+}
+
+impl Attributes {
+    /// Return value enabling attribute, e.g. `bold` for [`Attr::Bold`].
+    pub fn get(&self, attr: Attr) -> &str {
+        match attr {
+            Attr::Bold => &self.bold,
+            Attr::Dim => &self.dim,
+            Attr::Italic => &self.italic,
+            Attr::Underline => &self.underline,
+            Attr::Blink => &self.blink,
+            Attr::Reverse => &self.reverse,
+            Attr::Hidden => &self.hidden,
+            Attr::Strikethrough => &self.strikethrough,
+            Attr::DoubleUnderline => &self.double_underline,
+            Attr::Overline => &self.overline,
+        }
+    }
+
+    /// Return mutable references to all values.
+    fn values_mut(&mut self) -> Vec<&mut String> {
+        vec![
+            &mut self.blink,
+            &mut self.bold,
+            &mut self.dim,
+            &mut self.double_underline,
+            &mut self.hidden,
+            &mut self.italic,
+            &mut self.overline,
+            &mut self.reset,
+            &mut self.reverse,
+            &mut self.strikethrough,
+            &mut self.underline,
+            &mut self.no_blink,
+            &mut self.no_bold,
+            &mut self.no_dim,
+            &mut self.no_hidden,
+            &mut self.no_italic,
+            &mut self.no_overline,
+            &mut self.no_reverse,
+            &mut self.no_strikethrough,
+            &mut self.no_underline,
+        ]
+    }
 }
 
 /// Terminal background & foreground colors.
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Colors {
     pub black: String,
     pub blue: String,
@@ -102,91 +319,360 @@ pub struct Colors {
     pub bright_red: String,
     pub bright_white: String,
     pub bright_yellow: String,
+
+    /// Return to default color, leaving attributes as is.
+    pub reset: String,
+}
+
+impl Colors {
+    /// Return value of color, e.g. `red` for [`Color::Red`].
+    pub fn get(&self, color: Color) -> &str {
+        match color {
+            Color::Black => &self.black,
+            Color::Red => &self.red,
+            Color::Green => &self.green,
+            Color::Yellow => &self.yellow,
+            Color::Blue => &self.blue,
+            Color::Magenta => &self.magenta,
+            Color::Cyan => &self.cyan,
+            Color::White => &self.white,
+            Color::BrightBlack => &self.bright_black,
+            Color::BrightRed => &self.bright_red,
+            Color::BrightGreen => &self.bright_green,
+            Color::BrightYellow => &self.bright_yellow,
+            Color::BrightBlue => &self.bright_blue,
+            Color::BrightMagenta => &self.bright_magenta,
+            Color::BrightCyan => &self.bright_cyan,
+            Color::BrightWhite => &self.bright_white,
+        }
+    }
+
+    /// Return mutable references to all values.
+    fn values_mut(&mut self) -> Vec<&mut String> {
+        vec![
+            &mut self.black,
+            &mut self.blue,
+            &mut self.cyan,
+            &mut self.green,
+            &mut self.magenta,
+            &mut self.red,
+            &mut self.white,
+            &mut self.yellow,
+            &mut self.bright_black,
+            &mut self.bright_blue,
+            &mut self.bright_cyan,
+            &mut self.bright_green,
+            &mut self.bright_magenta,
+            &mut self.bright_red,
+            &mut self.bright_white,
+            &mut self.bright_yellow,
+            &mut self.reset,
+        ]
+    }
+
+    /// Replace bright colors with their normal variants, for terminals with
+    /// only 8 colors.
+    fn drop_bright(&mut self) {
+        self.bright_black = self.black.clone();
+        self.bright_blue = self.blue.clone();
+        self.bright_cyan = self.cyan.clone();
+        self.bright_green = self.green.clone();
+        self.bright_magenta = self.magenta.clone();
+        self.bright_red = self.red.clone();
+        self.bright_white = self.white.clone();
+        self.bright_yellow = self.yellow.clone();
+    }
 }
 
 /// Data structure containing all attributes and colors.
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Codes {
     pub attr: Attributes,
     pub bg: Colors,
     pub fg: Colors,
+    wrapper: CodeWrapper,
+    support: ColorSupport,
 }
 
-/// Check if running inside of TTY using libc isatty().
-fn is_tty() -> bool {
-    unsafe { libc::isatty(libc::STDOUT_FILENO) != 0 }
+impl Codes {
+    /// Check if attribute and color values are set, i.e. data structure was
+    /// created by [`init_on`].
+    pub fn is_enabled(&self) -> bool {
+        !self.attr.reset.is_empty()
+    }
+
+    /// Return data structure with all values, and all sequences later
+    /// rendered through it, wrapped using `wrapper`.
+    pub fn wrapped(mut self, wrapper: CodeWrapper) -> Codes {
+        let values = self.attr.values_mut().into_iter();
+        let values = values
+            .chain(self.bg.values_mut())
+            .chain(self.fg.values_mut());
+        for value in values {
+            *value = wrapper.wrap(value);
+        }
+        self.wrapper = wrapper;
+        self
+    }
+
+    /// Return escape sequence wrapped the same way as all values, see
+    /// [`Codes::wrapped`].
+    pub fn wrap(&self, seq: &str) -> String {
+        self.wrapper.wrap(seq)
+    }
+
+    /// Write escape sequence wrapped the same way as all values to writer,
+    /// see [`Codes::wrapped`].
+    pub fn write_to<W: Write>(&self, seq: &[u8], writer: &mut W) -> io::Result<()> {
+        self.wrapper.write_to(seq, writer)
+    }
+
+    /// Return number of colors sequences are rendered for, detected by
+    /// [`init_on`] and [`ColorSupport::NoColor`] if colors are disabled.
+    pub fn color_support(&self) -> ColorSupport {
+        if self.is_enabled() {
+            self.support
+        } else {
+            ColorSupport::NoColor
+        }
+    }
+
+    /// Return data structure with color support set, e.g. to force 24-bit
+    /// colors. Bright colors are replaced by normal colors for
+    /// [`ColorSupport::Ansi8`]. Has no effect if colors are disabled.
+    pub fn with_color_support(mut self, support: ColorSupport) -> Codes {
+        self.support = support;
+        if support == ColorSupport::Ansi8 {
+            self.bg.drop_bright();
+            self.fg.drop_bright();
+        }
+        self
+    }
+
+    /// Return color sequence for 256 color palette index, downgraded to 16 or
+    /// 8 colors if needed. Base is 38 for foreground and 48 for background.
+    fn indexed(&self, base: u8, n: u8) -> String {
+        let seq = match self.color_support() {
+            ColorSupport::NoColor => return String::new(),
+            support @ (ColorSupport::Ansi8 | ColorSupport::Ansi16) => {
+                let mut color = convert::index_to_16(n);
+                if support == ColorSupport::Ansi8 {
+                    color = color.normal();
+                }
+                match base {
+                    38 => format!("\x1B[{}m", color.fg_param()),
+                    _ => format!("\x1B[{}m", color.bg_param()),
+                }
+            }
+            _ => format!("\x1B[{};5;{}m", base, n),
+        };
+        self.wrap(&seq)
+    }
+
+    /// Return color sequence for RGB color, downgraded to 256 or 16 colors if
+    /// needed.
+    fn rgb(&self, base: u8, r: u8, g: u8, b: u8) -> String {
+        match self.color_support() {
+            ColorSupport::TrueColor => self.wrap(&format!("\x1B[{};2;{};{};{}m", base, r, g, b)),
+            _ => self.indexed(base, convert::rgb_to_256(r, g, b)),
+        }
+    }
+
+    /// Return foreground color sequence for xterm 256 color palette index,
+    /// or empty string if colors are disabled. Nearest of 16 colors is used
+    /// if terminal does not support 256 colors.
+    pub fn fg_256(&self, n: u8) -> String {
+        self.indexed(38, n)
+    }
+
+    /// Return background color sequence for xterm 256 color palette index,
+    /// or empty string if colors are disabled. Nearest of 16 colors is used
+    /// if terminal does not support 256 colors.
+    pub fn bg_256(&self, n: u8) -> String {
+        self.indexed(48, n)
+    }
+
+    /// Return foreground color sequence for 24-bit RGB color, or empty string
+    /// if colors are disabled. Nearest of 256 or 16 colors is used if terminal
+    /// does not support 24-bit colors.
+    pub fn fg_rgb(&self, r: u8, g: u8, b: u8) -> String {
+        self.rgb(38, r, g, b)
+    }
+
+    /// Return background color sequence for 24-bit RGB color, or empty string
+    /// if colors are disabled. Nearest of 256 or 16 colors is used if terminal
+    /// does not support 24-bit colors.
+    pub fn bg_rgb(&self, r: u8, g: u8, b: u8) -> String {
+        self.rgb(48, r, g, b)
+    }
+
+    /// Write reset value to writer.
+    pub fn write_reset<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.attr.reset.as_bytes())
+    }
+}
+
+/// Standard output streams.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    /// Return file descriptor of stream.
+    pub fn fd(self) -> libc::c_int {
+        match self {
+            Stream::Stdout => 1,
+            Stream::Stderr => 2,
+        }
+    }
+}
+
+/// Check if standard output is TTY, see [`is_tty_fd`].
+pub(crate) fn is_tty() -> bool {
+    is_tty_fd(Stream::Stdout.fd())
 }
 
-/// Check if `NO_COLOR` environment variable is set.
-fn no_color_env() -> bool {
-    env::var("NO_COLOR").is_ok()
+/// Check if file descriptor is TTY using libc isatty(), or console using
+/// GetConsoleMode() on Windows.
+pub(crate) fn is_tty_fd(fd: libc::c_int) -> bool {
+    #[cfg(windows)]
+    {
+        windows::is_console(fd)
+    }
+    #[cfg(not(windows))]
+    {
+        unsafe { libc::isatty(fd) != 0 }
+    }
+}
+
+/// Prepare terminal for escape sequences and return `false` if it can not
+/// display them, i.e. legacy Windows console.
+fn enable_sequences(fd: libc::c_int) -> bool {
+    #[cfg(windows)]
+    {
+        windows::enable_vt(fd)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = fd;
+        true
+    }
+}
+
+/// Check if locale environment variables specify UTF-8 encoding.
+///
+/// First non-empty value of `LC_ALL`, `LC_CTYPE` and `LANG` is used.
+pub(crate) fn utf8_env() -> bool {
+    for key in ["LC_ALL", "LC_CTYPE", "LANG"] {
+        if let Ok(value) = env::var(key) {
+            if !value.is_empty() {
+                let value = value.to_lowercase();
+                return value.contains("utf-8") || value.contains("utf8");
+            }
+        }
+    }
+    false
 }
 
 /// Run [`init_on`] or [`init_off`] and return result from function.
 ///
-/// If program is running inside of interactive TTY and `NO_COLOR` environment
-/// variable is not set use function [`init_on`], otherwise use [`init_off`].
+/// If [`ColorChoice::detect`] enables colors, i.e. program is running inside
+/// of interactive TTY and `NO_COLOR` environment variable is not set, use
+/// function [`init_on`], otherwise use [`init_off`].
 pub fn init_auto() -> Codes {
-    if is_tty() && !no_color_env() {
-        return init_on();
+    init_auto_fd(Stream::Stdout.fd())
+}
+
+/// Run [`init_auto`] detection for given stream instead of standard output,
+/// e.g. for messages printed to standard error.
+pub fn init_auto_for(stream: Stream) -> Codes {
+    init_auto_fd(stream.fd())
+}
+
+/// Run [`init_auto`] detection for given file descriptor instead of standard
+/// output.
+///
+/// On Windows virtual terminal processing is enabled for consoles, legacy
+/// consoles without support get [`init_off`] unless colors are forced.
+pub fn init_auto_fd(fd: libc::c_int) -> Codes {
+    if auto_enabled(fd) {
+        init_on()
+    } else {
+        init_off()
+    }
+}
+
+/// Check if [`init_auto_fd`] enables colors for file descriptor, preparing
+/// terminal for escape sequences if so. Colors are never enabled if
+/// [`Profile`] set with [`set_profile`] disallows them.
+pub(crate) fn auto_enabled(fd: libc::c_int) -> bool {
+    if !profile::allows(Profile::colors) {
+        return false;
+    }
+    match ColorChoice::detect_fd(fd) {
+        ColorChoice::Forced => {
+            enable_sequences(fd);
+            true
+        }
+        ColorChoice::Enabled => enable_sequences(fd),
+        ColorChoice::Disabled => false,
+    }
+}
+
+/// Run [`init_on`], [`init_off`] or [`init_auto`] as selected by `mode`, e.g.
+/// parsed from `--color` argument.
+pub fn init_with(mode: ColorMode) -> Codes {
+    match mode {
+        ColorMode::Auto => init_auto(),
+        ColorMode::Always => {
+            enable_sequences(Stream::Stdout.fd());
+            init_on()
+        }
+        ColorMode::Never => init_off(),
     }
+}
+
+/// Codes shared by whole program, see [`global`].
+static GLOBAL: OnceLock<Codes> = OnceLock::new();
+
+/// Return codes shared by whole program, initialized with [`init_auto`] on
+/// first use unless [`set_global`] was called before.
+pub fn global() -> &'static Codes {
+    GLOBAL.get_or_init(init_auto)
+}
 
-    init_off()
+/// Initialize codes returned by [`global`] with [`init_with`], e.g. early in
+/// `main` after parsing `--color` argument. Return `false` if already
+/// initialized, codes are then left unchanged.
+pub fn set_global(mode: ColorMode) -> bool {
+    GLOBAL.set(init_with(mode)).is_ok()
 }
 
 /// Return data structure with preset attribute and color values.
+///
+/// Bright colors fall back to normal colors on terminals with only 8 colors.
+/// 16 colors are used if `TERM` is unset or `dumb`, as colors are then
+/// forced, e.g. by `FORCE_COLOR` in CI.
 pub fn init_on() -> Codes {
-    Codes {
-        #[rustfmt::skip]
-        attr: Attributes {
-            reset:     "\x1B[0m".to_string(),
-            bold:      "\x1B[1m".to_string(),
-            italic:    "\x1B[3m".to_string(),
-            underline: "\x1B[4m".to_string(),
-            blink:     "\x1B[5m".to_string(),
-            reverse:   "\x1B[7m".to_string(),
-        },
-        #[rustfmt::skip]
-        bg: Colors {
-            black:   "\x1B[40m".to_string(),
-            red:     "\x1B[41m".to_string(),
-            green:   "\x1B[42m".to_string(),
-            yellow:  "\x1B[43m".to_string(),
-            blue:    "\x1B[44m".to_string(),
-            magenta: "\x1B[45m".to_string(),
-            cyan:    "\x1B[46m".to_string(),
-            white:   "\x1B[47m".to_string(),
-
-            bright_black:   "\x1B[100m".to_string(),
-            bright_red:     "\x1B[101m".to_string(),
-            bright_green:   "\x1B[102m".to_string(),
-            bright_yellow:  "\x1B[103m".to_string(),
-            bright_blue:    "\x1B[104m".to_string(),
-            bright_magenta: "\x1B[105m".to_string(),
-            bright_cyan:    "\x1B[106m".to_string(),
-            bright_white:   "\x1B[107m".to_string(),
-        },
-        #[rustfmt::skip]
-        fg: Colors {
-            black:   "\x1B[30m".to_string(),
-            red:     "\x1B[31m".to_string(),
-            green:   "\x1B[32m".to_string(),
-            yellow:  "\x1B[33m".to_string(),
-            blue:    "\x1B[34m".to_string(),
-            magenta: "\x1B[35m".to_string(),
-            cyan:    "\x1B[36m".to_string(),
-            white:   "\x1B[37m".to_string(),
-
-            bright_black:   "\x1B[90m".to_string(),
-            bright_red:     "\x1B[91m".to_string(),
-            bright_green:   "\x1B[92m".to_string(),
-            bright_yellow:  "\x1B[93m".to_string(),
-            bright_blue:    "\x1B[94m".to_string(),
-            bright_magenta: "\x1B[95m".to_string(),
-            bright_cyan:    "\x1B[96m".to_string(),
-            bright_white:   "\x1B[97m".to_string(),
-        },
-    }
+    init_on_with(match ColorSupport::detect() {
+        ColorSupport::NoColor => ColorSupport::Ansi16,
+        support => support,
+    })
+}
+
+/// Return data structure from [`init_on`] for given color support instead of
+/// detected one.
+pub(crate) fn init_on_with(support: ColorSupport) -> Codes {
+    let codes = Codes {
+        attr: consts::ON.attr.to_strings(),
+        bg: consts::ON.bg.to_strings(),
+        fg: consts::ON.fg.to_strings(),
+        wrapper: CodeWrapper::None,
+        support: ColorSupport::Ansi16,
+    };
+    codes.with_color_support(support)
 }
 
 /// Return data structure with empty attribute and color values.
@@ -0,0 +1,78 @@
+//! Per-line style resets.
+//!
+//! Styled text spanning several lines normally relies on the terminal keeping
+//! state between lines. Pagers like `less -R`, `head` and tools processing
+//! lines independently break that assumption. Text is rewritten so that every
+//! physical line ends with a reset and starts by re-emitting active styles.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! print!("{}", colors::reset_lines(&ansi, &rendered));
+//! ```
+
+use crate::ansi::{segments, Segment};
+use crate::Codes;
+
+/// Return SGR parameters of escape sequence, or `None` if not SGR.
+fn sgr_params(seq: &str) -> Option<&str> {
+    seq.strip_prefix("\x1B[")?.strip_suffix('m')
+}
+
+/// Return text with reset added before every line break where styles are
+/// active and active styles re-emitted after it, or text as is if colors are
+/// disabled in `codes`.
+///
+/// Active styles are tracked as the SGR sequences seen since the last reset,
+/// so extended colors are re-emitted exactly as written.
+pub fn reset_lines(codes: &Codes, text: &str) -> String {
+    if !codes.is_enabled() {
+        return text.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut active: Vec<&str> = Vec::new();
+    for segment in segments(text) {
+        match segment {
+            Segment::Escape(seq) => {
+                if let Some(params) = sgr_params(seq) {
+                    let first = params.split(';').next().unwrap_or_default();
+                    if first.is_empty() || first.parse::<u16>() == Ok(0) {
+                        active.clear();
+                    }
+                    if params
+                        .split(';')
+                        .any(|p| !p.is_empty() && p.parse::<u16>() != Ok(0))
+                    {
+                        active.push(seq);
+                    }
+                }
+                output.push_str(seq);
+            }
+            Segment::Text(text) => {
+                let mut lines = text.split('\n').peekable();
+                while let Some(line) = lines.next() {
+                    if lines.peek().is_none() {
+                        output.push_str(line);
+                        break;
+                    }
+                    let (line, cr) = match line.strip_suffix('\r') {
+                        Some(line) => (line, "\r"),
+                        None => (line, ""),
+                    };
+                    output.push_str(line);
+                    if !active.is_empty() {
+                        output.push_str(&codes.attr.reset);
+                    }
+                    output.push_str(cr);
+                    output.push('\n');
+                    for seq in &active {
+                        output.push_str(seq);
+                    }
+                }
+            }
+        }
+    }
+    output
+}
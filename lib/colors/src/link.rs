@@ -0,0 +1,244 @@
+//! Terminal hyperlinks (OSC 8).
+//!
+//! Text is wrapped in OSC 8 sequences making it clickable in supporting
+//! terminals, other terminals show the text only. Links are only emitted if
+//! colors are enabled, i.e. never into files or pipes, and never in hosts
+//! known to print them literally, see [`Host::supports_hyperlinks`], or if
+//! output profile disallows them.
+//!
+//! [`Host::supports_hyperlinks`]: crate::Host::supports_hyperlinks
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let style = colors::Style::new().attr(colors::Attr::Underline);
+//! println!("{}:12: error", colors::link_path(&ansi, Path::new("src/main.rs"), &style));
+//! println!("{}: error", colors::link_location(&ansi, Path::new("src/main.rs"), 12, 5, &style));
+//! println!("see {}", ansi.hyperlink("https://example.com/docs", "docs"));
+//! ```
+//!
+//! [`Codes::hyperlink`] is meant for URLs that must stay visible, it only
+//! emits links in terminals detected to support them by
+//! [`hyperlinks_supported`] and shows `text (url)` otherwise.
+//!
+//! Locations link to `file://` URLs by default, which do not carry line
+//! numbers. Set `COLORS_EDITOR_LINK` to `vscode` or to a template such as
+//! `idea://open?file={path}&line={line}&column={column}` to open locations
+//! directly in an editor, or call [`set_editor_link`].
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::profile;
+use crate::{Codes, Host, Profile, Style};
+
+/// Environment variable selecting editor URL scheme.
+const EDITOR_LINK_ENV: &str = "COLORS_EDITOR_LINK";
+
+/// Editor link set with [`set_editor_link`], overriding environment.
+static EDITOR_LINK: Mutex<Option<EditorLink>> = Mutex::new(None);
+
+/// URL scheme used when linking file locations.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EditorLink {
+    /// Plain `file://` URL, line and column are not part of link.
+    #[default]
+    File,
+    /// Visual Studio Code, `vscode://file/path:line:column`.
+    VsCode,
+    /// Custom template where `{path}`, `{line}` and `{column}` are replaced
+    /// with absolute percent-encoded path, line and column.
+    Template(String),
+}
+
+impl EditorLink {
+    /// Return editor link from `COLORS_EDITOR_LINK`, i.e. `file`, `vscode` or
+    /// template containing `{path}`. Defaults to [`EditorLink::File`].
+    pub fn from_env() -> EditorLink {
+        match env::var(EDITOR_LINK_ENV) {
+            Ok(value) => match value.as_str() {
+                "vscode" => EditorLink::VsCode,
+                _ if value.contains("{path}") => EditorLink::Template(value),
+                _ => EditorLink::File,
+            },
+            Err(_) => EditorLink::File,
+        }
+    }
+
+    /// Return URL opening path at line and column.
+    pub fn url(&self, path: &Path, line: u32, column: u32) -> String {
+        match self {
+            EditorLink::File => file_url(path),
+            EditorLink::VsCode => format!(
+                "vscode://file{}:{}:{}",
+                percent_encode(&absolute(path).to_string_lossy()),
+                line,
+                column
+            ),
+            EditorLink::Template(template) => template
+                .replace("{path}", &percent_encode(&absolute(path).to_string_lossy()))
+                .replace("{line}", &line.to_string())
+                .replace("{column}", &column.to_string()),
+        }
+    }
+}
+
+/// Set editor link used for locations for whole program, overriding
+/// `COLORS_EDITOR_LINK`.
+pub fn set_editor_link(link: EditorLink) {
+    *EDITOR_LINK.lock().unwrap_or_else(|e| e.into_inner()) = Some(link);
+}
+
+/// Return editor link set with [`set_editor_link`] or from environment.
+pub fn editor_link() -> EditorLink {
+    let link = EDITOR_LINK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    link.unwrap_or_else(EditorLink::from_env)
+}
+
+/// Values of `TERM_PROGRAM` of terminals supporting hyperlinks.
+const HYPERLINK_PROGRAMS: [&str; 7] = [
+    "ghostty",
+    "Hyper",
+    "iTerm.app",
+    "rio",
+    "Tabby",
+    "vscode",
+    "WezTerm",
+];
+
+/// Parts of `TERM` of terminals supporting hyperlinks.
+const HYPERLINK_TERMS: [&str; 5] = ["alacritty", "foot", "ghostty", "kitty", "wezterm"];
+
+/// First VTE version supporting hyperlinks, 0.50.
+const VTE_HYPERLINKS: u32 = 5000;
+
+/// Check if terminal is known to support hyperlinks by `TERM_PROGRAM`,
+/// `TERM`, `VTE_VERSION`, `WT_SESSION` for Windows Terminal or
+/// `KONSOLE_VERSION`, and host and output profile allow them.
+pub fn hyperlinks_supported() -> bool {
+    if !Host::detect().supports_hyperlinks() || !profile::allows(Profile::hyperlinks) {
+        return false;
+    }
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    HYPERLINK_PROGRAMS.contains(&var("TERM_PROGRAM").as_str())
+        || HYPERLINK_TERMS.iter().any(|t| term.contains(t))
+        || var("VTE_VERSION")
+            .parse()
+            .is_ok_and(|v: u32| v >= VTE_HYPERLINKS)
+        || env::var_os("WT_SESSION").is_some()
+        || env::var_os("KONSOLE_VERSION").is_some()
+}
+
+impl Codes {
+    /// Return text linked to URL if colors are enabled and terminal supports
+    /// hyperlinks, see [`hyperlinks_supported`]. Otherwise return
+    /// `text (url)`, or just text if it is the URL itself.
+    pub fn hyperlink(&self, url: &str, text: &str) -> String {
+        if self.is_enabled() && hyperlinks_supported() {
+            osc8(self, url, text)
+        } else if text == url {
+            text.to_string()
+        } else {
+            format!("{} ({})", text, url)
+        }
+    }
+}
+
+/// Return text wrapped in OSC 8 hyperlink sequences.
+pub(crate) fn osc8(codes: &Codes, url: &str, text: &str) -> String {
+    format!(
+        "{}{}{}",
+        codes.wrap(&format!("\x1B]8;;{}\x1B\\", url)),
+        text,
+        codes.wrap("\x1B]8;;\x1B\\")
+    )
+}
+
+/// Return string with all bytes except unreserved characters and `/`
+/// percent-encoded.
+pub(crate) fn percent_encode(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                output.push(b as char)
+            }
+            _ => output.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    output
+}
+
+/// Return host name of machine from `COMPUTERNAME`.
+#[cfg(windows)]
+pub(crate) fn hostname() -> Option<String> {
+    env::var("COMPUTERNAME").ok()
+}
+
+/// Return host name of machine using libc gethostname().
+#[cfg(unix)]
+pub(crate) fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let ok = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) } == 0;
+    if !ok {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+/// Return absolute version of path without touching file system.
+pub(crate) fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match env::current_dir() {
+        Ok(dir) => dir.join(path),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Return `file://` URL of path, including host name so links are not opened
+/// on the wrong machine over SSH.
+pub fn file_url(path: &Path) -> String {
+    let path = absolute(path);
+    let host = hostname().unwrap_or_default();
+    format!(
+        "file://{}{}",
+        percent_encode(&host),
+        percent_encode(&path.to_string_lossy())
+    )
+}
+
+/// Return path painted with style and linked to its `file://` URL, or plain
+/// path if colors are disabled in `codes`.
+pub fn link_path(codes: &Codes, path: &Path, style: &Style) -> String {
+    let text = path.display().to_string();
+    if !codes.is_enabled() {
+        return text;
+    }
+    if !Host::detect().supports_hyperlinks() || !profile::allows(Profile::hyperlinks) {
+        return style.paint(codes, &text);
+    }
+    osc8(codes, &file_url(path), &style.paint(codes, &text))
+}
+
+/// Return location painted with style as `path:line:column` and linked using
+/// [`editor_link`], or plain location if colors are disabled in `codes`.
+pub fn link_location(codes: &Codes, path: &Path, line: u32, column: u32, style: &Style) -> String {
+    let text = format!("{}:{}:{}", path.display(), line, column);
+    if !codes.is_enabled() {
+        return text;
+    }
+    if !Host::detect().supports_hyperlinks() || !profile::allows(Profile::hyperlinks) {
+        return style.paint(codes, &text);
+    }
+    let url = editor_link().url(path, line, column);
+    osc8(codes, &url, &style.paint(codes, &text))
+}
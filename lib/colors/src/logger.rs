@@ -0,0 +1,109 @@
+//! Column aligned log lines.
+//!
+//! Every line starts with a fixed width component name, colored by hash of
+//! the name so the same subsystem always gets the same color, followed by a
+//! level badge. Columns are aligned using visible width and continuation
+//! lines of multi-line messages are indented under the first one, keeping
+//! interleaved output from concurrent subsystems scannable.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let log = colors::Logger::new(colors::init_auto_for(colors::Stream::Stderr), 8);
+//! log.log("http", Level::Info, "listening on :8080");
+//! log.log("db", Level::Warning, "slow query");
+//! ```
+
+use std::io::{self, Write};
+
+use crate::width::{display_width, truncate};
+use crate::{Attr, Codes, Color, Level, Style};
+
+/// Colors used for component names, red is left out to not look like errors.
+const PALETTE: [Color; 10] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+];
+
+/// Width of level badge column.
+const BADGE_WIDTH: usize = 5;
+
+/// Return color picked by FNV-1a hash of component name.
+pub fn component_color(name: &str) -> Color {
+    let mut hash: u32 = 0x811c_9dc5;
+    for b in name.bytes() {
+        hash ^= u32::from(b);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+/// Return short uppercase badge text for level.
+fn badge(level: Level) -> &'static str {
+    match level {
+        Level::Error => "ERROR",
+        Level::Warning => "WARN",
+        Level::Info => "INFO",
+        Level::Success => "OK",
+        Level::Hint => "HINT",
+        Level::Verbose => "VERB",
+        Level::Debug => "DEBUG",
+    }
+}
+
+/// Return text cut or padded with spaces to exactly `width` columns.
+fn fit(text: &str, width: usize) -> String {
+    let output = truncate(text, width, "");
+    let used = display_width(&output);
+    output + &" ".repeat(width.saturating_sub(used))
+}
+
+/// Formatter and writer of aligned log lines.
+pub struct Logger {
+    codes: Codes,
+    width: usize,
+}
+
+impl Logger {
+    /// Return logger using `width` columns for component names.
+    pub fn new(codes: Codes, width: usize) -> Logger {
+        Logger { codes, width }
+    }
+
+    /// Return formatted log line, including trailing newline.
+    pub fn line(&self, component: &str, level: Level, message: &str) -> String {
+        let name = Style::new().fg(component_color(component));
+        let name = name.paint(&self.codes, &fit(component, self.width));
+        let level_style = level.style().attr(Attr::Bold);
+        let badge = level_style.paint(&self.codes, &fit(badge(level), BADGE_WIDTH));
+
+        let indent = " ".repeat(self.width + BADGE_WIDTH + 2);
+        let mut output = String::new();
+        for (i, text) in message.lines().enumerate() {
+            if i == 0 {
+                output.push_str(&format!("{} {} {}\n", name, badge, text));
+            } else {
+                output.push_str(&format!("{}{}\n", indent, text));
+            }
+        }
+        if output.is_empty() {
+            output = format!("{} {}\n", name, badge);
+        }
+        output
+    }
+
+    /// Write log line to standard error in one call, keeping lines from
+    /// multiple threads intact.
+    pub fn log(&self, component: &str, level: Level, message: &str) {
+        let line = self.line(component, level, message);
+        let _ = io::stderr().lock().write_all(line.as_bytes());
+    }
+}
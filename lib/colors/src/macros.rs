@@ -0,0 +1,99 @@
+//! Styled printing macros.
+
+/// Write style, formatted content and reset directly into writer.
+///
+/// Nothing besides formatted content is written if colors are disabled in
+/// codes or style is plain. Evaluates to `io::Result<()>`.
+///
+/// Usage:
+///
+/// ```rust,ignore
+/// let ansi = colors::init_auto();
+/// let warn = colors::Style::new().fg(colors::Color::Yellow);
+/// colors::cwrite!(stderr, &ansi, &warn, "warning: {} retries left", n)?;
+/// ```
+#[macro_export]
+macro_rules! cwrite {
+    ($writer:expr, $codes:expr, $style:expr, $($arg:tt)*) => {{
+        let codes: &$crate::Codes = $codes;
+        let style: &$crate::Style = $style;
+        let writer = &mut $writer;
+        style
+            .write_to(codes, writer)
+            .and_then(|_| ::std::io::Write::write_fmt(writer, format_args!($($arg)*)))
+            .and_then(|_| {
+                if codes.is_enabled() && !style.is_plain() {
+                    codes.write_reset(writer)
+                } else {
+                    Ok(())
+                }
+            })
+    }};
+}
+
+/// Same as [`cwrite!`] but with newline written after reset.
+#[macro_export]
+macro_rules! cwriteln {
+    ($writer:expr, $codes:expr, $style:expr, $($arg:tt)*) => {{
+        let writer = &mut $writer;
+        $crate::cwrite!(*writer, $codes, $style, $($arg)*)
+            .and_then(|_| ::std::io::Write::write_all(writer, b"\n"))
+    }};
+}
+
+/// Format arguments and render inline style tags like `<red,bold>` and `</>`
+/// through codes, see [`markup`](crate::markup). Tags are removed if colors
+/// are disabled in codes. Evaluates to `String`.
+///
+/// Tags are only read from the format string, formatted arguments are kept
+/// as text even if they look like tags, e.g. file names containing `</>`.
+/// Variables captured by name inside of the format string, like `{name}`,
+/// are not arguments and are rendered as markup, pass user data as
+/// arguments instead.
+///
+/// Usage:
+///
+/// ```rust,ignore
+/// let ansi = colors::init_auto();
+/// let line = colors::cformat!(&ansi, "<green>ok</> {} tests", n);
+/// ```
+#[macro_export]
+macro_rules! cformat {
+    ($codes:expr, $fmt:literal $(, $($arg:tt)*)?) => {
+        $crate::__cformat_args!(@[$codes, $fmt] [] $($($arg)*)?)
+    };
+}
+
+/// Wrap every argument of [`cformat!`] in literal markers, named arguments
+/// are matched before expressions since `name = value` is an expression.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cformat_args {
+    (@[$codes:expr, $fmt:literal] [$($done:tt)*]) => {
+        $crate::__markup_literals($codes, &::std::format!($fmt $($done)*))
+    };
+    (@$head:tt [$($done:tt)*] $name:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__cformat_args!(
+            @$head [$($done)* , $name = $crate::__Literal(&$value)] $($($rest)*)?
+        )
+    };
+    (@$head:tt [$($done:tt)*] $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::__cformat_args!(@$head [$($done)* , $crate::__Literal(&$arg)] $($($rest)*)?)
+    };
+}
+
+/// Same as [`cformat!`] but printed to standard output.
+#[macro_export]
+macro_rules! cprint {
+    ($codes:expr, $($arg:tt)*) => {
+        ::std::print!("{}", $crate::cformat!($codes, $($arg)*))
+    };
+}
+
+/// Same as [`cformat!`] but printed to standard output with newline.
+#[macro_export]
+macro_rules! cprintln {
+    ($codes:expr, $($arg:tt)*) => {
+        ::std::println!("{}", $crate::cformat!($codes, $($arg)*))
+    };
+}
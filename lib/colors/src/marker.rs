@@ -0,0 +1,108 @@
+//! Structural markers around semantic regions.
+//!
+//! Wrapper tools parsing output can opt in to markers delimiting regions like
+//! diagnostics and sections, which stay reliable even with styling present.
+//! Markers are either invisible OSC sequences or sentinel lines, selected by
+//! `COLORS_MARKERS` or [`set_markers`]. By default no markers are emitted.
+//!
+//! `COLORS_MARKERS=osc` emits `ESC ] 7701 ; begin ; kind BEL` and matching
+//! `end` sequences. Any other value containing `{kind}` is used as sentinel
+//! template, with begin and end templates separated by `|`, e.g.
+//! `COLORS_MARKERS='>>> {kind}|<<< {kind}'`.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! print!("{}", colors::region("summary", &summary));
+//! ```
+
+use std::env;
+use std::sync::Mutex;
+
+/// OSC number used for markers, not assigned to any terminal feature.
+const OSC: u32 = 7701;
+
+/// Markers set with [`set_markers`], overriding environment.
+static MARKERS: Mutex<Option<Markers>> = Mutex::new(None);
+
+/// Kind of structural markers emitted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Markers {
+    #[default]
+    Off,
+    /// Invisible OSC sequences.
+    Osc,
+    /// Sentinel lines, `{kind}` is replaced with region kind.
+    Sentinel { begin: String, end: String },
+}
+
+impl Markers {
+    /// Return markers selected by `COLORS_MARKERS`, defaults to
+    /// [`Markers::Off`].
+    pub fn from_env() -> Markers {
+        let value = env::var("COLORS_MARKERS").unwrap_or_default();
+        if value == "osc" {
+            return Markers::Osc;
+        }
+        match value.split_once('|') {
+            Some((begin, end)) if value.contains("{kind}") => Markers::Sentinel {
+                begin: begin.to_string(),
+                end: end.to_string(),
+            },
+            _ => Markers::Off,
+        }
+    }
+
+    /// Return marker starting region of kind.
+    pub fn begin(&self, kind: &str) -> String {
+        match self {
+            Markers::Off => String::new(),
+            Markers::Osc => format!("\x1B]{};begin;{}\x07", OSC, kind),
+            Markers::Sentinel { begin, .. } => format!("{}\n", begin.replace("{kind}", kind)),
+        }
+    }
+
+    /// Return marker ending region of kind.
+    pub fn end(&self, kind: &str) -> String {
+        match self {
+            Markers::Off => String::new(),
+            Markers::Osc => format!("\x1B]{};end;{}\x07", OSC, kind),
+            Markers::Sentinel { end, .. } => format!("{}\n", end.replace("{kind}", kind)),
+        }
+    }
+}
+
+/// Set markers for whole program, overriding `COLORS_MARKERS`.
+pub fn set_markers(markers: Markers) {
+    *MARKERS.lock().unwrap_or_else(|e| e.into_inner()) = Some(markers);
+}
+
+/// Return markers set with [`set_markers`] or from environment.
+pub fn markers() -> Markers {
+    let markers = MARKERS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    markers.unwrap_or_else(Markers::from_env)
+}
+
+/// Return text wrapped in markers of region kind, or text as is if markers
+/// are off. OSC end markers are placed before final newline of text so that
+/// line count does not change.
+pub fn region(kind: &str, text: &str) -> String {
+    let markers = markers();
+    match markers {
+        Markers::Off => text.to_string(),
+        Markers::Osc => {
+            let body = text.strip_suffix('\n').unwrap_or(text);
+            let newline = &text[body.len()..];
+            format!(
+                "{}{}{}{}",
+                markers.begin(kind),
+                body,
+                markers.end(kind),
+                newline
+            )
+        }
+        Markers::Sentinel { .. } => {
+            format!("{}{}{}", markers.begin(kind), text, markers.end(kind))
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Inline style tags.
+//!
+//! Text like `<red,bold>error:</> file not found` is rendered by replacing
+//! tags with escape sequences through [`Codes`], or removing them if colors
+//! are disabled. A tag lists comma separated color names like `red` or
+//! `bright_red`, background colors like `bg:blue` and attributes like `bold`.
+//! `</>` closes the most recent tag and restores the style around it, see
+//! [`StyleStack`]. Tags that do not parse, like `<T>` in `Vec<T>`, are left
+//! as text.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! colors::cprintln!(&ansi, "<red,bold>error:</> {}", msg);
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::{parse_style, Codes, Style, StyleStack};
+
+/// Start of formatted argument in [`cformat!`](crate::cformat) output,
+/// noncharacter reserved for internal use.
+const LITERAL_START: char = '\u{FDD0}';
+
+/// End of formatted argument in [`cformat!`](crate::cformat) output.
+const LITERAL_END: char = '\u{FDD1}';
+
+/// Argument of [`cformat!`](crate::cformat) formatted between markers, so
+/// that tags in it are kept as text.
+#[doc(hidden)]
+pub struct Literal<'a, T: ?Sized>(pub &'a T);
+
+macro_rules! literal_fmt {
+    ($($trait:ident),*) => {
+        $(
+            impl<'a, T: fmt::$trait + ?Sized> fmt::$trait for Literal<'a, T> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_char(LITERAL_START)?;
+                    fmt::$trait::fmt(self.0, f)?;
+                    f.write_char(LITERAL_END)
+                }
+            }
+        )*
+    };
+}
+
+literal_fmt!(Display, Debug, LowerHex, UpperHex, Octal, Binary, LowerExp, UpperExp, Pointer);
+
+/// Return style of tag content, or `None` if content is not a valid list of
+/// style names.
+fn parse_tag(spec: &str) -> Option<Style> {
+    if spec.trim().is_empty() {
+        return None;
+    }
+    parse_style(spec).ok()
+}
+
+/// Return text with style tags replaced by escape sequences, or removed if
+/// colors are disabled in `codes`. Styles still open at end of text are
+/// reset.
+pub fn markup(codes: &Codes, text: &str) -> String {
+    render(codes, text, false)
+}
+
+/// Return output of [`markup`] keeping text between literal markers as is,
+/// used by [`cformat!`](crate::cformat).
+#[doc(hidden)]
+pub fn markup_literals(codes: &Codes, text: &str) -> String {
+    render(codes, text, true)
+}
+
+/// Return text with style tags rendered, and literal markers removed with
+/// text between them kept as is if `literals` is set.
+fn render(codes: &Codes, text: &str, literals: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut stack = StyleStack::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c| c == '<' || (literals && c == LITERAL_START)) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(body) = rest.strip_prefix(LITERAL_START) {
+            let end = body.find(LITERAL_END).unwrap_or(body.len());
+            output.push_str(&body[..end]);
+            rest = body.get(end + LITERAL_END.len_utf8()..).unwrap_or_default();
+            continue;
+        }
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let spec = &rest[1..end];
+
+        if spec == "/" && !stack.is_empty() {
+            output.push_str(&stack.pop(codes));
+        } else if let Some(style) = parse_tag(spec) {
+            output.push_str(&stack.push(codes, style));
+        } else {
+            // Not a tag, keep `<` and continue after it.
+            output.push('<');
+            rest = &rest[1..];
+            continue;
+        }
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+
+    output.push_str(&stack.reset(codes));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_off, init_on_with, ColorSupport};
+
+    #[test]
+    fn nested_tags_restore_outer_style() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        assert_eq!(
+            markup(&codes, "<red>a <bold>b</> c</> d"),
+            "\x1B[31ma \x1B[1;31mb\x1B[0m\x1B[31m c\x1B[0m d"
+        );
+        assert_eq!(markup(&codes, "<bg:blue>x"), "\x1B[44mx\x1B[0m");
+    }
+
+    #[test]
+    fn unknown_tags_are_text() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        assert_eq!(markup(&codes, "Vec<T> a < b <>"), "Vec<T> a < b <>");
+        assert_eq!(markup(&codes, "</> stray"), "</> stray");
+    }
+
+    #[test]
+    fn tags_removed_without_colors() {
+        assert_eq!(
+            markup(&init_off(), "<red,bold>error:</> failed"),
+            "error: failed"
+        );
+    }
+
+    #[test]
+    fn cformat_keeps_arguments_as_text() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let file = "a</>b<red>c";
+        assert_eq!(
+            crate::cformat!(
+                &codes,
+                "<green>{}</> {:>4} {name}",
+                file,
+                7,
+                name = "<bold>"
+            ),
+            "\x1B[32ma</>b<red>c\x1B[0m    7 <bold>"
+        );
+        assert_eq!(crate::cformat!(&codes, "<{}>x", "red"), "<red>x");
+        assert_eq!(
+            crate::cformat!(&init_off(), "<red>{:?}</>", "</>"),
+            "\"</>\""
+        );
+    }
+}
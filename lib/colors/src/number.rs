@@ -0,0 +1,100 @@
+//! Number formatting for tables and summaries.
+//!
+//! Digits are grouped in thousands using separators of the language in
+//! `LC_ALL`, `LC_NUMERIC` or `LANG`, e.g. `1,234.5` for English and the C
+//! locale, `1.234,5` for German and `1 234,5` with narrow no-break space for
+//! French or Swedish.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let delta = colors::sign_style(change).paint(&ansi, &colors::fmt_float(change, 2));
+//! println!("{} files, {}", colors::fmt_int(files), delta);
+//! ```
+
+use std::env;
+
+use crate::{Color, Style};
+
+/// Languages grouping with `.` and using `,` as decimal separator.
+const DOT_GROUPING: [&str; 10] = ["da", "de", "el", "es", "id", "it", "nl", "pt", "sl", "tr"];
+
+/// Languages grouping with narrow no-break space and using `,` as decimal
+/// separator.
+const SPACE_GROUPING: [&str; 10] = ["cs", "fi", "fr", "hu", "nb", "nn", "pl", "ru", "sk", "sv"];
+
+/// Return group and decimal separators of locale.
+fn separators() -> (&'static str, char) {
+    let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+    if DOT_GROUPING.contains(&language) {
+        (".", ',')
+    } else if SPACE_GROUPING.contains(&language) {
+        ("\u{202F}", ',')
+    } else {
+        (",", '.')
+    }
+}
+
+/// Return digits grouped in thousands.
+fn group(digits: &str, separator: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() * 2);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Return integer with digits grouped in thousands, e.g. `-1,234,567`.
+pub fn fmt_int(n: i64) -> String {
+    let (separator, _) = separators();
+    let digits = group(&n.unsigned_abs().to_string(), separator);
+    if n < 0 {
+        format!("-{}", digits)
+    } else {
+        digits
+    }
+}
+
+/// Return number rounded to `prec` decimals with digits grouped in
+/// thousands, e.g. `1,234.50`. Infinity and NaN are returned as `inf` and
+/// `NaN`.
+pub fn fmt_float(n: f64, prec: usize) -> String {
+    if !n.is_finite() {
+        return n.to_string();
+    }
+    let (separator, decimal) = separators();
+    let text = format!("{:.*}", prec, n.abs());
+    let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+    let mut output = String::new();
+    // Keep sign only if something nonzero is left after rounding.
+    if n < 0.0 && text.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+        output.push('-');
+    }
+    output.push_str(&group(int, separator));
+    if !frac.is_empty() {
+        output.push(decimal);
+        output.push_str(frac);
+    }
+    output
+}
+
+/// Return style for coloring number by sign, red if negative, green if
+/// positive and plain for zero and NaN.
+pub fn sign_style(n: f64) -> Style {
+    if n < 0.0 {
+        Style::new().fg(Color::Red)
+    } else if n > 0.0 {
+        Style::new().fg(Color::Green)
+    } else {
+        Style::new()
+    }
+}
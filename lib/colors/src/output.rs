@@ -0,0 +1,165 @@
+//! Output facade with verbosity levels.
+//!
+//! Wraps an [`Emitter`] and filters messages by [`Verbosity`], giving every
+//! program the same `-q`, `-v` and `-vv` behaviour. Errors are always shown,
+//! warnings and normal messages unless quiet, verbose and debug messages only
+//! when requested. Output can be split into nested titled groups.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let verbosity = colors::Verbosity::from_flags(args.quiet, args.verbose);
+//! let mut out = colors::Output::auto(colors::emit::Format::Text, verbosity);
+//! out.verbose("reading config")?;
+//! out.group("Tests", |out| out.success("all passed"))?;
+//! out.status(Level::Success, "Finished", "3 files")?;
+//! ```
+
+use std::io;
+
+use crate::emit::{self, Emitter, Event, Format};
+use crate::{init_auto, Diagnostic, Level};
+
+/// Amount of output shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Verbosity {
+    Quiet,
+    #[default]
+    Normal,
+    Verbose,
+    Debug,
+}
+
+impl Verbosity {
+    /// Return verbosity from `-q` flag and number of `-v` flags, quiet wins.
+    pub fn from_flags(quiet: bool, verbose: u8) -> Verbosity {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        }
+    }
+}
+
+/// Return lowest verbosity showing message of level.
+fn threshold(level: Level) -> Verbosity {
+    match level {
+        Level::Error => Verbosity::Quiet,
+        Level::Verbose => Verbosity::Verbose,
+        Level::Debug => Verbosity::Debug,
+        _ => Verbosity::Normal,
+    }
+}
+
+/// Emitter wrapper filtering events by verbosity.
+pub struct Output {
+    emitter: Box<dyn Emitter>,
+    verbosity: Verbosity,
+}
+
+impl Output {
+    pub fn new(emitter: Box<dyn Emitter>, verbosity: Verbosity) -> Output {
+        Output { emitter, verbosity }
+    }
+
+    /// Return output to standard output using [`emit::auto`] and
+    /// [`init_auto`].
+    pub fn auto(format: Format, verbosity: Verbosity) -> Output {
+        Output::new(emit::auto(format, init_auto(), io::stdout()), verbosity)
+    }
+
+    /// Return current verbosity.
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    /// Check if messages requiring verbosity are shown.
+    pub fn shows(&self, verbosity: Verbosity) -> bool {
+        self.verbosity >= verbosity
+    }
+
+    /// Emit event if shown at current verbosity.
+    fn emit_at(&mut self, verbosity: Verbosity, event: &Event) -> io::Result<()> {
+        if !self.shows(verbosity) {
+            return Ok(());
+        }
+        self.emitter.emit(event)
+    }
+
+    /// Emit message of level.
+    pub fn message(&mut self, level: Level, text: &str) -> io::Result<()> {
+        self.emit_at(threshold(level), &Event::Message { level, text })
+    }
+
+    /// Emit error message, shown even when quiet.
+    pub fn error(&mut self, text: &str) -> io::Result<()> {
+        self.message(Level::Error, text)
+    }
+
+    /// Emit warning message.
+    pub fn warning(&mut self, text: &str) -> io::Result<()> {
+        self.message(Level::Warning, text)
+    }
+
+    /// Emit normal message.
+    pub fn info(&mut self, text: &str) -> io::Result<()> {
+        self.message(Level::Info, text)
+    }
+
+    /// Emit success message.
+    pub fn success(&mut self, text: &str) -> io::Result<()> {
+        self.message(Level::Success, text)
+    }
+
+    /// Emit muted message only shown with `-v`.
+    pub fn verbose(&mut self, text: &str) -> io::Result<()> {
+        self.message(Level::Verbose, text)
+    }
+
+    /// Emit message only shown with `-vv`.
+    pub fn debug(&mut self, text: &str) -> io::Result<()> {
+        self.message(Level::Debug, text)
+    }
+
+    /// Emit status line.
+    pub fn status(&mut self, level: Level, label: &str, message: &str) -> io::Result<()> {
+        let event = Event::Status {
+            level,
+            label,
+            message,
+        };
+        self.emit_at(threshold(level), &event)
+    }
+
+    /// Emit table row.
+    pub fn row(&mut self, cells: &[&str]) -> io::Result<()> {
+        self.emit_at(Verbosity::Normal, &Event::Row(cells))
+    }
+
+    /// Emit diagnostic, errors are shown even when quiet.
+    pub fn diagnostic(&mut self, diag: &Diagnostic) -> io::Result<()> {
+        self.emit_at(threshold(diag.level), &Event::Diagnostic(diag))
+    }
+
+    /// Start titled group, hidden when quiet.
+    pub fn group_start(&mut self, title: &str) -> io::Result<()> {
+        self.emit_at(Verbosity::Normal, &Event::GroupStart { title })
+    }
+
+    /// End group started by last [`Output::group_start`].
+    pub fn group_end(&mut self) -> io::Result<()> {
+        self.emit_at(Verbosity::Normal, &Event::GroupEnd)
+    }
+
+    /// Run function with all output inside of titled group.
+    pub fn group<T, F>(&mut self, title: &str, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut Output) -> io::Result<T>,
+    {
+        self.group_start(title)?;
+        let result = f(self);
+        self.group_end()?;
+        result
+    }
+}
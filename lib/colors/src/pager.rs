@@ -0,0 +1,123 @@
+//! Detection of pagers reading piped output.
+//!
+//! Output piped into a pager like `prog | less -R` is not a TTY, so colors are
+//! disabled by default even though the pager displays them. Programs can opt
+//! in with [`set_pager_detection`] to keep colors enabled when the process
+//! reading the pipe is a pager known to pass colors through. Detection finds
+//! the reader by its standard input on Linux and never matches elsewhere.
+//!
+//! `less` only counts if started with `-R` or `-r`, on command line or in
+//! `LESS` environment variable of the pager process.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! colors::set_pager_detection(true);
+//! let ansi = colors::init_auto();
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Pagers displaying colors without options.
+#[cfg(target_os = "linux")]
+const COLOR_PAGERS: [&str; 4] = ["moar", "moor", "most", "ov"];
+
+/// Detection enabled with [`set_pager_detection`].
+static DETECTION: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable pager detection for whole program, disabled by default.
+pub fn set_pager_detection(enabled: bool) {
+    DETECTION.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if pager detection is enabled.
+pub(crate) fn pager_detection() -> bool {
+    DETECTION.load(Ordering::Relaxed)
+}
+
+/// Return name of pager reading standard output, see [`color_pager_fd`].
+pub fn color_pager() -> Option<String> {
+    color_pager_fd(crate::Stream::Stdout.fd())
+}
+
+/// Return name of pager reading file descriptor through pipe, or `None` if
+/// reader is not known to display colors. Works regardless of
+/// [`set_pager_detection`].
+pub fn color_pager_fd(fd: libc::c_int) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::color_pager(fd)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = fd;
+        None
+    }
+}
+
+/// Check if `less` options make it display colors, with or without leading
+/// `-` as allowed in `LESS`.
+#[cfg(target_os = "linux")]
+fn less_raw<'a, I: IntoIterator<Item = &'a str>>(args: I) -> bool {
+    args.into_iter().any(|arg| match arg.strip_prefix("--") {
+        Some(long) => long.eq_ignore_ascii_case("raw-control-chars"),
+        None => arg.trim_start_matches('-').contains(['R', 'r']),
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+    use std::path::Path;
+    use std::process;
+
+    use super::{less_raw, COLOR_PAGERS};
+
+    /// Return ID of other process with pipe of file descriptor as standard
+    /// input.
+    fn reader(fd: libc::c_int) -> Option<u32> {
+        let pipe = fs::read_link(format!("/proc/self/fd/{}", fd)).ok()?;
+        if !pipe.to_str()?.starts_with("pipe:") {
+            return None;
+        }
+        fs::read_dir("/proc")
+            .ok()?
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter(|&pid| pid != process::id())
+            .find(|pid| fs::read_link(format!("/proc/{}/fd/0", pid)).is_ok_and(|fd| fd == pipe))
+    }
+
+    /// Return NUL separated fields of process file.
+    fn fields(pid: u32, file: &str) -> Vec<String> {
+        let data = fs::read(format!("/proc/{}/{}", pid, file)).unwrap_or_default();
+        data.split(|&b| b == 0)
+            .filter(|field| !field.is_empty())
+            .map(|field| String::from_utf8_lossy(field).into_owned())
+            .collect()
+    }
+
+    pub(super) fn color_pager(fd: libc::c_int) -> Option<String> {
+        let pid = reader(fd)?;
+        let args = fields(pid, "cmdline");
+        let name = Path::new(args.first()?).file_name()?.to_str()?.to_string();
+        if COLOR_PAGERS.contains(&name.as_str()) {
+            return Some(name);
+        }
+        if name != "less" {
+            return None;
+        }
+        let environ = fields(pid, "environ");
+        let less = environ.iter().find_map(|var| var.strip_prefix("LESS="));
+        let options = less.into_iter().flat_map(str::split_whitespace);
+        let args = args[1..].iter().map(String::as_str);
+        let args = args
+            .take_while(|&arg| arg != "--")
+            .filter(|arg| arg.starts_with('-'));
+        if less_raw(args.chain(options)) {
+            Some(name)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Color decision from environment variables.
+//!
+//! Precedence, first match wins:
+//!
+//! 1. `NO_COLOR` set to any value disables colors.
+//! 2. `FORCE_COLOR` set to `0` or `false` disables colors, any other value
+//!    forces colors.
+//! 3. `CLICOLOR_FORCE` set to non-empty value other than `0` forces colors.
+//! 4. `CLICOLOR=0` disables colors.
+//! 5. `color` in user configuration, see [`Config`](crate::Config).
+//! 6. Colors are enabled if output is TTY and terminal supports colors, see
+//!    [`ColorSupport::detect`](crate::ColorSupport::detect). Output piped into
+//!    a pager displaying colors counts as TTY if enabled with
+//!    [`set_pager_detection`](crate::set_pager_detection).
+//!
+//! Forced colors are used even during redirection or piping.
+//!
+//! Programs with a `--color` argument can parse it into [`ColorMode`] and pass
+//! it to [`init_with`](crate::init_with), `auto` follows the precedence above.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! if colors::ColorChoice::detect() == colors::ColorChoice::Forced {
+//!     eprintln!("colors forced by environment");
+//! }
+//!
+//! let mode: colors::ColorMode = args.color.parse()?;
+//! let ansi = colors::init_with(mode);
+//! ```
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::pager::{color_pager_fd, pager_detection};
+use crate::{config, is_tty_fd, ColorSupport, Stream};
+
+/// Decision whether colors should be used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Colors requested by environment regardless of TTY.
+    Forced,
+    /// Colors enabled since output is TTY supporting colors.
+    Enabled,
+    /// Colors disabled by environment or since output is not TTY or does not
+    /// support colors.
+    Disabled,
+}
+
+impl ColorChoice {
+    /// Return decision made by environment variables alone, or `None` if they
+    /// leave it to TTY detection.
+    pub fn from_env() -> Option<ColorChoice> {
+        if env::var_os("NO_COLOR").is_some() {
+            return Some(ColorChoice::Disabled);
+        }
+        if let Ok(value) = env::var("FORCE_COLOR") {
+            return match value.as_str() {
+                "0" | "false" => Some(ColorChoice::Disabled),
+                _ => Some(ColorChoice::Forced),
+            };
+        }
+        if let Ok(value) = env::var("CLICOLOR_FORCE") {
+            if !value.is_empty() && value != "0" {
+                return Some(ColorChoice::Forced);
+            }
+        }
+        if env::var("CLICOLOR").is_ok_and(|value| value == "0") {
+            return Some(ColorChoice::Disabled);
+        }
+        None
+    }
+
+    /// Return decision for standard output.
+    pub fn detect() -> ColorChoice {
+        ColorChoice::detect_fd(Stream::Stdout.fd())
+    }
+
+    /// Return decision for given file descriptor.
+    pub fn detect_fd(fd: libc::c_int) -> ColorChoice {
+        ColorChoice::from_env()
+            .or(config().color)
+            .unwrap_or_else(|| {
+                let tty = is_tty_fd(fd) || pager_detection() && color_pager_fd(fd).is_some();
+                if tty && ColorSupport::detect() != ColorSupport::NoColor {
+                    ColorChoice::Enabled
+                } else {
+                    ColorChoice::Disabled
+                }
+            })
+    }
+
+    /// Check if colors should be used.
+    pub fn is_enabled(self) -> bool {
+        self != ColorChoice::Disabled
+    }
+}
+
+/// Color selection of `--color=auto|always|never` argument.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorMode {
+    /// Detect, see [`ColorChoice::detect`].
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Error returned when parsing unknown [`ColorMode`] name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorModeError(String);
+
+impl fmt::Display for ParseColorModeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown color mode '{}', expected auto, always or never",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseColorModeError {}
+
+impl FromStr for ColorMode {
+    type Err = ParseColorModeError;
+
+    fn from_str(s: &str) -> Result<ColorMode, ParseColorModeError> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" | "on" | "yes" => Ok(ColorMode::Always),
+            "never" | "off" | "no" => Ok(ColorMode::Never),
+            _ => Err(ParseColorModeError(s.to_string())),
+        }
+    }
+}
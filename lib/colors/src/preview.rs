@@ -0,0 +1,44 @@
+//! Dry-run preview styling.
+//!
+//! Actions a program would perform are rendered dim and italic behind a
+//! `[dry-run]` badge, so previews look the same in every tool. The badge is
+//! kept when colors are disabled since it carries the actual information.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! print!("{}", colors::preview(&ansi, |style| style.paint(&ansi, "rm -r build/")));
+//! ```
+
+use crate::{Attr, Codes, Color, Style};
+
+/// Badge put in front of every preview line.
+pub const DRY_RUN_BADGE: &str = "[dry-run]";
+
+/// Return style used for would-be actions.
+pub fn dry_run_style() -> Style {
+    Style::new().attr(Attr::Dim).attr(Attr::Italic)
+}
+
+/// Return output of `render`, given the dry-run style, with badge in front
+/// of every line.
+pub fn preview<F: FnOnce(&Style) -> String>(codes: &Codes, render: F) -> String {
+    let badge = Style::new().fg(Color::Yellow).attr(Attr::Bold);
+    let badge = badge.paint(codes, DRY_RUN_BADGE);
+
+    let mut output = String::new();
+    for line in render(&dry_run_style()).lines() {
+        output.push_str(&format!("{} {}\n", badge, line));
+    }
+    output
+}
+
+/// Return plain text rendered as preview.
+pub fn preview_text(codes: &Codes, text: &str) -> String {
+    preview(codes, |style| {
+        text.lines()
+            .map(|line| style.paint(codes, line) + "\n")
+            .collect()
+    })
+}
@@ -0,0 +1,110 @@
+//! Output profiles bundling presentation choices.
+//!
+//! A single user facing knob, e.g. `--output=plain|minimal|rich`, selects
+//! colors, Unicode glyphs, animations and hyperlinks together. Once set with
+//! [`set_profile`] all widgets follow it, without a profile every feature is
+//! detected on its own.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let profile: colors::Profile = args.output.parse()?;
+//! colors::set_profile(profile);
+//! let ansi = profile.init();
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::{init_auto, init_off, Codes};
+
+/// Profile set with [`set_profile`].
+static PROFILE: Mutex<Option<Profile>> = Mutex::new(None);
+
+/// Named bundle of output behavior.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Profile {
+    /// No colors, ASCII only, no animations or hyperlinks.
+    Plain,
+    /// Colors and Unicode where supported, no animations or hyperlinks.
+    Minimal,
+    /// Everything supported by the terminal.
+    Rich,
+}
+
+impl Profile {
+    /// Check if colors may be used.
+    pub fn colors(self) -> bool {
+        self != Profile::Plain
+    }
+
+    /// Check if Unicode glyphs may be used.
+    pub fn unicode(self) -> bool {
+        self != Profile::Plain
+    }
+
+    /// Check if animations and in place repaints may be used.
+    pub fn animations(self) -> bool {
+        self == Profile::Rich
+    }
+
+    /// Check if hyperlinks may be used.
+    pub fn hyperlinks(self) -> bool {
+        self == Profile::Rich
+    }
+
+    /// Return codes for profile, i.e. [`init_off`] for plain profile and
+    /// [`init_auto`] otherwise.
+    pub fn init(self) -> Codes {
+        if self.colors() {
+            init_auto()
+        } else {
+            init_off()
+        }
+    }
+}
+
+/// Error returned when parsing unknown [`Profile`] name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseProfileError(String);
+
+impl fmt::Display for ParseProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown output profile '{}', expected plain, minimal or rich",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseProfileError {}
+
+impl FromStr for Profile {
+    type Err = ParseProfileError;
+
+    fn from_str(s: &str) -> Result<Profile, ParseProfileError> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Ok(Profile::Plain),
+            "minimal" => Ok(Profile::Minimal),
+            "rich" => Ok(Profile::Rich),
+            _ => Err(ParseProfileError(s.to_string())),
+        }
+    }
+}
+
+/// Set profile followed by all widgets for whole program.
+pub fn set_profile(profile: Profile) {
+    *PROFILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(profile);
+}
+
+/// Return profile set with [`set_profile`], if any.
+pub fn profile() -> Option<Profile> {
+    *PROFILE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Check if feature is allowed by current profile, `true` without profile.
+pub(crate) fn allows(feature: fn(Profile) -> bool) -> bool {
+    profile().is_none_or(feature)
+}
@@ -0,0 +1,195 @@
+//! Child process output capture through a pseudo-terminal.
+//!
+//! Programs typically disable colors when their output is not a TTY. Running
+//! them under a pseudo-terminal makes them emit styled output, which can then
+//! be captured and processed further, e.g. with [`reset_lines`] or
+//! [`strip_with`]. Unix uses libc openpty(), Windows uses pseudo consoles
+//! (ConPTY) of Windows 10 version 1809 and later, capturing fails with
+//! [`io::ErrorKind::Unsupported`] on older versions. Pseudo consoles render
+//! output like a console window, so it can contain cursor movement and clears
+//! the program did not print itself.
+//!
+//! [`reset_lines`]: crate::reset_lines
+//! [`strip_with`]: crate::ansi::strip_with
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let mut command = Command::new("cargo");
+//! command.arg("build");
+//! let captured = colors::capture_pty(command)?;
+//! print!("{}", captured.output);
+//! ```
+
+#[cfg(unix)]
+use std::fs::File;
+use std::io;
+#[cfg(unix)]
+use std::io::Read;
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(unix)]
+use std::process::Stdio;
+use std::process::{Command, ExitStatus};
+
+use crate::term;
+
+/// Output and exit status of process run under pseudo-terminal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Captured {
+    pub status: ExitStatus,
+    /// Combined standard output and standard error, with `\r\n` line endings
+    /// added by the terminal turned back into `\n`.
+    pub output: String,
+}
+
+impl Captured {
+    /// Return captured output of process exited with status.
+    fn new(status: ExitStatus, bytes: &[u8]) -> Captured {
+        let output = String::from_utf8_lossy(bytes).replace("\r\n", "\n");
+        Captured { status, output }
+    }
+}
+
+/// Append argument to Windows command line, quoted as the C runtime parses
+/// it if needed.
+#[cfg(any(windows, test))]
+fn push_arg(line: &mut Vec<u16>, arg: &[u16]) {
+    let (quote, backslash) = (u16::from(b'"'), u16::from(b'\\'));
+    if !line.is_empty() {
+        line.push(u16::from(b' '));
+    }
+    let special = [quote, u16::from(b' '), u16::from(b'\t')];
+    if !arg.is_empty() && !arg.iter().any(|c| special.contains(c)) {
+        line.extend_from_slice(arg);
+        return;
+    }
+    // Backslashes are literal unless they precede a quote.
+    line.push(quote);
+    let mut backslashes = 0;
+    for &c in arg {
+        if c == backslash {
+            backslashes += 1;
+        } else {
+            if c == quote {
+                line.extend(std::iter::repeat_n(backslash, backslashes + 1));
+            }
+            backslashes = 0;
+        }
+        line.push(c);
+    }
+    line.extend(std::iter::repeat_n(backslash, backslashes));
+    line.push(quote);
+}
+
+/// Run command with standard input, output and error connected to new
+/// pseudo-terminal and return everything it printed.
+///
+/// Pseudo-terminal has size of current terminal, or 80x24 if unknown. On
+/// Windows environment changes and working directory of command are applied,
+/// its standard input, output and error settings are ignored.
+#[cfg(unix)]
+pub fn capture_pty(mut command: Command) -> io::Result<Captured> {
+    let (cols, rows) = term::size().unwrap_or((80, 24));
+    let ws = libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    let mut master = -1;
+    let mut slave = -1;
+    let res = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            &ws,
+        )
+    };
+    if res != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut master = unsafe { File::from_raw_fd(master) };
+    let slave = unsafe { File::from_raw_fd(slave) };
+
+    let stdio = || slave.try_clone().map(Stdio::from);
+    command.stdin(stdio()?).stdout(stdio()?).stderr(stdio()?);
+    unsafe {
+        command.pre_exec(|| {
+            // New session with pseudo-terminal as controlling terminal.
+            if libc::setsid() < 0 || libc::ioctl(0, libc::TIOCSCTTY, 0) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn()?;
+    // Command keeps its copies until dropped, reading would never end.
+    drop((slave, command));
+
+    let mut bytes = Vec::new();
+    let mut buf = [0; 4096];
+    loop {
+        match master.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => bytes.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            // Linux reports EIO once all slave descriptors are closed.
+            Err(e) if e.raw_os_error() == Some(libc::EIO) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(Captured::new(child.wait()?, &bytes))
+}
+
+#[cfg(windows)]
+pub fn capture_pty(command: Command) -> io::Result<Captured> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let (cols, rows) = term::size().unwrap_or((80, 24));
+    let mut line = Vec::new();
+    for arg in std::iter::once(command.get_program()).chain(command.get_args()) {
+        push_arg(&mut line, &arg.encode_wide().collect::<Vec<_>>());
+    }
+    let (status, bytes) = crate::windows::capture_conpty(&command, line, cols, rows)?;
+    Ok(Captured::new(status, &bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return command line of arguments.
+    fn line(args: &[&str]) -> String {
+        let mut line = Vec::new();
+        for arg in args {
+            push_arg(&mut line, &arg.encode_utf16().collect::<Vec<_>>());
+        }
+        String::from_utf16(&line).unwrap()
+    }
+
+    #[test]
+    fn quote_arguments() {
+        assert_eq!(line(&["cargo", "build"]), "cargo build");
+        assert_eq!(line(&["a b", ""]), r#""a b" """#);
+        assert_eq!(line(&[r"C:\dir\", r"C:\dir\ x"]), r#"C:\dir\ "C:\dir\ x""#);
+        assert_eq!(line(&[r#"say "hi""#]), r#""say \"hi\"""#);
+        assert_eq!(line(&[r#"a\"b"#, r"a b\"]), r#""a\\\"b" "a b\\""#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn capture_tty_output() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "test -t 1 && printf 'tty\\n'; exit 3"]);
+        let captured = capture_pty(command).unwrap();
+        assert_eq!(captured.output, "tty\n");
+        assert_eq!(captured.status.code(), Some(3));
+    }
+}
@@ -0,0 +1,120 @@
+//! Recording of styled output.
+//!
+//! A [`Recorder`] is a writer keeping everything written to it together with
+//! the time it was written, for export as HTML or asciicast. Output can also
+//! be echoed to standard output while recording.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let mut rec = colors::Recorder::new();
+//! writeln!(rec, "{}", colors::Level::Success.style().paint(rec.codes(), "done"))?;
+//! fs::write("run.cast", rec.to_asciicast(80, 24))?;
+//! ```
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::{ansi, init_auto, init_on, json, Codes};
+
+/// Writer recording output with timestamps.
+#[derive(Debug)]
+pub struct Recorder {
+    codes: Codes,
+    start: Instant,
+    events: Vec<(Duration, String)>,
+    pending: Vec<u8>,
+    echo: Option<bool>,
+}
+
+impl Recorder {
+    /// Return empty recorder, time starts now.
+    pub fn new() -> Recorder {
+        Recorder {
+            codes: init_on(),
+            start: Instant::now(),
+            events: Vec::new(),
+            pending: Vec::new(),
+            echo: None,
+        }
+    }
+
+    /// Echo output to standard output as it is written, with escape
+    /// sequences stripped if standard output has colors disabled.
+    pub fn echo(mut self) -> Recorder {
+        self.echo = Some(init_auto().is_enabled());
+        self
+    }
+
+    /// Return codes with colors enabled, use to style recorded output
+    /// regardless of where the program itself writes.
+    pub fn codes(&self) -> &Codes {
+        &self.codes
+    }
+
+    /// Return chunks of output with time since start when they were written.
+    pub fn events(&self) -> &[(Duration, String)] {
+        &self.events
+    }
+
+    /// Return all recorded output.
+    pub fn output(&self) -> String {
+        let mut output: String = self.events.iter().map(|(_, text)| text.as_str()).collect();
+        output.push_str(&String::from_utf8_lossy(&self.pending));
+        output
+    }
+
+    /// Return recording as asciicast v2 file for terminal of given size.
+    pub fn to_asciicast(&self, width: u16, height: u16) -> String {
+        let header = json::Object::new()
+            .raw("version", "2")
+            .raw("width", &width.to_string())
+            .raw("height", &height.to_string())
+            .finish();
+        let mut output = format!("{}\n", header);
+        for (time, text) in &self.events {
+            output.push_str(&format!(
+                "[{:.6}, \"o\", {}]\n",
+                time.as_secs_f64(),
+                json::quote(text)
+            ));
+        }
+        output
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Recorder {
+        Recorder::new()
+    }
+}
+
+impl Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        // Keep incomplete UTF-8 sequences until rest arrives.
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        if valid > 0 {
+            let text = String::from_utf8_lossy(&self.pending[..valid]).into_owned();
+            self.pending.drain(..valid);
+            match self.echo {
+                Some(true) => io::stdout().write_all(text.as_bytes())?,
+                Some(false) => io::stdout().write_all(ansi::strip(&text).as_bytes())?,
+                None => (),
+            }
+            self.events.push((self.start.elapsed(), text));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.echo {
+            Some(_) => io::stdout().flush(),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Playback of recorded sessions.
+//!
+//! Plays asciicast v2 files, e.g. written by [`Recorder::to_asciicast`] or
+//! asciinema, in the terminal with original timing. Every chunk is written as
+//! synchronized update to avoid tearing.
+//!
+//! [`Recorder::to_asciicast`]: crate::Recorder::to_asciicast
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! colors::replay("run.cast", 2.0)?;
+//! ```
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::signal::Interrupt;
+use crate::stats::{self, Source};
+use crate::{cursor, is_tty, json, sync};
+
+/// Return time and data of output event line, e.g. `[1.5, "o", "text"]`, or
+/// `None` for other events.
+fn parse_event(line: &str) -> Option<(f64, String)> {
+    let line = line.trim().strip_prefix('[')?;
+    let (time, rest) = line.split_once(',')?;
+    let time: f64 = time.trim().parse().ok()?;
+    let (kind, rest) = json::parse_string(rest.trim_start())?;
+    if kind != "o" {
+        return None;
+    }
+    let rest = rest.trim_start().strip_prefix(',')?;
+    let (data, _) = json::parse_string(rest.trim_start())?;
+    Some((time, data))
+}
+
+/// Longest sleep between checks for Ctrl-C.
+const SLICE: Duration = Duration::from_millis(50);
+
+/// Return time after start that event at `time` seconds is due at `speed`,
+/// or error if it is too large to represent, e.g. infinite.
+fn due(time: f64, speed: f64) -> io::Result<Duration> {
+    Duration::try_from_secs_f64(time.max(0.0) / speed)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid event time"))
+}
+
+/// Play asciicast v2 file at `speed` times original speed and return `false`
+/// if stopped early by Ctrl-C. Attributes are reset and cursor shown after
+/// playback.
+///
+/// If standard output is not a TTY all output is written at once.
+pub fn replay<P: AsRef<Path>>(path: P, speed: f64) -> io::Result<bool> {
+    let cast = fs::read_to_string(path)?;
+    let mut lines = cast.lines();
+    let header = lines.next().unwrap_or_default();
+    if !header.contains("\"version\":2") && !header.contains("\"version\": 2") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an asciicast v2 file",
+        ));
+    }
+
+    let mut stdout = io::stdout().lock();
+    let tty = is_tty();
+    let speed = if speed > 0.0 { speed } else { 1.0 };
+    let interrupt = Interrupt::catch();
+    let start = Instant::now();
+    let mut completed = true;
+
+    for (time, data) in lines.filter_map(parse_event) {
+        if tty {
+            let due = due(time, speed)?;
+            while let Some(wait) = due.checked_sub(start.elapsed()) {
+                if wait.is_zero() || interrupt.triggered() {
+                    break;
+                }
+                thread::sleep(wait.min(SLICE));
+            }
+            if interrupt.triggered() {
+                completed = false;
+                break;
+            }
+            let frame = format!("{}{}{}", sync::BEGIN, data, sync::END);
+            stdout.write_all(frame.as_bytes())?;
+            stats::record(Source::Replay, frame.as_bytes(), false);
+            stdout.flush()?;
+        } else {
+            stdout.write_all(data.as_bytes())?;
+            stats::record(Source::Replay, data.as_bytes(), false);
+        }
+    }
+    if tty {
+        write!(stdout, "\x1B[0m{}", cursor::SHOW)?;
+    }
+    stdout.flush()?;
+
+    Ok(completed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_output_events() {
+        assert_eq!(
+            parse_event(r#"[1.5, "o", "a\u001b[1m"]"#),
+            Some((1.5, "a\x1B[1m".to_string()))
+        );
+        assert_eq!(parse_event(r#"[2.0, "i", "x"]"#), None);
+        assert_eq!(parse_event(r#"{"version": 2}"#), None);
+        assert_eq!(
+            parse_event(r#"[1e400, "o", "x"]"#).map(|e| e.0),
+            Some(f64::INFINITY)
+        );
+    }
+
+    #[test]
+    fn due_times() {
+        assert_eq!(due(3.0, 2.0).unwrap(), Duration::from_millis(1500));
+        assert_eq!(due(-1.0, 1.0).unwrap(), Duration::ZERO);
+        assert!(due(f64::INFINITY, 1.0).is_err());
+        assert!(due(1e300, 1e-300).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Session capture to standalone HTML reports.
+//!
+//! Everything a closure writes to the given [`Recorder`] is echoed to
+//! standard output as usual and kept, so it can be saved as an HTML file for
+//! attaching to tickets or CI artifacts.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let report = colors::Report::capture(|out| {
+//!     let ok = colors::Level::Success.style().paint(out.codes(), "ok");
+//!     writeln!(out, "build {}", ok)
+//! })?;
+//! report.theme(colors::Theme::detect()).save("report.html", "Build")?;
+//! ```
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::{to_html_with, Recorder, Theme};
+
+/// Recorded output of run.
+#[derive(Debug)]
+pub struct Report {
+    recorder: Recorder,
+    theme: Theme,
+}
+
+impl Report {
+    /// Run closure and return report of everything it wrote to recorder.
+    /// Output is echoed to standard output while running.
+    pub fn capture<F>(f: F) -> io::Result<Report>
+    where
+        F: FnOnce(&mut Recorder) -> io::Result<()>,
+    {
+        let mut recorder = Recorder::new().echo();
+        f(&mut recorder)?;
+        recorder.flush()?;
+        Ok(Report {
+            recorder,
+            theme: Theme::new(),
+        })
+    }
+
+    /// Return report rendering HTML with colors of theme palette instead of
+    /// [`Theme::new`].
+    pub fn theme(self, theme: Theme) -> Report {
+        Report { theme, ..self }
+    }
+
+    /// Return recorder holding captured output.
+    pub fn recorder(&self) -> &Recorder {
+        &self.recorder
+    }
+
+    /// Return captured output as standalone HTML document with colors of
+    /// theme palette.
+    pub fn to_html(&self, title: &str) -> String {
+        to_html_with(&self.theme.palette, &self.recorder.output(), title)
+    }
+
+    /// Write captured output as HTML document to file.
+    pub fn save<P: AsRef<Path>>(&self, path: P, title: &str) -> io::Result<()> {
+        fs::write(path, self.to_html(title))
+    }
+}
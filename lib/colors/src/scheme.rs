@@ -0,0 +1,651 @@
+//! Terminal emulator color schemes.
+//!
+//! A [`Palette`] holds the colors a terminal emulator itself is configured
+//! with, i.e. default foreground, background and the 16 ANSI colors. Themes
+//! carry one so they can be exported with [`Theme::export`] and applied to
+//! the terminal for a consistent look. Published schemes are imported with
+//! [`Theme::import`].
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! use colors::scheme::Format;
+//!
+//! let theme = colors::Theme::detect();
+//! fs::write("skrot.itermcolors", theme.export(Format::ItermColors))?;
+//!
+//! let text = fs::read_to_string("gruvbox-dark.yaml")?;
+//! let theme = colors::Theme::import(&text, Format::Base16Yaml)?;
+//! ```
+//!
+//! [`Theme::export`]: crate::Theme::export
+//! [`Theme::import`]: crate::Theme::import
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use crate::convert::mix;
+use crate::json;
+use crate::{Attr, Color, Style, Theme};
+
+/// Name of exported schemes for formats requiring one.
+const NAME: &str = "Skrot";
+
+/// Scheme color names used by Windows Terminal, in [`Color`] order.
+const WINDOWS_TERMINAL_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "purple",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightPurple",
+    "brightCyan",
+    "brightWhite",
+];
+
+/// Colors of terminal emulator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Palette {
+    pub foreground: (u8, u8, u8),
+    pub background: (u8, u8, u8),
+    /// ANSI 16 colors in [`Color`] order.
+    pub colors: [(u8, u8, u8); 16],
+}
+
+impl Palette {
+    /// Return built-in palette resembling common terminal defaults on dark
+    /// background.
+    pub const fn new() -> Palette {
+        Palette {
+            foreground: (0xd0, 0xd0, 0xd0),
+            background: (0x1c, 0x1c, 0x1c),
+            colors: [
+                (0x00, 0x00, 0x00),
+                (0xcd, 0x31, 0x31),
+                (0x0d, 0xbc, 0x79),
+                (0xe5, 0xe5, 0x10),
+                (0x24, 0x72, 0xc8),
+                (0xbc, 0x3f, 0xbc),
+                (0x11, 0xa8, 0xcd),
+                (0xe5, 0xe5, 0xe5),
+                (0x66, 0x66, 0x66),
+                (0xf1, 0x4c, 0x4c),
+                (0x23, 0xd1, 0x8b),
+                (0xf5, 0xf5, 0x43),
+                (0x3b, 0x8e, 0xea),
+                (0xd6, 0x70, 0xd6),
+                (0x29, 0xb8, 0xdb),
+                (0xff, 0xff, 0xff),
+            ],
+        }
+    }
+
+    /// Return RGB value of palette color.
+    pub fn get(&self, color: Color) -> (u8, u8, u8) {
+        self.colors[color as usize]
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::new()
+    }
+}
+
+/// Base16 slots used for ANSI 16 colors, in [`Color`] order, following
+/// base16-shell.
+const BASE16_COLORS: [usize; 16] = [
+    0x00, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x05, 0x03, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x07,
+];
+
+/// Base24 slots used for ANSI 16 colors, in [`Color`] order.
+const BASE24_COLORS: [usize; 16] = [
+    0x00, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x06, 0x02, 0x12, 0x14, 0x13, 0x16, 0x17, 0x15, 0x07,
+];
+
+/// Base16 scheme, optionally extended to Base24.
+///
+/// Slots have fixed meanings: `base00` to `base07` run from default
+/// background to brightest foreground, with `base03` for comments and
+/// `base05` for default foreground, `base08` to `base0F` are accents starting
+/// with red for errors. Base24 adds darker backgrounds `base10` and `base11`
+/// and bright accents `base12` to `base17`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Base16 {
+    /// Slots `base00` to `base0F`.
+    pub base: [(u8, u8, u8); 16],
+    /// Base24 slots `base10` to `base17`.
+    pub base24: Option<[(u8, u8, u8); 8]>,
+}
+
+impl Base16 {
+    /// Parse base16 or base24 YAML scheme, slots may be nested below
+    /// `palette` key.
+    pub fn parse(text: &str) -> Result<Base16, ParseSchemeError> {
+        let mut slots: [Option<(u8, u8, u8)>; 24] = [None; 24];
+
+        for line in text.lines() {
+            let (key, value) = match line.trim().split_once(':') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let index = match key.trim().strip_prefix("base") {
+                Some(index) if index.len() == 2 => match usize::from_str_radix(index, 16) {
+                    Ok(index) if index < slots.len() => index,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let value = value.trim();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next(),
+                _ => value.split_whitespace().next(),
+            };
+            slots[index] = Some(
+                value
+                    .and_then(parse_hex)
+                    .ok_or_else(|| ParseSchemeError(format!("invalid {}", key.trim())))?,
+            );
+        }
+
+        let slot = |i: usize| slots[i].ok_or_else(|| missing(&format!("base{:02X}", i)));
+        let mut base = [(0, 0, 0); 16];
+        for (i, rgb) in base.iter_mut().enumerate() {
+            *rgb = slot(i)?;
+        }
+        let base24 = if slots[16..].iter().any(Option::is_some) {
+            let mut base24 = [(0, 0, 0); 8];
+            for (i, rgb) in base24.iter_mut().enumerate() {
+                *rgb = slot(i + 16)?;
+            }
+            Some(base24)
+        } else {
+            None
+        };
+        Ok(Base16 { base, base24 })
+    }
+
+    /// Return base16 scheme for palette, slots without ANSI counterpart are
+    /// mixed from neighbouring colors.
+    pub fn from_palette(palette: &Palette) -> Base16 {
+        let c = |color: Color| palette.get(color);
+        let (bg, fg) = (palette.background, palette.foreground);
+        Base16 {
+            base: [
+                bg,
+                mix(bg, fg, 0.1),
+                mix(bg, fg, 0.2),
+                c(Color::BrightBlack),
+                mix(bg, fg, 0.6),
+                fg,
+                mix(fg, c(Color::BrightWhite), 0.5),
+                c(Color::BrightWhite),
+                c(Color::Red),
+                mix(c(Color::Red), c(Color::Yellow), 0.5),
+                c(Color::Yellow),
+                c(Color::Green),
+                c(Color::Cyan),
+                c(Color::Blue),
+                c(Color::Magenta),
+                mix(c(Color::Red), c(Color::Black), 0.5),
+            ],
+            base24: None,
+        }
+    }
+
+    /// Return RGB value of slot `0x00` to `0x17`, or `None` for other
+    /// slots. Base24 slots fall back to their base16 counterparts.
+    pub fn get(&self, slot: usize) -> Option<(u8, u8, u8)> {
+        match (slot, self.base24) {
+            (0x00..=0x0F, _) => Some(self.base[slot]),
+            (0x10..=0x17, Some(base24)) => Some(base24[slot - 0x10]),
+            (0x10 | 0x11, None) => Some(self.base[0x00]),
+            (0x12..=0x17, None) => {
+                Some(self.base[[0x08, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E][slot - 0x12]])
+            }
+            _ => None,
+        }
+    }
+
+    /// Return terminal palette of scheme, following base16-shell or base24
+    /// mapping of slots onto ANSI colors.
+    pub fn palette(&self) -> Palette {
+        let mapping = match self.base24 {
+            Some(_) => &BASE24_COLORS,
+            None => &BASE16_COLORS,
+        };
+        let mut colors = [(0, 0, 0); 16];
+        for (rgb, &slot) in colors.iter_mut().zip(mapping) {
+            *rgb = self.get(slot).unwrap_or_default();
+        }
+        Palette {
+            foreground: self.base[0x05],
+            background: self.base[0x00],
+            colors,
+        }
+    }
+
+    /// Return theme using scheme palette with semantic roles following slot
+    /// meanings, e.g. muted text in comment color `base03`.
+    pub fn theme(&self) -> Theme {
+        let palette = self.palette();
+        let muted = match self.base24 {
+            // Base24 maps selection background base02 onto bright black, see
+            // `palette`, too dark for text on default background.
+            Some(_) => Style::new().attr(Attr::Dim),
+            None => Style::new().fg(Color::BrightBlack),
+        };
+        Theme {
+            muted,
+            palette,
+            ..Theme::new()
+        }
+    }
+
+    /// Return scheme as base16 YAML, including base24 slots if present.
+    pub fn to_yaml(&self) -> String {
+        let mut output = format!("scheme: \"{}\"\nauthor: \"\"\n", NAME);
+        let slots = 16 + self.base24.map_or(0, |base24| base24.len());
+        for (i, rgb) in (0..slots).filter_map(|i| Some((i, self.get(i)?))) {
+            let _ = writeln!(output, "base{:02X}: \"{}\"", i, &hex(rgb)[1..]);
+        }
+        output
+    }
+}
+
+/// Scheme file format of terminal emulator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// iTerm2 `.itermcolors` property list.
+    ItermColors,
+    /// Scheme object for `schemes` list of Windows Terminal `settings.json`.
+    WindowsTerminalJson,
+    /// X resources for xterm, URxvt and compatible terminals.
+    Xresources,
+    /// Base16 or Base24 scheme, see [`Base16`].
+    Base16Yaml,
+}
+
+/// Error returned when importing invalid scheme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSchemeError(String);
+
+impl fmt::Display for ParseSchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid color scheme, {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseSchemeError {}
+
+/// Return color as `#rrggbb`.
+pub(crate) fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Parse color written as `rrggbb` or `#rrggbb`.
+pub(crate) fn parse_hex(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value.strip_prefix('#').unwrap_or(value);
+    if value.len() != 6 || !value.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&value[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Return palette in given format.
+pub(crate) fn export(palette: &Palette, format: Format) -> String {
+    match format {
+        Format::ItermColors => iterm_colors(palette),
+        Format::WindowsTerminalJson => windows_terminal(palette),
+        Format::Xresources => xresources(palette),
+        Format::Base16Yaml => Base16::from_palette(palette).to_yaml(),
+    }
+}
+
+/// Parse palette from text in given format.
+pub(crate) fn import(text: &str, format: Format) -> Result<Palette, ParseSchemeError> {
+    match format {
+        Format::ItermColors => parse_iterm_colors(text),
+        Format::WindowsTerminalJson => parse_windows_terminal(text),
+        Format::Xresources => parse_xresources(text),
+        Format::Base16Yaml => Ok(Base16::parse(text)?.palette()),
+    }
+}
+
+/// Return error for missing color.
+fn missing(name: &str) -> ParseSchemeError {
+    ParseSchemeError(format!("missing {}", name))
+}
+
+/// Return palette as iTerm2 property list.
+fn iterm_colors(palette: &Palette) -> String {
+    let mut entries: Vec<(String, (u8, u8, u8))> = palette
+        .colors
+        .iter()
+        .enumerate()
+        .map(|(i, &rgb)| (format!("Ansi {} Color", i), rgb))
+        .collect();
+    entries.push(("Background Color".to_string(), palette.background));
+    entries.push(("Foreground Color".to_string(), palette.foreground));
+    entries.push(("Cursor Color".to_string(), palette.foreground));
+    entries.push(("Cursor Text Color".to_string(), palette.background));
+
+    let mut output = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+         \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n",
+    );
+    for (key, (r, g, b)) in entries {
+        let _ = write!(output, "\t<key>{}</key>\n\t<dict>\n", key);
+        for (component, value) in [("Alpha", 255), ("Blue", b), ("Green", g), ("Red", r)] {
+            let _ = write!(
+                output,
+                "\t\t<key>{} Component</key>\n\t\t<real>{}</real>\n",
+                component,
+                f64::from(value) / 255.0
+            );
+        }
+        output.push_str("\t\t<key>Color Space</key>\n\t\t<string>sRGB</string>\n\t</dict>\n");
+    }
+    output.push_str("</dict>\n</plist>\n");
+    output
+}
+
+/// Return palette as Windows Terminal scheme object.
+fn windows_terminal(palette: &Palette) -> String {
+    let mut entries = vec![
+        ("name", json::quote(NAME)),
+        ("foreground", json::quote(&hex(palette.foreground))),
+        ("background", json::quote(&hex(palette.background))),
+        ("cursorColor", json::quote(&hex(palette.foreground))),
+    ];
+    for (name, &rgb) in WINDOWS_TERMINAL_NAMES.iter().zip(&palette.colors) {
+        entries.push((name, json::quote(&hex(rgb))));
+    }
+
+    let entries: Vec<String> = entries
+        .iter()
+        .map(|(key, value)| format!("    \"{}\": {}", key, value))
+        .collect();
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+/// Return palette as X resources.
+fn xresources(palette: &Palette) -> String {
+    let mut output = String::new();
+    let _ = writeln!(output, "*.foreground: {}", hex(palette.foreground));
+    let _ = writeln!(output, "*.background: {}", hex(palette.background));
+    let _ = writeln!(output, "*.cursorColor: {}", hex(palette.foreground));
+    for (i, &rgb) in palette.colors.iter().enumerate() {
+        let _ = writeln!(output, "*.color{}: {}", i, hex(rgb));
+    }
+    output
+}
+
+/// Parse iTerm2 property list, colors are read as sRGB components.
+fn parse_iterm_colors(text: &str) -> Result<Palette, ParseSchemeError> {
+    let mut colors: [Option<(u8, u8, u8)>; 16] = [None; 16];
+    let (mut foreground, mut background) = (None, None);
+
+    let mut rest = text;
+    while let Some(start) = rest.find("<key>") {
+        rest = &rest[start + 5..];
+        let end = rest.find("</key>").ok_or_else(|| missing("</key>"))?;
+        let key = &rest[..end];
+        rest = rest[end + 6..].trim_start();
+        if !rest.starts_with("<dict>") {
+            continue;
+        }
+        let end = rest.find("</dict>").ok_or_else(|| missing("</dict>"))?;
+        let rgb = parse_iterm_color(&rest[..end])
+            .ok_or_else(|| ParseSchemeError(format!("invalid {}", key)))?;
+        rest = &rest[end..];
+
+        match key {
+            "Foreground Color" => foreground = Some(rgb),
+            "Background Color" => background = Some(rgb),
+            key => {
+                let index = key
+                    .strip_prefix("Ansi ")
+                    .and_then(|key| key.strip_suffix(" Color"))
+                    .and_then(|index| index.parse::<usize>().ok());
+                if let Some(slot) = index.and_then(|index| colors.get_mut(index)) {
+                    *slot = Some(rgb);
+                }
+            }
+        }
+    }
+
+    palette(foreground, background, colors, |i| {
+        format!("Ansi {} Color", i)
+    })
+}
+
+/// Parse contents of iTerm2 color dictionary.
+fn parse_iterm_color(dict: &str) -> Option<(u8, u8, u8)> {
+    let component = |name: &str| -> Option<u8> {
+        let key = format!("<key>{} Component</key>", name);
+        let rest = dict[dict.find(&key)? + key.len()..].trim_start();
+        let rest = rest
+            .strip_prefix("<real>")
+            .or_else(|| rest.strip_prefix("<integer>"))?;
+        let value: f64 = rest[..rest.find('<')?].trim().parse().ok()?;
+        Some((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    };
+    Some((component("Red")?, component("Green")?, component("Blue")?))
+}
+
+/// Parse Windows Terminal scheme object.
+fn parse_windows_terminal(text: &str) -> Result<Palette, ParseSchemeError> {
+    let invalid = || ParseSchemeError("expected JSON object with string values".to_string());
+    let mut colors: [Option<(u8, u8, u8)>; 16] = [None; 16];
+    let (mut foreground, mut background) = (None, None);
+
+    let mut rest = text
+        .trim_start()
+        .strip_prefix('{')
+        .ok_or_else(invalid)?
+        .trim_start();
+    while !rest.starts_with('}') {
+        let (key, after) = json::parse_string(rest).ok_or_else(invalid)?;
+        let after = after.trim_start().strip_prefix(':').ok_or_else(invalid)?;
+        let (value, after) = json::parse_string(after.trim_start()).ok_or_else(invalid)?;
+        rest = after.trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+
+        let slot = match key.as_str() {
+            "foreground" => &mut foreground,
+            "background" => &mut background,
+            key => match WINDOWS_TERMINAL_NAMES.iter().position(|&name| name == key) {
+                Some(index) => &mut colors[index],
+                None => continue,
+            },
+        };
+        *slot =
+            Some(parse_hex(&value).ok_or_else(|| ParseSchemeError(format!("invalid {}", key)))?);
+    }
+
+    palette(foreground, background, colors, |i| {
+        WINDOWS_TERMINAL_NAMES[i].to_string()
+    })
+}
+
+/// Parse X resources, class or instance prefixes of names are ignored.
+/// Values may name macros of preceding `#define` lines, other preprocessor
+/// directives are skipped.
+fn parse_xresources(text: &str) -> Result<Palette, ParseSchemeError> {
+    let mut colors: [Option<(u8, u8, u8)>; 16] = [None; 16];
+    let (mut foreground, mut background) = (None, None);
+    let mut defines: HashMap<&str, &str> = HashMap::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(directive) = line.strip_prefix('#') {
+            let mut words = directive.trim_start().splitn(3, char::is_whitespace);
+            if let (Some("define"), Some(name), Some(value)) =
+                (words.next(), words.next(), words.next())
+            {
+                defines.insert(name, value.trim());
+            }
+            continue;
+        }
+        if line.starts_with('!') {
+            continue;
+        }
+        let (name, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let name = name.trim().rsplit(['.', '*']).next().unwrap_or_default();
+        let slot = match name {
+            "foreground" => &mut foreground,
+            "background" => &mut background,
+            name => match name
+                .strip_prefix("color")
+                .and_then(|i| i.parse::<usize>().ok())
+            {
+                Some(index) if index < 16 => &mut colors[index],
+                _ => continue,
+            },
+        };
+        let mut value = value.trim();
+        // Macros may expand to other macros, bounded in case of cycles.
+        for _ in 0..defines.len() {
+            match defines.get(value) {
+                Some(expansion) => value = expansion,
+                None => break,
+            }
+        }
+        *slot =
+            Some(parse_hex(value).ok_or_else(|| ParseSchemeError(format!("invalid {}", name)))?);
+    }
+
+    palette(foreground, background, colors, |i| format!("color{}", i))
+}
+
+/// Return palette if all colors are present, otherwise error naming first
+/// missing color.
+fn palette(
+    foreground: Option<(u8, u8, u8)>,
+    background: Option<(u8, u8, u8)>,
+    colors: [Option<(u8, u8, u8)>; 16],
+    name: impl Fn(usize) -> String,
+) -> Result<Palette, ParseSchemeError> {
+    let mut palette = Palette::new();
+    for (i, color) in colors.iter().enumerate() {
+        palette.colors[i] = color.ok_or_else(|| missing(&name(i)))?;
+    }
+    palette.foreground = foreground
+        .or(colors[7])
+        .ok_or_else(|| missing("foreground"))?;
+    palette.background = background
+        .or(colors[0])
+        .ok_or_else(|| missing("background"))?;
+    Ok(palette)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return base16 YAML with slot `i` set to `#iiiiii`, up to `slots`.
+    fn yaml(slots: usize) -> String {
+        let mut text = String::from("scheme: \"Test\"\npalette:\n");
+        for i in 0..slots {
+            let _ = writeln!(
+                text,
+                "  base{:02X}: \"{:02x}{:02x}{:02x}\" # slot",
+                i, i, i, i
+            );
+        }
+        text
+    }
+
+    #[test]
+    fn parse_base16() {
+        let scheme = Base16::parse(&yaml(16)).unwrap();
+        assert_eq!(scheme.base24, None);
+        assert_eq!(scheme.get(0x0D), Some((0x0d, 0x0d, 0x0d)));
+        assert_eq!(scheme.get(0x12), Some((0x08, 0x08, 0x08)));
+        assert_eq!(scheme.get(0x18), None);
+        let palette = scheme.palette();
+        assert_eq!(palette.background, (0x00, 0x00, 0x00));
+        assert_eq!(palette.foreground, (0x05, 0x05, 0x05));
+        assert_eq!(palette.get(Color::BrightBlack), (0x03, 0x03, 0x03));
+        assert_eq!(scheme.theme().muted, Style::new().fg(Color::BrightBlack));
+        assert_eq!(Base16::parse(&scheme.to_yaml()), Ok(scheme));
+    }
+
+    #[test]
+    fn parse_base24() {
+        let scheme = Base16::parse(&yaml(24)).unwrap();
+        assert_eq!(scheme.get(0x17), Some((0x17, 0x17, 0x17)));
+        let palette = scheme.palette();
+        assert_eq!(palette.get(Color::BrightBlack), (0x02, 0x02, 0x02));
+        assert_eq!(palette.get(Color::BrightRed), (0x12, 0x12, 0x12));
+        assert_eq!(scheme.theme().muted, Style::new().attr(Attr::Dim));
+        assert_eq!(Base16::parse(&scheme.to_yaml()), Ok(scheme));
+    }
+
+    #[test]
+    fn parse_base16_errors() {
+        let error = Base16::parse(&yaml(15)).unwrap_err();
+        assert_eq!(error.to_string(), "invalid color scheme, missing base0F");
+        assert!(Base16::parse(&yaml(20)).is_err());
+        assert!(Base16::parse("base00: \"zzzzzz\"").is_err());
+    }
+
+    #[test]
+    fn import_xresources_defines() {
+        let mut text = String::from(
+            "! comment\n#include \"colors.h\"\n#define base00 #101010\n#define bg base00\n#define fg #e0e0e0\n",
+        );
+        text.push_str("*.background: bg\n*foreground: fg\nURxvt*color0: base00\n");
+        for i in 1..16 {
+            let _ = writeln!(text, "*.color{}: #{:02x}0000", i, i);
+        }
+        let palette = import(&text, Format::Xresources).unwrap();
+        assert_eq!(palette.background, (0x10, 0x10, 0x10));
+        assert_eq!(palette.foreground, (0xe0, 0xe0, 0xe0));
+        assert_eq!(palette.colors[0], (0x10, 0x10, 0x10));
+        assert_eq!(palette.colors[15], (0x0f, 0x00, 0x00));
+
+        let text = "#define a b\n#define b a\n*.background: a\n";
+        let error = import(text, Format::Xresources).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid color scheme, invalid background"
+        );
+    }
+
+    #[test]
+    fn import_exported_palette() {
+        let palette = Palette::new();
+        for format in [
+            Format::ItermColors,
+            Format::WindowsTerminalJson,
+            Format::Xresources,
+        ] {
+            assert_eq!(import(&export(&palette, format), format), Ok(palette));
+        }
+        let error = import(
+            "{ \"foreground\": \"#ffffff\" }",
+            Format::WindowsTerminalJson,
+        );
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "invalid color scheme, missing black"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! Cell grid screen buffer with damage tracking.
+//!
+//! Content is drawn into a back buffer of cells, each holding a character and
+//! a [`Style`]. Flushing compares the back buffer against what was flushed
+//! last time and only emits cursor movement, style changes and characters for
+//! cells that differ, wrapped in a synchronized update. What was flushed is
+//! available as styled document from [`Screen::snapshot`].
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let (cols, rows) = colors::term::size().unwrap_or((80, 24));
+//! let mut screen = colors::screen::Screen::new(cols as usize, rows as usize);
+//! screen.print(0, 0, "Status: OK", &Style::new().fg(Color::Green));
+//! screen.flush(&ansi, &mut io::stdout())?;
+//! ```
+
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+
+use crate::buf::with_buf;
+use crate::stats::{self, Source};
+use crate::width::char_width;
+use crate::{sync, Attr, Codes, RenderBuf, Style};
+
+/// Character marking second column of wide character.
+const CONTINUATION: char = '\0';
+
+/// Single screen cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Cell {
+        Cell {
+            ch: ' ',
+            style: Style::new(),
+        }
+    }
+}
+
+/// Run of equally styled text on line of [`Snapshot`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+/// Styled document model of cell grid, e.g. of what [`Screen`] displays, see
+/// [`Screen::snapshot`]. Displayed as plain text, one line per row.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Snapshot {
+    /// Rows of spans, without trailing blank cells and rows.
+    pub lines: Vec<Vec<Span>>,
+}
+
+impl Snapshot {
+    /// Return snapshot of rows, removing trailing blank cells and rows.
+    pub(crate) fn from_rows<'a, I: IntoIterator<Item = &'a [Cell]>>(rows: I) -> Snapshot {
+        let blank = |cell: &Cell| {
+            cell.ch == ' '
+                && cell.style.bg.is_none()
+                && !cell.style.has(Attr::Reverse)
+                && !cell.style.has(Attr::Underline)
+                && !cell.style.has(Attr::DoubleUnderline)
+                && !cell.style.has(Attr::Strikethrough)
+                && !cell.style.has(Attr::Overline)
+        };
+        let rows: Vec<&[Cell]> = rows.into_iter().collect();
+        let end = rows
+            .iter()
+            .rposition(|row| !row.iter().all(blank))
+            .map_or(0, |i| i + 1);
+
+        let mut lines = Vec::with_capacity(end);
+        for row in &rows[..end] {
+            let len = row
+                .iter()
+                .rposition(|cell| !blank(cell))
+                .map_or(0, |i| i + 1);
+            let mut spans: Vec<Span> = Vec::new();
+            for cell in row[..len].iter().filter(|cell| cell.ch != CONTINUATION) {
+                match spans.last_mut() {
+                    Some(span) if span.style == cell.style => span.text.push(cell.ch),
+                    _ => spans.push(Span {
+                        text: cell.ch.to_string(),
+                        style: cell.style,
+                    }),
+                }
+            }
+            lines.push(spans);
+        }
+        Snapshot { lines }
+    }
+
+    /// Return lines styled through `codes`, every line ending with newline.
+    pub fn render(&self, codes: &Codes) -> String {
+        let mut output = String::new();
+        for line in &self.lines {
+            for span in line {
+                output.push_str(&span.style.paint(codes, &span.text));
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.lines {
+            for span in line {
+                f.write_str(&span.text)?;
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Screen buffer with front (flushed) and back (drawn) cell grids.
+pub struct Screen {
+    width: usize,
+    height: usize,
+    back: Vec<Cell>,
+    front: Vec<Cell>,
+    full: bool,
+}
+
+impl Screen {
+    /// Return blank screen buffer of given size.
+    pub fn new(width: usize, height: usize) -> Screen {
+        Screen {
+            width,
+            height,
+            back: vec![Cell::default(); width * height],
+            front: vec![Cell::default(); width * height],
+            full: true,
+        }
+    }
+
+    /// Return width in columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return height in rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Change size, clearing content and forcing full redraw on next flush.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        *self = Screen::new(width, height);
+    }
+
+    /// Force full redraw on next flush, e.g. after something else has
+    /// written to the terminal.
+    pub fn invalidate(&mut self) {
+        self.full = true;
+    }
+
+    /// Reset all cells of back buffer to blank.
+    pub fn clear(&mut self) {
+        self.back.fill(Cell::default());
+    }
+
+    /// Return cell at column `x` and row `y`.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.back.get(y * self.width + x)
+    }
+
+    /// Blank other half of wide character at back buffer index, so that
+    /// writing over one half does not leave the other behind.
+    fn split_wide(&mut self, i: usize) {
+        let x = i % self.width;
+        if self.back[i].ch == CONTINUATION && x > 0 {
+            self.back[i - 1] = Cell::default();
+        }
+        if x + 1 < self.width && self.back[i + 1].ch == CONTINUATION {
+            self.back[i + 1] = Cell::default();
+        }
+    }
+
+    /// Set cell at column `x` and row `y`, ignored if outside of screen.
+    pub fn set(&mut self, x: usize, y: usize, ch: char, style: &Style) {
+        if x < self.width && y < self.height {
+            let i = y * self.width + x;
+            self.split_wide(i);
+            self.back[i] = Cell { ch, style: *style };
+        }
+    }
+
+    /// Draw text starting at column `x` and row `y`, clipped at right edge,
+    /// and return number of columns drawn.
+    pub fn print(&mut self, x: usize, y: usize, text: &str, style: &Style) -> usize {
+        let mut col = x;
+        for c in text.chars() {
+            let width = char_width(c);
+            if width == 0 {
+                continue;
+            }
+            if col + width > self.width {
+                break;
+            }
+            if y < self.height {
+                let i = y * self.width + col;
+                self.split_wide(i);
+                if width == 2 {
+                    self.split_wide(i + 1);
+                }
+                self.back[i] = Cell {
+                    ch: c,
+                    style: *style,
+                };
+                if width == 2 {
+                    self.back[i + 1] = Cell {
+                        ch: CONTINUATION,
+                        style: *style,
+                    };
+                }
+            }
+            col += width;
+        }
+        col - x
+    }
+
+    /// Return output updating terminal from front to back buffer and mark
+    /// back buffer as flushed.
+    ///
+    /// Positions are relative to the top left corner of the terminal. If
+    /// colors are disabled in `codes` all rows are returned as plain lines
+    /// if anything changed.
+    pub fn render(&mut self, codes: &Codes) -> String {
+        let mut buf = RenderBuf::new();
+        self.render_into(codes, &mut buf);
+        buf.as_str().to_string()
+    }
+
+    /// Append output from [`Screen::render`] to buffer, allowing the same
+    /// allocation to be reused for every frame.
+    pub fn render_into(&mut self, codes: &Codes, buf: &mut RenderBuf) {
+        if !codes.is_enabled() {
+            if self.full || self.back != self.front {
+                for row in self.back.chunks(self.width.max(1)) {
+                    let end = row.iter().rposition(|c| c.ch != ' ').map_or(0, |i| i + 1);
+                    for cell in &row[..end] {
+                        if cell.ch != CONTINUATION {
+                            buf.push(cell.ch);
+                        }
+                    }
+                    buf.push('\n');
+                }
+            }
+            self.front.clone_from(&self.back);
+            self.full = false;
+            return;
+        }
+
+        let mut style = Style::new();
+        let mut cursor_at = None;
+        let mut changed = false;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y * self.width + x;
+                let cell = self.back[i];
+                if cell.ch == CONTINUATION || (!self.full && cell == self.front[i]) {
+                    continue;
+                }
+
+                if !changed {
+                    buf.push_str(sync::BEGIN);
+                    changed = true;
+                }
+                if cursor_at != Some((x, y)) {
+                    let _ = write!(buf, "\x1B[{};{}H", y + 1, x + 1);
+                }
+                if cell.style != style {
+                    buf.push_str(&codes.attr.reset);
+                    buf.push_style(codes, &cell.style);
+                    style = cell.style;
+                }
+                buf.push(cell.ch);
+                cursor_at = Some((x + char_width(cell.ch).max(1), y));
+            }
+        }
+
+        if changed {
+            buf.push_str(&codes.attr.reset);
+            buf.push_str(sync::END);
+        }
+        self.front.clone_from(&self.back);
+        self.full = false;
+    }
+
+    /// Return what was flushed last as styled document, e.g. to keep final
+    /// state of a live display in logs after it ends using
+    /// [`Snapshot::render`]. Trailing blank cells and rows are removed.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::from_rows(self.front.chunks(self.width.max(1)))
+    }
+
+    /// Write output from [`Screen::render`] to writer and flush it, using
+    /// thread local render buffer.
+    pub fn flush<W: Write>(&mut self, codes: &Codes, writer: &mut W) -> io::Result<()> {
+        with_buf(|buf| {
+            self.render_into(codes, buf);
+            if !buf.is_empty() {
+                writer.write_all(buf.as_bytes())?;
+                stats::record(Source::Screen, buf.as_bytes(), true);
+            }
+            writer.flush()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_on_with, Color, ColorSupport};
+
+    #[test]
+    fn overwrite_wide_characters() {
+        let chars = |screen: &Screen| -> String { screen.back[..6].iter().map(|c| c.ch).collect() };
+        let mut screen = Screen::new(6, 1);
+        screen.print(0, 0, "世界", &Style::new());
+        screen.print(1, 0, "x", &Style::new());
+        assert_eq!(chars(&screen), " x界\0  ");
+        screen.print(2, 0, "y", &Style::new());
+        assert_eq!(chars(&screen), " xy   ");
+        screen.print(3, 0, "世", &Style::new());
+        screen.set(4, 0, 'z', &Style::new());
+        assert_eq!(chars(&screen), " xy z ");
+    }
+
+    #[test]
+    fn snapshot_of_flushed_cells() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let green = Style::new().fg(Color::Green);
+        let mut screen = Screen::new(10, 4);
+        screen.print(0, 0, "OK 世界", &green);
+        screen.print(7, 0, "!", &Style::new());
+        screen.print(1, 1, " ", &Style::new().bg(Color::Blue));
+        screen.print(0, 2, "  ", &Style::new());
+        assert_eq!(screen.snapshot(), Snapshot::default());
+
+        screen.flush(&codes, &mut io::sink()).unwrap();
+        let snapshot = screen.snapshot();
+        assert_eq!(
+            snapshot.lines[0],
+            [
+                Span {
+                    text: "OK 世界".to_string(),
+                    style: green,
+                },
+                Span {
+                    text: "!".to_string(),
+                    style: Style::new(),
+                },
+            ]
+        );
+        assert_eq!(snapshot.lines.len(), 2);
+        assert_eq!(snapshot.to_string(), "OK 世界!\n  \n");
+        assert_eq!(
+            snapshot.render(&codes),
+            format!(
+                "{}!\n {}\n",
+                green.paint(&codes, "OK 世界"),
+                Style::new().bg(Color::Blue).paint(&codes, " ")
+            )
+        );
+    }
+}
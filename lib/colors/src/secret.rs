@@ -0,0 +1,104 @@
+//! Redacted values.
+//!
+//! [`Secret`] wraps tokens, passwords and similar values so they can be passed
+//! to any output code without leaking. Both `Display` and `Debug` render a
+//! fixed length mask, optionally revealing the last few characters, so the
+//! value never ends up in logs, recordings or exported reports. The real
+//! value is only available through [`Secret::expose`].
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let token = colors::Secret::new(env::var("API_TOKEN")?).reveal_last(4);
+//! println!("Using token {}", token.render(&ansi, &colors::Theme::detect()));
+//! ```
+
+use std::fmt;
+
+use crate::{Charset, Codes, Theme};
+
+/// Number of mask characters rendered regardless of value length.
+const MASK_LEN: usize = 5;
+
+/// Value rendered as mask in all output.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret<T> {
+    value: T,
+    reveal: usize,
+}
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Secret<T> {
+        Secret { value, reveal: 0 }
+    }
+
+    /// Reveal last `n` characters after mask, nothing is revealed for values
+    /// not at least twice as long.
+    pub fn reveal_last(mut self, n: usize) -> Secret<T> {
+        self.reveal = n;
+        self
+    }
+
+    /// Return wrapped value.
+    pub fn expose(&self) -> &T {
+        &self.value
+    }
+
+    /// Return wrapped value, consuming wrapper.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: fmt::Display> Secret<T> {
+    /// Return mask and revealed characters, if any.
+    fn parts(&self) -> (String, String) {
+        let mask = Charset::detect().glyphs().mask.repeat(MASK_LEN);
+
+        let value: Vec<char> = self.value.to_string().chars().collect();
+        if self.reveal == 0 || value.len() < self.reveal * 2 {
+            return (mask, String::new());
+        }
+        (mask, value[value.len() - self.reveal..].iter().collect())
+    }
+
+    /// Return mask rendered in muted style of theme, revealed characters as
+    /// is.
+    pub fn render(&self, codes: &Codes, theme: &Theme) -> String {
+        let (mask, revealed) = self.parts();
+        format!("{}{}", theme.muted.paint(codes, &mask), revealed)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (mask, revealed) = self.parts();
+        write!(f, "{}{}", mask, revealed)
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Secret({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_on_with, Color, ColorSupport, Style};
+
+    #[test]
+    fn render_mask_in_muted_style() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let theme = Theme {
+            muted: Style::new().fg(Color::Blue),
+            ..Theme::new()
+        };
+        let secret = Secret::new("token-1234").reveal_last(4);
+        let mask = Charset::detect().glyphs().mask.repeat(MASK_LEN);
+        let expected = format!("{}1234", theme.muted.paint(&codes, &mask));
+        assert_eq!(secret.render(&codes, &theme), expected);
+        assert_eq!(format!("{:?}", secret), format!("Secret({}1234)", mask));
+    }
+}
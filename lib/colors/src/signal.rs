@@ -0,0 +1,35 @@
+//! Ctrl-C handling for blocking terminal effects.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_sigint(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Guard catching `SIGINT` while alive, previous handler is restored on drop.
+pub(crate) struct Interrupt {
+    previous: libc::sighandler_t,
+}
+
+impl Interrupt {
+    /// Install `SIGINT` handler and clear interrupted state.
+    pub(crate) fn catch() -> Interrupt {
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        let handler = on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        let previous = unsafe { libc::signal(libc::SIGINT, handler) };
+        Interrupt { previous }
+    }
+
+    /// Check if `SIGINT` has been received since guard was created.
+    pub(crate) fn triggered(&self) -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Interrupt {
+    fn drop(&mut self) {
+        unsafe { libc::signal(libc::SIGINT, self.previous) };
+    }
+}
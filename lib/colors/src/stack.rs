@@ -0,0 +1,97 @@
+//! Nested styles.
+//!
+//! A plain reset ends every style at once, so a bold region inside of a red
+//! region would leave the rest of the red region unstyled. [`StyleStack`]
+//! adds pushed styles to the enclosing ones and restores the enclosing style
+//! when popping.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let mut stack = colors::StyleStack::new();
+//! let mut line = stack.push(&ansi, Style::new().fg(Color::Red));
+//! line += "error in ";
+//! line += &stack.paint(&ansi, Style::new().attr(Attr::Bold), "main.rs");
+//! line += " line 3";
+//! line += &stack.pop(&ansi);
+//! ```
+
+use crate::{Codes, Style};
+
+/// Stack of nested styles, each including the styles around it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StyleStack {
+    styles: Vec<Style>,
+}
+
+impl StyleStack {
+    /// Return empty stack.
+    pub fn new() -> StyleStack {
+        StyleStack { styles: Vec::new() }
+    }
+
+    /// Return number of pushed styles.
+    pub fn depth(&self) -> usize {
+        self.styles.len()
+    }
+
+    /// Check if no style is pushed.
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+    }
+
+    /// Return innermost style including all styles around it, plain if stack
+    /// is empty.
+    pub fn current(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    /// Push style added to current style and return sequence enabling it,
+    /// or empty string if colors are disabled in `codes`.
+    pub fn push(&mut self, codes: &Codes, style: Style) -> String {
+        let style = self.current().merge(style);
+        self.styles.push(style);
+        if !codes.is_enabled() || style.is_plain() {
+            return String::new();
+        }
+        codes.wrap(style.to_sgr().as_str())
+    }
+
+    /// Pop innermost style and return sequences resetting it and restoring
+    /// enclosing style. Nothing is returned if stack is empty or colors are
+    /// disabled in `codes`.
+    pub fn pop(&mut self, codes: &Codes) -> String {
+        if self.styles.pop().is_none() || !codes.is_enabled() {
+            return String::new();
+        }
+        let outer = self.current();
+        if outer.is_plain() {
+            return codes.attr.reset.clone();
+        }
+        format!(
+            "{}{}",
+            codes.attr.reset,
+            codes.wrap(outer.to_sgr().as_str())
+        )
+    }
+
+    /// Return text in style added to current style, followed by sequences
+    /// restoring current style.
+    pub fn paint(&mut self, codes: &Codes, style: Style, text: &str) -> String {
+        let start = self.push(codes, style);
+        format!("{}{}{}", start, text, self.pop(codes))
+    }
+
+    /// Pop all styles and return reset, or empty string if stack was empty
+    /// or colors are disabled in `codes`.
+    pub fn reset(&mut self, codes: &Codes) -> String {
+        let open = !self.styles.is_empty();
+        self.styles.clear();
+        if open && codes.is_enabled() {
+            codes.attr.reset.clone()
+        } else {
+            String::new()
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Counters and hooks for emitted output.
+//!
+//! Renderers and writers of this crate report what they write: bytes, escape
+//! sequences and redraws of the screen buffer and animations. This helps
+//! finding excessive output, e.g. over slow SSH links, and tuning incremental
+//! rendering. Counting is off by default and costs a single atomic load per
+//! write then. [`ColoredWriter`](crate::ColoredWriter) and
+//! [`emit::Text`](crate::emit::Text) count what is written through them,
+//! applications instrument their own writers by wrapping them in [`Counted`].
+//!
+//! Callbacks run outside of any lock and may register callbacks or write
+//! counted output themselves. Writes made by callbacks are counted but don't
+//! run callbacks again.
+//!
+//! Escape sequences are counted as ESC bytes, so sequences wrapped for tmux
+//! count twice per ESC inside of them.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! colors::stats::set_enabled(true);
+//! colors::stats::on_record(|record| {
+//!     if record.bytes > 4096 {
+//!         log::debug!("{:?} wrote {} bytes", record.source, record.bytes);
+//!     }
+//! });
+//! run_ui()?;
+//! let totals = colors::stats::totals();
+//! eprintln!("{} bytes, {} redraws", totals.bytes, totals.redraws);
+//! ```
+
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+type RecordCallback = Arc<dyn Fn(&Record) + Send + Sync>;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static SEQUENCES: AtomicU64 = AtomicU64::new(0);
+static WRITES: AtomicU64 = AtomicU64::new(0);
+static REDRAWS: AtomicU64 = AtomicU64::new(0);
+static CALLBACKS: RwLock<Vec<RecordCallback>> = RwLock::new(Vec::new());
+
+thread_local! {
+    /// Set while callbacks run on this thread.
+    static IN_CALLBACK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Component output was written by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// Flush of [`Screen`](crate::screen::Screen).
+    Screen,
+    /// Frame of [`animate`](crate::animate).
+    Animation,
+    /// Event of [`replay`](crate::replay).
+    Replay,
+    /// Control sequence of [`term`](crate::term), e.g. window title.
+    Control,
+    /// Writer wrapped in [`Counted`].
+    Writer,
+}
+
+/// Single write reported to callbacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Record {
+    pub source: Source,
+    pub bytes: u64,
+    pub sequences: u64,
+    /// Write drew frame of screen buffer or animation.
+    pub redraw: bool,
+}
+
+/// Totals since counting was enabled or last [`reset`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Totals {
+    pub bytes: u64,
+    pub sequences: u64,
+    pub writes: u64,
+    pub redraws: u64,
+}
+
+/// Enable or disable counting and callbacks.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if counting is enabled.
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Register callback run for every write while counting is enabled.
+pub fn on_record<F: Fn(&Record) + Send + Sync + 'static>(callback: F) {
+    CALLBACKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(callback));
+}
+
+/// Return totals.
+pub fn totals() -> Totals {
+    Totals {
+        bytes: BYTES.load(Ordering::Relaxed),
+        sequences: SEQUENCES.load(Ordering::Relaxed),
+        writes: WRITES.load(Ordering::Relaxed),
+        redraws: REDRAWS.load(Ordering::Relaxed),
+    }
+}
+
+/// Reset totals to zero, callbacks are kept.
+pub fn reset() {
+    for counter in [&BYTES, &SEQUENCES, &WRITES, &REDRAWS] {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Count written data and run callbacks, if enabled.
+pub(crate) fn record(source: Source, data: &[u8], redraw: bool) {
+    if !enabled() || data.is_empty() {
+        return;
+    }
+    let record = Record {
+        source,
+        bytes: data.len() as u64,
+        sequences: data.iter().filter(|&&b| b == 0x1B).count() as u64,
+        redraw,
+    };
+    BYTES.fetch_add(record.bytes, Ordering::Relaxed);
+    SEQUENCES.fetch_add(record.sequences, Ordering::Relaxed);
+    WRITES.fetch_add(1, Ordering::Relaxed);
+    if redraw {
+        REDRAWS.fetch_add(1, Ordering::Relaxed);
+    }
+    if IN_CALLBACK.with(|running| running.replace(true)) {
+        return;
+    }
+    let _running = Running;
+    let callbacks = CALLBACKS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    for callback in callbacks {
+        callback(&record);
+    }
+}
+
+/// Guard clearing [`IN_CALLBACK`] when callbacks return or panic.
+struct Running;
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        IN_CALLBACK.with(|running| running.set(false));
+    }
+}
+
+/// Writer counting everything written through it as [`Source::Writer`].
+#[derive(Debug)]
+pub struct Counted<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Counted<W> {
+    pub fn new(writer: W) -> Counted<W> {
+        Counted { writer }
+    }
+
+    /// Return reference to wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Return mutable reference to wrapped writer, writes through it are not
+    /// counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Return wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for Counted<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        record(Source::Writer, &buf[..written], false);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::thread::{self, ThreadId};
+
+    #[test]
+    fn callbacks_may_write_and_register() {
+        static SEEN: Mutex<Vec<(ThreadId, u64)>> = Mutex::new(Vec::new());
+        set_enabled(true);
+        on_record(|record| {
+            SEEN.lock()
+                .unwrap()
+                .push((thread::current().id(), record.bytes));
+            // Neither deadlocks nor recurses.
+            let _ = Counted::new(io::sink()).write_all(b"nested");
+            on_record(|_| ());
+        });
+        let mut writer = Counted::new(Vec::new());
+        writer.write_all(b"\x1B[1mbold").unwrap();
+        assert_eq!(writer.get_ref(), b"\x1B[1mbold");
+
+        let id = thread::current().id();
+        let seen: Vec<u64> = SEEN
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(thread, _)| *thread == id)
+            .map(|&(_, bytes)| bytes)
+            .collect();
+        assert_eq!(seen, [8]);
+    }
+}
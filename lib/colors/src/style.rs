@@ -0,0 +1,973 @@
+//! Composable text styles.
+//!
+//! A [`Style`] is a plain value describing foreground color, background color
+//! and attributes. It is turned into a single combined SGR sequence when
+//! rendered through a [`Codes`] instance, and into nothing at all when the
+//! instance has colors disabled.
+//!
+//! Styles compare semantically: parameter order and redundant codes in the
+//! sequences a style was parsed from do not matter, see [`Style::from_sgr`].
+
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{Add, BitOr};
+use std::str::FromStr;
+
+use crate::ansi::{self, Segment};
+use crate::Codes;
+
+/// ANSI 16 colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl Color {
+    /// All colors, normal colors followed by bright colors in SGR order.
+    pub const ALL: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+
+    /// Return SGR parameter used for foreground color.
+    pub fn fg_param(self) -> u8 {
+        match self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+
+            Color::BrightBlack => 90,
+            Color::BrightRed => 91,
+            Color::BrightGreen => 92,
+            Color::BrightYellow => 93,
+            Color::BrightBlue => 94,
+            Color::BrightMagenta => 95,
+            Color::BrightCyan => 96,
+            Color::BrightWhite => 97,
+        }
+    }
+
+    /// Check if color is one of the bright colors.
+    pub fn is_bright(self) -> bool {
+        self.fg_param() >= 90
+    }
+
+    /// Return bright variant of color, bright colors are returned as is.
+    pub fn bright(self) -> Color {
+        match self {
+            Color::Black => Color::BrightBlack,
+            Color::Red => Color::BrightRed,
+            Color::Green => Color::BrightGreen,
+            Color::Yellow => Color::BrightYellow,
+            Color::Blue => Color::BrightBlue,
+            Color::Magenta => Color::BrightMagenta,
+            Color::Cyan => Color::BrightCyan,
+            Color::White => Color::BrightWhite,
+            color => color,
+        }
+    }
+
+    /// Return normal variant of bright color, other colors are returned as is.
+    pub fn normal(self) -> Color {
+        match self {
+            Color::BrightBlack => Color::Black,
+            Color::BrightRed => Color::Red,
+            Color::BrightGreen => Color::Green,
+            Color::BrightYellow => Color::Yellow,
+            Color::BrightBlue => Color::Blue,
+            Color::BrightMagenta => Color::Magenta,
+            Color::BrightCyan => Color::Cyan,
+            Color::BrightWhite => Color::White,
+            color => color,
+        }
+    }
+
+    /// Return SGR parameter used for background color.
+    pub fn bg_param(self) -> u8 {
+        self.fg_param() + 10
+    }
+}
+
+/// Return name in lowercase with `-` and spaces replaced by `_`.
+fn normalize(name: &str) -> String {
+    name.trim().to_ascii_lowercase().replace(['-', ' '], "_")
+}
+
+/// Foreground color selecting value in `codes.fg`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Fg(pub Color);
+
+impl Fg {
+    /// Return every foreground color, see [`Color::ALL`].
+    pub fn all() -> impl Iterator<Item = Fg> {
+        Color::ALL.iter().copied().map(Fg)
+    }
+
+    /// Return value of color in `codes`, e.g. `codes.fg.red`.
+    pub fn code(self, codes: &Codes) -> &str {
+        codes.fg.get(self.0)
+    }
+}
+
+/// Background color selecting value in `codes.bg`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bg(pub Color);
+
+impl Bg {
+    /// Return every background color, see [`Color::ALL`].
+    pub fn all() -> impl Iterator<Item = Bg> {
+        Color::ALL.iter().copied().map(Bg)
+    }
+
+    /// Return value of color in `codes`, e.g. `codes.bg.red`.
+    pub fn code(self, codes: &Codes) -> &str {
+        codes.bg.get(self.0)
+    }
+}
+
+/// Error returned when parsing unknown [`Color`] name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown color '{}', expected name like red or bright_red",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Parse case-insensitive name like `red`, `bright_red` or `Bright-Red`,
+    /// `light_` works as `bright_` and `grey` or `gray` is bright black.
+    fn from_str(s: &str) -> Result<Color, ParseColorError> {
+        let name = normalize(s);
+        let (bright, base) = match name
+            .strip_prefix("bright_")
+            .or_else(|| name.strip_prefix("light_"))
+        {
+            Some(base) => (true, base),
+            None => (false, name.as_str()),
+        };
+        let color = match base {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" | "purple" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            "grey" | "gray" if !bright => Color::BrightBlack,
+            _ => return Err(ParseColorError(s.to_string())),
+        };
+        Ok(if bright { color.bright() } else { color })
+    }
+}
+
+/// Terminal style attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Attr {
+    Bold,
+    Dim,
+    Italic,
+    Underline,
+    Blink,
+    Reverse,
+    Hidden,
+    Strikethrough,
+    DoubleUnderline,
+    Overline,
+}
+
+impl Attr {
+    /// All attributes in SGR order.
+    pub const ALL: [Attr; 10] = [
+        Attr::Bold,
+        Attr::Dim,
+        Attr::Italic,
+        Attr::Underline,
+        Attr::Blink,
+        Attr::Reverse,
+        Attr::Hidden,
+        Attr::Strikethrough,
+        Attr::DoubleUnderline,
+        Attr::Overline,
+    ];
+
+    /// Return value of attribute in `codes`, e.g. `codes.attr.bold`.
+    pub fn code(self, codes: &Codes) -> &str {
+        codes.attr.get(self)
+    }
+
+    /// Return SGR parameter used to enable attribute.
+    pub fn param(self) -> u8 {
+        match self {
+            Attr::Bold => 1,
+            Attr::Dim => 2,
+            Attr::Italic => 3,
+            Attr::Underline => 4,
+            Attr::Blink => 5,
+            Attr::Reverse => 7,
+            Attr::Hidden => 8,
+            Attr::Strikethrough => 9,
+            Attr::DoubleUnderline => 21,
+            Attr::Overline => 53,
+        }
+    }
+
+    const fn bit(self) -> u16 {
+        1 << (self as u16)
+    }
+}
+
+/// Error returned when parsing unknown [`Attr`] name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseAttrError(String);
+
+impl fmt::Display for ParseAttrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown attribute '{}', expected bold, dim, italic, underline, blink, reverse, \
+             hidden, strikethrough, double_underline or overline",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseAttrError {}
+
+impl FromStr for Attr {
+    type Err = ParseAttrError;
+
+    /// Parse case-insensitive name like `bold` or `double_underline`, with
+    /// aliases `faint`, `underlined`, `inverse`, `invert`, `conceal` and
+    /// `strike`.
+    fn from_str(s: &str) -> Result<Attr, ParseAttrError> {
+        match normalize(s).as_str() {
+            "bold" => Ok(Attr::Bold),
+            "dim" | "faint" => Ok(Attr::Dim),
+            "italic" => Ok(Attr::Italic),
+            "underline" | "underlined" => Ok(Attr::Underline),
+            "blink" => Ok(Attr::Blink),
+            "reverse" | "inverse" | "invert" => Ok(Attr::Reverse),
+            "hidden" | "conceal" => Ok(Attr::Hidden),
+            "strikethrough" | "strike" => Ok(Attr::Strikethrough),
+            "double_underline" => Ok(Attr::DoubleUnderline),
+            "overline" => Ok(Attr::Overline),
+            _ => Err(ParseAttrError(s.to_string())),
+        }
+    }
+}
+
+/// Foreground color, background color and set of attributes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    attrs: u16,
+}
+
+impl Style {
+    /// Return style without colors or attributes.
+    pub const fn new() -> Style {
+        Style {
+            fg: None,
+            bg: None,
+            attrs: 0,
+        }
+    }
+
+    /// Set foreground color.
+    pub const fn fg(mut self, color: Color) -> Style {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Set background color.
+    pub const fn bg(mut self, color: Color) -> Style {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Enable attribute.
+    pub const fn attr(mut self, attr: Attr) -> Style {
+        self.attrs |= attr.bit();
+        self
+    }
+
+    /// Disable attribute.
+    pub const fn without(mut self, attr: Attr) -> Style {
+        self.attrs &= !attr.bit();
+        self
+    }
+
+    /// Return style combined with other style: colors set in `other` win,
+    /// attributes are merged.
+    pub const fn merge(self, other: Style) -> Style {
+        Style {
+            fg: match other.fg {
+                Some(fg) => Some(fg),
+                None => self.fg,
+            },
+            bg: match other.bg {
+                Some(bg) => Some(bg),
+                None => self.bg,
+            },
+            attrs: self.attrs | other.attrs,
+        }
+    }
+
+    /// Return style if condition holds, otherwise plain style.
+    pub const fn when(self, cond: bool) -> Style {
+        if cond {
+            self
+        } else {
+            Style::new()
+        }
+    }
+
+    /// Return text paired with style for display through `codes`.
+    pub fn styled<'a>(self, codes: &'a Codes, text: &'a str) -> Styled<'a> {
+        Styled::new(codes, self, text)
+    }
+
+    /// Return style remapped for high contrast: dim becomes bold, foreground
+    /// colors become bright and near black foregrounds become bright white
+    /// unless background is bright.
+    pub fn high_contrast(self) -> Style {
+        let mut style = self;
+        if style.has(Attr::Dim) {
+            style = style.without(Attr::Dim).attr(Attr::Bold);
+        }
+        let dark_bg = !style.bg.is_some_and(Color::is_bright);
+        style.fg = style.fg.map(|fg| match fg {
+            Color::Black | Color::BrightBlack if dark_bg => Color::BrightWhite,
+            Color::Black | Color::BrightBlack => Color::Black,
+            fg => fg.bright(),
+        });
+        style
+    }
+
+    /// Check if attribute is enabled.
+    pub fn has(&self, attr: Attr) -> bool {
+        self.attrs & attr.bit() != 0
+    }
+
+    /// Return style resulting from applying all SGR sequences in text, e.g.
+    /// `\x1B[31;1m` and `\x1B[1m\x1B[1;31m` both give bold red. Other escape
+    /// sequences and plain text are ignored.
+    pub fn from_sgr(text: &str) -> Style {
+        ansi::segments(text).fold(Style::new(), |style, segment| match segment {
+            Segment::Escape(seq) => ansi::apply_sgr(style, seq),
+            Segment::Text(_) => style,
+        })
+    }
+
+    /// Return style in canonical form, i.e. as parsed back from its own SGR
+    /// sequence. Normalized styles are equal if and only if they render
+    /// identically.
+    pub fn normalized(&self) -> Style {
+        Style::from_sgr(self.to_sgr().as_str())
+    }
+
+    /// Check if style has neither colors nor attributes.
+    pub fn is_plain(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && self.attrs == 0
+    }
+
+    /// Return combined SGR sequence, e.g. `\x1B[1;31m`, or empty sequence if
+    /// style is plain. Sequence is built on the stack without allocation.
+    pub fn to_sgr(&self) -> Sgr {
+        let mut sgr = Sgr::new();
+        for attr in Attr::ALL {
+            if self.has(attr) {
+                sgr.push(attr.param());
+            }
+        }
+        if let Some(fg) = self.fg {
+            sgr.push(fg.fg_param());
+        }
+        if let Some(bg) = self.bg {
+            sgr.push(bg.bg_param());
+        }
+        sgr.finish();
+        sgr
+    }
+
+    /// Return combined SGR sequence as string, see [`Style::to_sgr`].
+    pub fn sgr(&self) -> String {
+        self.to_sgr().as_str().to_string()
+    }
+
+    /// Write combined SGR sequence to writer, nothing is written if colors are
+    /// disabled in `codes` or style is plain.
+    pub fn write_to<W: Write>(&self, codes: &Codes, writer: &mut W) -> io::Result<()> {
+        if !codes.is_enabled() || self.is_plain() {
+            return Ok(());
+        }
+        codes.write_to(self.to_sgr().as_bytes(), writer)
+    }
+
+    /// Return text wrapped in style and reset, or text as is if colors are
+    /// disabled in `codes`.
+    pub fn paint(&self, codes: &Codes, text: &str) -> String {
+        if !codes.is_enabled() || self.is_plain() {
+            return text.to_string();
+        }
+        format!(
+            "{}{}{}",
+            codes.wrap(self.to_sgr().as_str()),
+            text,
+            codes.attr.reset
+        )
+    }
+}
+
+/// Error returned when parsing unknown style name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseStyleError(String);
+
+impl fmt::Display for ParseStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown style '{}', expected color like bright_red, bg:color or attribute like bold",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseStyleError {}
+
+/// Parse style from names separated by spaces or commas, e.g.
+/// `bright_red bold` or `white,bg:blue`. Names are colors like `red` and
+/// `bright_red`, background colors like `bg:red` and attributes like
+/// `bold`, parsed as [`Color`] and [`Attr`]. A later color replaces an
+/// earlier one.
+pub fn parse_style(text: &str) -> Result<Style, ParseStyleError> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+        .try_fold(Style::new(), |style, name| {
+            if let Some(bg) = name.strip_prefix("bg:") {
+                if let Ok(color) = bg.parse() {
+                    return Ok(style.bg(color));
+                }
+            } else if let Ok(color) = name.parse() {
+                return Ok(style.fg(color));
+            } else if let Ok(attr) = name.parse() {
+                return Ok(style.attr(attr));
+            }
+            Err(ParseStyleError(name.to_string()))
+        })
+}
+
+/// Text paired with style, displayed with style and reset or with declared
+/// fallback if colors are disabled. Nothing is allocated if colors are
+/// disabled.
+#[derive(Clone, Copy, Debug)]
+pub struct Styled<'a> {
+    codes: &'a Codes,
+    style: Style,
+    text: &'a str,
+    prefix: &'a str,
+    plain: bool,
+}
+
+impl<'a> Styled<'a> {
+    /// Return styled text, displayed as is if colors are disabled.
+    pub fn new(codes: &'a Codes, style: Style, text: &'a str) -> Styled<'a> {
+        Styled {
+            codes,
+            style,
+            text,
+            prefix: "",
+            plain: true,
+        }
+    }
+
+    /// Return styled text displayed only if colors are enabled, e.g. purely
+    /// decorative markers.
+    pub fn if_enabled(codes: &'a Codes, style: Style, text: &'a str) -> Styled<'a> {
+        Styled {
+            plain: false,
+            ..Styled::new(codes, style, text)
+        }
+    }
+
+    /// Set prefix displayed before text if colors are disabled, e.g. `*`
+    /// instead of bold, so information conveyed by style is not lost.
+    pub fn or_plain(mut self, prefix: &'a str) -> Styled<'a> {
+        self.prefix = prefix;
+        self.plain = true;
+        self
+    }
+}
+
+impl fmt::Display for Styled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.codes.is_enabled() {
+            if self.plain {
+                f.write_str(self.prefix)?;
+                f.write_str(self.text)?;
+            }
+            return Ok(());
+        }
+        if self.style.is_plain() {
+            return f.write_str(self.text);
+        }
+        f.write_str(&self.codes.wrap(self.style.to_sgr().as_str()))?;
+        f.write_str(self.text)?;
+        f.write_str(&self.codes.attr.reset)
+    }
+}
+
+/// Style bound to [`Codes`] instance for fluent chaining, see
+/// [`Codes::style`].
+#[derive(Clone, Copy, Debug)]
+pub struct StyleBuilder<'a> {
+    codes: &'a Codes,
+    style: Style,
+}
+
+/// Generate methods setting colors.
+macro_rules! color_methods {
+    ($setter:ident; $($name:ident => $color:ident,)*) => {
+        $(
+            #[doc = concat!("Set color to [`Color::", stringify!($color), "`].")]
+            pub fn $name(self) -> StyleBuilder<'a> {
+                self.with(self.style.$setter(Color::$color))
+            }
+        )*
+    };
+}
+
+/// Generate methods enabling attributes.
+macro_rules! attr_methods {
+    ($($name:ident => $attr:ident,)*) => {
+        $(
+            #[doc = concat!("Enable [`Attr::", stringify!($attr), "`].")]
+            pub fn $name(self) -> StyleBuilder<'a> {
+                self.with(self.style.attr(Attr::$attr))
+            }
+        )*
+    };
+}
+
+impl<'a> StyleBuilder<'a> {
+    fn with(self, style: Style) -> StyleBuilder<'a> {
+        StyleBuilder { style, ..self }
+    }
+
+    color_methods! { fg;
+        fg_black => Black,
+        fg_red => Red,
+        fg_green => Green,
+        fg_yellow => Yellow,
+        fg_blue => Blue,
+        fg_magenta => Magenta,
+        fg_cyan => Cyan,
+        fg_white => White,
+        fg_bright_black => BrightBlack,
+        fg_bright_red => BrightRed,
+        fg_bright_green => BrightGreen,
+        fg_bright_yellow => BrightYellow,
+        fg_bright_blue => BrightBlue,
+        fg_bright_magenta => BrightMagenta,
+        fg_bright_cyan => BrightCyan,
+        fg_bright_white => BrightWhite,
+    }
+
+    color_methods! { bg;
+        bg_black => Black,
+        bg_red => Red,
+        bg_green => Green,
+        bg_yellow => Yellow,
+        bg_blue => Blue,
+        bg_magenta => Magenta,
+        bg_cyan => Cyan,
+        bg_white => White,
+        bg_bright_black => BrightBlack,
+        bg_bright_red => BrightRed,
+        bg_bright_green => BrightGreen,
+        bg_bright_yellow => BrightYellow,
+        bg_bright_blue => BrightBlue,
+        bg_bright_magenta => BrightMagenta,
+        bg_bright_cyan => BrightCyan,
+        bg_bright_white => BrightWhite,
+    }
+
+    attr_methods! {
+        bold => Bold,
+        dim => Dim,
+        italic => Italic,
+        underline => Underline,
+        blink => Blink,
+        reverse => Reverse,
+    }
+
+    /// Return built style.
+    pub fn build(self) -> Style {
+        self.style
+    }
+
+    /// Return text wrapped in single combined SGR sequence and reset, or text
+    /// as is if colors are disabled, see [`Style::paint`].
+    pub fn paint(self, text: &str) -> String {
+        self.style.paint(self.codes, text)
+    }
+}
+
+/// Generate methods returning text paired with style.
+macro_rules! styled_methods {
+    ($($name:ident => $style:expr,)*) => {
+        $(
+            #[doc = concat!("Return text paired with `", stringify!($style), "`, see [`Codes::paint`].")]
+            pub fn $name<'a>(&'a self, text: &'a str) -> Styled<'a> {
+                self.paint(text, $style)
+            }
+        )*
+    };
+}
+
+impl Codes {
+    /// Return builder for style rendered through this instance, e.g.
+    /// `ansi.style().fg_red().bold().paint("error")`.
+    pub fn style(&self) -> StyleBuilder<'_> {
+        StyleBuilder {
+            codes: self,
+            style: Style::new(),
+        }
+    }
+
+    /// Return text paired with style, color or attribute, displayed with
+    /// style and reset, e.g. `println!("{}", ansi.paint("error", Color::Red))`.
+    pub fn paint<'a, S: Into<Style>>(&'a self, text: &'a str, style: S) -> Styled<'a> {
+        Styled::new(self, style.into(), text)
+    }
+
+    styled_methods! {
+        black => Color::Black,
+        red => Color::Red,
+        green => Color::Green,
+        yellow => Color::Yellow,
+        blue => Color::Blue,
+        magenta => Color::Magenta,
+        cyan => Color::Cyan,
+        white => Color::White,
+        bright_black => Color::BrightBlack,
+        bright_red => Color::BrightRed,
+        bright_green => Color::BrightGreen,
+        bright_yellow => Color::BrightYellow,
+        bright_blue => Color::BrightBlue,
+        bright_magenta => Color::BrightMagenta,
+        bright_cyan => Color::BrightCyan,
+        bright_white => Color::BrightWhite,
+        bold => Attr::Bold,
+        dim => Attr::Dim,
+        italic => Attr::Italic,
+        underline => Attr::Underline,
+    }
+}
+
+impl From<Color> for Style {
+    /// Return style with foreground color.
+    fn from(color: Color) -> Style {
+        Style::new().fg(color)
+    }
+}
+
+impl From<Attr> for Style {
+    /// Return style with attribute.
+    fn from(attr: Attr) -> Style {
+        Style::new().attr(attr)
+    }
+}
+
+impl<const N: usize> From<[Attr; N]> for Style {
+    /// Return style with all attributes.
+    fn from(attrs: [Attr; N]) -> Style {
+        Style::from(&attrs[..])
+    }
+}
+
+impl From<&[Attr]> for Style {
+    /// Return style with all attributes.
+    fn from(attrs: &[Attr]) -> Style {
+        attrs
+            .iter()
+            .fold(Style::new(), |style, &attr| style.attr(attr))
+    }
+}
+
+/// Combine styles, see [`Style::merge`].
+impl Add for Style {
+    type Output = Style;
+
+    fn add(self, other: Style) -> Style {
+        self.merge(other)
+    }
+}
+
+/// Combine styles, see [`Style::merge`].
+impl BitOr for Style {
+    type Output = Style;
+
+    fn bitor(self, other: Style) -> Style {
+        self.merge(other)
+    }
+}
+
+/// Set foreground color.
+impl Add<Color> for Style {
+    type Output = Style;
+
+    fn add(self, color: Color) -> Style {
+        self.fg(color)
+    }
+}
+
+/// Enable attribute.
+impl Add<Attr> for Style {
+    type Output = Style;
+
+    fn add(self, attr: Attr) -> Style {
+        self.attr(attr)
+    }
+}
+
+/// Enable attribute.
+impl BitOr<Attr> for Style {
+    type Output = Style;
+
+    fn bitor(self, attr: Attr) -> Style {
+        self.attr(attr)
+    }
+}
+
+/// Maximum length of combined SGR sequence: `ESC [`, every attribute plus
+/// foreground and background as parameters of up to three digits with
+/// separator, and final `m`.
+const SGR_CAPACITY: usize = 2 + (Attr::ALL.len() + 2) * 4 + 1;
+
+/// Combined SGR sequence stored inline without heap allocation.
+#[derive(Clone, Copy)]
+pub struct Sgr {
+    buf: [u8; SGR_CAPACITY],
+    len: usize,
+}
+
+impl Sgr {
+    fn new() -> Sgr {
+        Sgr {
+            buf: [0; SGR_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Append parameter, starting sequence or adding separator as needed.
+    fn push(&mut self, param: u8) {
+        let mut digits = [0u8; 3];
+        let mut n = 0;
+        let mut value = param;
+        loop {
+            digits[n] = b'0' + value % 10;
+            n += 1;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+
+        if self.len == 0 {
+            self.extend(b"\x1B[");
+        } else {
+            self.extend(b";");
+        }
+        for i in (0..n).rev() {
+            self.extend(&[digits[i]]);
+        }
+    }
+
+    /// Terminate sequence if any parameters were added.
+    fn finish(&mut self) {
+        if self.len > 0 {
+            self.extend(b"m");
+        }
+    }
+
+    /// Append bytes, bytes not fitting into buffer are dropped.
+    fn extend(&mut self, bytes: &[u8]) {
+        let end = (self.len + bytes.len()).min(SGR_CAPACITY);
+        self.buf[self.len..end].copy_from_slice(&bytes[..end - self.len]);
+        self.len = end;
+    }
+
+    /// Return sequence bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Return sequence as string.
+    pub fn as_str(&self) -> &str {
+        // Only ASCII is ever written to buffer.
+        std::str::from_utf8(self.as_bytes()).unwrap_or_default()
+    }
+
+    /// Check if sequence is empty, i.e. style is plain.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl fmt::Display for Sgr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Sgr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi::apply_sgr;
+
+    #[test]
+    fn parse_attr_names() {
+        assert_eq!("Strike".parse(), Ok(Attr::Strikethrough));
+        assert_eq!("double-underline".parse(), Ok(Attr::DoubleUnderline));
+        assert_eq!("conceal".parse(), Ok(Attr::Hidden));
+        assert_eq!("overline".parse(), Ok(Attr::Overline));
+        assert!("sparkle".parse::<Attr>().is_err());
+    }
+
+    #[test]
+    fn parse_style_names() {
+        let style = parse_style("bright-red, bg:blue strikethrough").unwrap();
+        assert_eq!(style.fg, Some(Color::BrightRed));
+        assert_eq!(style.bg, Some(Color::Blue));
+        assert!(style.has(Attr::Strikethrough));
+        assert!(parse_style("bg:nope").is_err());
+    }
+
+    #[test]
+    fn apply_new_attributes() {
+        let style = apply_sgr(Style::new(), "\x1B[8;9;21;53m");
+        for attr in [
+            Attr::Hidden,
+            Attr::Strikethrough,
+            Attr::DoubleUnderline,
+            Attr::Overline,
+        ] {
+            assert!(style.has(attr));
+        }
+        assert_eq!(apply_sgr(style, "\x1B[24;28;29;55m"), Style::new());
+    }
+
+    #[test]
+    fn apply_subparameters() {
+        let style = Style::from_sgr("\x1B[1;4:3m");
+        assert!(style.has(Attr::Bold) && style.has(Attr::Underline));
+        let style = apply_sgr(Style::new(), "\x1B[1;x;3m");
+        assert_eq!(style, Style::new().attr(Attr::Bold).attr(Attr::Italic));
+    }
+
+    #[test]
+    fn apply_extended_colors() {
+        let red = Style::new().fg(Color::Red).bg(Color::Red);
+        let style = apply_sgr(red, "\x1B[38;5;4;48;2;0;0;255;1m");
+        assert_eq!(style.fg, Some(Color::Blue));
+        assert_eq!(style.bg, Some(crate::convert::rgb_to_16(0, 0, 255)));
+        assert!(style.has(Attr::Bold));
+        let style = apply_sgr(red, "\x1B[38:2::0:255:0;48:5:0m");
+        assert_eq!(style.fg, Some(crate::convert::rgb_to_16(0, 255, 0)));
+        assert_eq!(style.bg, Some(Color::Black));
+        // Truncated arguments leave colors unchanged.
+        assert_eq!(apply_sgr(red, "\x1B[38;5m"), red);
+    }
+
+    #[test]
+    fn sgr_round_trip() {
+        let style = Style::new()
+            .fg(Color::Green)
+            .attr(Attr::Hidden)
+            .attr(Attr::Overline)
+            .attr(Attr::DoubleUnderline);
+        assert_eq!(style.sgr(), "\x1B[8;21;53;32m");
+        assert_eq!(apply_sgr(Style::new(), &style.sgr()), style);
+        let cleared = apply_sgr(style, "\x1B[24;28;55m");
+        assert_eq!(cleared, Style::new().fg(Color::Green));
+    }
+
+    #[test]
+    fn all_lists_every_variant() {
+        for (i, attr) in Attr::ALL.iter().enumerate() {
+            assert_eq!(*attr as usize, i);
+        }
+        for (i, color) in Color::ALL.iter().enumerate() {
+            assert_eq!(*color as usize, i);
+        }
+        let codes = crate::init_on_with(crate::ColorSupport::Ansi16);
+        for attr in Attr::ALL {
+            assert_eq!(attr.code(&codes), format!("\x1B[{}m", attr.param()));
+        }
+    }
+
+    #[test]
+    fn sgr_fits_every_style() {
+        let mut style = Style::new().fg(Color::BrightWhite).bg(Color::BrightWhite);
+        for attr in Attr::ALL {
+            style = style.attr(attr);
+        }
+        let sgr = style.sgr();
+        assert!(sgr.len() <= SGR_CAPACITY);
+        assert!(sgr.ends_with(";97;107m"));
+    }
+}
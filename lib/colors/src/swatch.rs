@@ -0,0 +1,32 @@
+//! Inline color swatches.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! for &rgb in &theme.palette.colors {
+//!     print!("{} ", colors::swatch(&ansi, rgb, true));
+//! }
+//! ```
+
+use crate::scheme::hex;
+use crate::{contrast_fg, Codes};
+
+/// Return small block painted in RGB color, optionally labeled with its hex
+/// value in black or white, whichever is more readable. Only the label is
+/// returned if colors are disabled in `codes`.
+pub fn swatch(codes: &Codes, rgb: (u8, u8, u8), label: bool) -> String {
+    let text = if label { hex(rgb) } else { String::new() };
+    if !codes.is_enabled() {
+        return text;
+    }
+
+    let (r, g, b) = rgb;
+    format!(
+        "{}{} {} {}",
+        codes.bg_rgb(r, g, b),
+        codes.fg.get(contrast_fg(rgb)),
+        text,
+        codes.attr.reset
+    )
+}
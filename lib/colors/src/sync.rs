@@ -0,0 +1,10 @@
+//! Synchronized output (DEC private mode 2026).
+//!
+//! Terminals supporting the mode buffer everything between [`BEGIN`] and
+//! [`END`] and paint it at once, which removes flicker when redrawing. Other
+//! terminals ignore the sequences.
+
+/// Begin synchronized update.
+pub const BEGIN: &str = "\x1B[?2026h";
+/// End synchronized update.
+pub const END: &str = "\x1B[?2026l";
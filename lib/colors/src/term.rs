@@ -0,0 +1,538 @@
+//! Terminal state and control.
+//!
+//! Size queries and resize notifications, used by anything that needs to
+//! re-render when the terminal window changes, plus window title handling
+//! and iTerm2 badges and attention requests.
+//! Functions writing control sequences do nothing unless standard output is
+//! an interactive TTY.
+
+use std::env;
+#[cfg(unix)]
+use std::fs::{File, OpenOptions};
+#[cfg(unix)]
+use std::io::Read;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+#[cfg(windows)]
+use std::time::Duration;
+
+use crate::cursor;
+use crate::stats::{self, Source};
+
+type ResizeCallback = Arc<dyn Fn(u16, u16) + Send + Sync>;
+
+static RESIZE_CALLBACKS: Mutex<Vec<ResizeCallback>> = Mutex::new(Vec::new());
+static RESIZE_INIT: Once = Once::new();
+#[cfg(unix)]
+static RESIZE_PIPE: AtomicI32 = AtomicI32::new(-1);
+static TITLES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Return terminal size as columns and rows.
+///
+/// Size is read from standard output using libc ioctl(), or console screen
+/// buffer on Windows, falling back to `COLUMNS` and `LINES` environment
+/// variables.
+pub fn size() -> Option<(u16, u16)> {
+    if let Some(size) = stdout_size() {
+        return Some(size);
+    }
+
+    let cols = env::var("COLUMNS").ok()?.parse().ok()?;
+    let rows = env::var("LINES").ok()?.parse().ok()?;
+    Some((cols, rows))
+}
+
+/// Return size of terminal connected to standard output.
+#[cfg(unix)]
+fn stdout_size() -> Option<(u16, u16)> {
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) } == 0;
+    if ok && ws.ws_col > 0 && ws.ws_row > 0 {
+        Some((ws.ws_col, ws.ws_row))
+    } else {
+        None
+    }
+}
+
+/// Return size of console connected to standard output.
+#[cfg(windows)]
+fn stdout_size() -> Option<(u16, u16)> {
+    crate::windows::size(1)
+}
+
+/// Run resize callbacks with new size.
+fn run_callbacks(cols: u16, rows: u16) {
+    // Callbacks may register further callbacks, so lock is not held.
+    let callbacks = RESIZE_CALLBACKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    for callback in callbacks.iter() {
+        callback(cols, rows);
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_sigwinch(_: libc::c_int) {
+    let fd = RESIZE_PIPE.load(Ordering::SeqCst);
+    if fd >= 0 {
+        // Only async-signal-safe call, waking up watcher thread.
+        unsafe { libc::write(fd, b"!".as_ptr() as *const libc::c_void, 1) };
+    }
+}
+
+/// Install `SIGWINCH` handler and start watcher thread running callbacks.
+#[cfg(unix)]
+fn init_resize() {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return;
+    }
+    let (read_fd, write_fd) = (fds[0], fds[1]);
+    // Signal handler must never block on full pipe, watcher reads until empty.
+    for fd in fds {
+        unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
+        }
+    }
+    RESIZE_PIPE.store(write_fd, Ordering::SeqCst);
+
+    thread::spawn(move || {
+        let mut buf = [0u8; 64];
+        loop {
+            let mut poll = libc::pollfd {
+                fd: read_fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            if unsafe { libc::poll(&mut poll, 1, -1) } < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                break;
+            }
+            // Drain pipe, signals arriving meanwhile need one run only.
+            let mut closed = false;
+            loop {
+                let n = unsafe {
+                    libc::read(read_fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len())
+                };
+                if n == 0 {
+                    closed = true;
+                }
+                if n <= 0 {
+                    break;
+                }
+            }
+            if closed {
+                break;
+            }
+            if let Some((cols, rows)) = size() {
+                run_callbacks(cols, rows);
+            }
+        }
+    });
+
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_sigwinch as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(libc::SIGWINCH, &action, std::ptr::null_mut());
+    }
+}
+
+/// Start watcher thread polling console size, Windows has no resize signal
+/// for consoles.
+#[cfg(windows)]
+fn init_resize() {
+    thread::spawn(|| {
+        let mut resize = Resize::new();
+        loop {
+            thread::sleep(Duration::from_millis(250));
+            if let Some((cols, rows)) = resize.changed() {
+                run_callbacks(cols, rows);
+            }
+        }
+    });
+}
+
+/// Register callback run with new columns and rows whenever terminal is
+/// resized.
+///
+/// Callbacks run on a background thread and stay registered for the lifetime
+/// of the program. Use [`Resize`] for polling from a render loop instead.
+pub fn on_resize<F: Fn(u16, u16) + Send + Sync + 'static>(callback: F) {
+    RESIZE_INIT.call_once(init_resize);
+    RESIZE_CALLBACKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(callback));
+}
+
+/// Polling based resize detection, e.g. checked once per rendered frame.
+pub struct Resize {
+    last: Option<(u16, u16)>,
+}
+
+impl Resize {
+    /// Return watcher remembering current terminal size.
+    pub fn new() -> Resize {
+        Resize { last: size() }
+    }
+
+    /// Return new columns and rows if size differs since last check.
+    pub fn changed(&mut self) -> Option<(u16, u16)> {
+        let current = size();
+        if current == self.last {
+            return None;
+        }
+        self.last = current;
+        current
+    }
+}
+
+impl Default for Resize {
+    fn default() -> Resize {
+        Resize::new()
+    }
+}
+
+/// Write control sequence to standard output if it is a TTY.
+fn emit(seq: &str) -> io::Result<()> {
+    if !crate::is_tty() {
+        return Ok(());
+    }
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(seq.as_bytes())?;
+    stats::record(Source::Control, seq.as_bytes(), false);
+    stdout.flush()
+}
+
+/// Check if terminal is known to support title stack (XTWINOPS 22/23).
+fn title_stack_supported() -> bool {
+    let has = |key| env::var_os(key).is_some();
+    if has("TMUX") {
+        return false;
+    }
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    has("XTERM_VERSION")
+        || has("VTE_VERSION")
+        || has("KITTY_WINDOW_ID")
+        || has("ALACRITTY_WINDOW_ID")
+        || program == "WezTerm"
+        || program == "iTerm.app"
+}
+
+/// Set window title (OSC 2).
+pub fn set_title(title: &str) -> io::Result<()> {
+    emit(&format!("\x1B]2;{}\x07", title))
+}
+
+/// Save current window title and set new one.
+///
+/// Title is saved on the terminal title stack where supported. Titles set
+/// through this function are also remembered by the library, used by
+/// [`pop_title`] on other terminals.
+pub fn push_title(title: &str) -> io::Result<()> {
+    TITLES.lock().unwrap().push(title.to_string());
+    if title_stack_supported() {
+        emit("\x1B[22;2t")?;
+    }
+    set_title(title)
+}
+
+/// Restore window title active before last [`push_title`].
+///
+/// Without terminal title stack support the previously pushed title is set
+/// again, or an empty title if there is none, which most terminals treat as
+/// their default title.
+pub fn pop_title() -> io::Result<()> {
+    let mut titles = TITLES.lock().unwrap();
+    titles.pop();
+    if title_stack_supported() {
+        return emit("\x1B[23;2t");
+    }
+    let previous = titles.last().cloned().unwrap_or_default();
+    drop(titles);
+    set_title(&previous)
+}
+
+/// Guard setting window title while alive, restored by [`pop_title`] on drop.
+pub struct TitleGuard {
+    _private: (),
+}
+
+impl TitleGuard {
+    /// Push new window title, see [`push_title`].
+    pub fn new(title: &str) -> TitleGuard {
+        let _ = push_title(title);
+        TitleGuard { _private: () }
+    }
+}
+
+impl Drop for TitleGuard {
+    fn drop(&mut self) {
+        let _ = pop_title();
+    }
+}
+
+/// Soft reset terminal (DECSTR), reset attributes and show cursor.
+///
+/// Recovers from state left behind by crashed child processes, e.g. hidden
+/// cursor, stray colors or changed scroll region, without clearing screen.
+pub fn soft_reset() -> io::Result<()> {
+    emit(&format!("\x1B[!p\x1B[0m{}", cursor::SHOW))
+}
+
+/// Check if terminal is iTerm2.
+pub fn is_iterm2() -> bool {
+    env::var("TERM_PROGRAM").is_ok_and(|v| v == "iTerm.app")
+        || env::var("LC_TERMINAL").is_ok_and(|v| v == "iTerm2")
+}
+
+/// Return data encoded as standard base64 with padding.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Set iTerm2 session badge, shown large in background of session. Empty
+/// badge removes it. Nothing is written on other terminals.
+pub fn set_badge(badge: &str) -> io::Result<()> {
+    if !is_iterm2() {
+        return Ok(());
+    }
+    emit(&format!(
+        "\x1B]1337;SetBadgeFormat={}\x07",
+        base64(badge.as_bytes())
+    ))
+}
+
+/// Request attention in iTerm2, i.e. bounce dock icon until application is
+/// activated, or stop request. Nothing is written on other terminals.
+pub fn request_attention(enable: bool) -> io::Result<()> {
+    if !is_iterm2() {
+        return Ok(());
+    }
+    let value = if enable { "yes" } else { "no" };
+    emit(&format!("\x1B]1337;RequestAttention={}\x07", value))
+}
+
+/// Controlling terminal opened through `/dev/tty` in raw mode, previous mode
+/// is restored on drop.
+#[cfg(unix)]
+pub(crate) struct RawTty {
+    file: File,
+    saved: libc::termios,
+}
+
+#[cfg(unix)]
+impl RawTty {
+    /// Open controlling terminal and switch to raw mode without echo.
+    pub(crate) fn open() -> io::Result<RawTty> {
+        let file = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        let fd = file.as_raw_fd();
+        let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(fd, &mut saved) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut raw = saved;
+        unsafe { libc::cfmakeraw(&mut raw) };
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(RawTty { file, saved })
+    }
+
+    /// Write sequence and return reply up to and including `terminator`.
+    /// Fails with [`io::ErrorKind::TimedOut`] if terminal does not reply
+    /// within `timeout_ms` milliseconds.
+    pub(crate) fn query(
+        &mut self,
+        seq: &str,
+        terminator: u8,
+        timeout_ms: i32,
+    ) -> io::Result<Vec<u8>> {
+        self.file.write_all(seq.as_bytes())?;
+        self.file.flush()?;
+
+        let mut reply = Vec::new();
+        let mut byte = [0u8; 1];
+        loop {
+            let mut pfd = libc::pollfd {
+                fd: self.file.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            match unsafe { libc::poll(&mut pfd, 1, timeout_ms) } {
+                0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "no reply from terminal",
+                    ))
+                }
+                n if n < 0 => return Err(io::Error::last_os_error()),
+                _ => (),
+            }
+            if self.file.read(&mut byte)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            reply.push(byte[0]);
+            if byte[0] == terminator {
+                return Ok(reply);
+            }
+        }
+    }
+
+    /// Write text to terminal.
+    pub(crate) fn write_str(&mut self, text: &str) -> io::Result<()> {
+        self.file.write_all(text.as_bytes())?;
+        self.file.flush()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawTty {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(self.file.as_raw_fd(), libc::TCSANOW, &self.saved) };
+    }
+}
+
+/// Terminal queries are not supported on Windows consoles.
+#[cfg(windows)]
+pub(crate) struct RawTty {
+    _private: (),
+}
+
+#[cfg(windows)]
+impl RawTty {
+    /// Fail with [`io::ErrorKind::Unsupported`].
+    pub(crate) fn open() -> io::Result<RawTty> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "terminal queries are not supported on Windows",
+        ))
+    }
+
+    pub(crate) fn query(&mut self, _: &str, _: u8, _: i32) -> io::Result<Vec<u8>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(crate) fn write_str(&mut self, _: &str) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Return zero-based cursor column and row reported by terminal (DSR 6).
+pub(crate) fn query_cursor(tty: &mut RawTty) -> io::Result<(u16, u16)> {
+    let reply = tty.query("\x1B[6n", b'R', 500)?;
+    let reply = String::from_utf8_lossy(&reply);
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid cursor position reply");
+    let params = reply
+        .rsplit_once("\x1B[")
+        .and_then(|(_, r)| r.strip_suffix('R'))
+        .ok_or_else(invalid)?;
+    let (row, col) = params.split_once(';').ok_or_else(invalid)?;
+    let row: u16 = row.parse().map_err(|_| invalid())?;
+    let col: u16 = col.parse().map_err(|_| invalid())?;
+    Ok((col.saturating_sub(1), row.saturating_sub(1)))
+}
+
+/// Return zero-based cursor column and row, queried from terminal.
+pub fn cursor_position() -> io::Result<(u16, u16)> {
+    query_cursor(&mut RawTty::open()?)
+}
+
+/// Return color component of 1 to 4 hex digits scaled to 8 bits.
+fn hex_component(hex: &str) -> Option<u8> {
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = match hex.len() {
+        1..=4 => (1u32 << (4 * hex.len())) - 1,
+        _ => return None,
+    };
+    Some((value * 255 / max) as u8)
+}
+
+/// Return background color reported by terminal (OSC 11), e.g.
+/// `rgb:1e1e/1e1e/1e1e`. Cursor position (DSR 6) is requested right after,
+/// so terminals not knowing OSC 11 fail fast instead of timing out.
+pub(crate) fn query_background(tty: &mut RawTty) -> io::Result<(u8, u8, u8)> {
+    let reply = tty.query("\x1B]11;?\x1B\\\x1B[6n", b'R', 100)?;
+    parse_background(&String::from_utf8_lossy(&reply))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no background color reply"))
+}
+
+/// Return background color of OSC 11 reply, terminated by BEL or ST, among
+/// other replies.
+fn parse_background(reply: &str) -> Option<(u8, u8, u8)> {
+    let (_, rest) = reply.split_once("\x1B]11;")?;
+    let spec = &rest[..rest.find(['\x07', '\x1B'])?];
+    let spec = spec
+        .strip_prefix("rgb:")
+        .or_else(|| spec.strip_prefix("rgba:"))?;
+    let mut parts = spec.split('/').map(hex_component);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Some(r)), Some(Some(g)), Some(Some(b))) => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+/// Return background color as RGB, queried from terminal.
+pub fn background_color() -> io::Result<(u8, u8, u8)> {
+    query_background(&mut RawTty::open()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn callbacks_register_callbacks() {
+        let count = || RESIZE_CALLBACKS.lock().unwrap().len();
+        on_resize(|_, _| on_resize(|_, _| ()));
+        let before = count();
+        run_callbacks(80, 24);
+        assert_eq!(count(), before + 1);
+    }
+
+    #[test]
+    fn parse_background_reply() {
+        let reply = "\x1B]11;rgb:1e1e/2020/ffff\x1B\\\x1B[24;1R";
+        assert_eq!(parse_background(reply), Some((0x1e, 0x20, 0xff)));
+        let reply = "\x1B]11;rgba:f/80/000/ffff\x07\x1B[1;1R";
+        assert_eq!(parse_background(reply), Some((0xff, 0x80, 0x00)));
+    }
+
+    #[test]
+    fn parse_background_invalid() {
+        // Only cursor position, terminal without OSC 11.
+        assert_eq!(parse_background("\x1B[24;1R"), None);
+        assert_eq!(parse_background("\x1B]11;rgb:1e1e/2020"), None);
+        assert_eq!(parse_background("\x1B]11;#1e2020\x07"), None);
+        assert_eq!(parse_background("\x1B]11;rgb:1e1e/2020/fffff\x07"), None);
+        assert_eq!(parse_background("\x1B]11;rgb:1e1e/xx/ffff\x07"), None);
+    }
+}
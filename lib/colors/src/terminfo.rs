@@ -0,0 +1,160 @@
+//! Minimal reader of compiled terminfo entries.
+//!
+//! Only numeric capabilities are read, which is enough to learn how many
+//! colors a terminal supports without linking against ncurses.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Magic number of legacy format with 16-bit numbers.
+const MAGIC_LEGACY: u16 = 0o432;
+/// Magic number of extended format with 32-bit numbers.
+const MAGIC_EXTENDED: u16 = 0o1036;
+
+/// Index of `colors` among numeric capabilities.
+const COLORS: usize = 13;
+
+/// Return directories searched for terminfo entries, in order.
+fn directories() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        // Empty entry means the system default.
+        dirs.extend(
+            list.split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+    for dir in ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"] {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs
+}
+
+/// Return compiled entry of terminal, stored below first character or its
+/// hexadecimal code on macOS.
+fn entry(term: &str) -> Option<Vec<u8>> {
+    let first = term.chars().next()?;
+    if term.contains('/') {
+        return None;
+    }
+    directories().into_iter().find_map(|dir| {
+        fs::read(dir.join(first.to_string()).join(term))
+            .or_else(|_| fs::read(dir.join(format!("{:x}", u32::from(first))).join(term)))
+            .ok()
+    })
+}
+
+/// Return numeric capability at index, or `None` if absent.
+fn number(data: &[u8], index: usize) -> Option<i32> {
+    let short = |i: usize| -> Option<u16> {
+        Some(u16::from_le_bytes([
+            *data.get(i * 2)?,
+            *data.get(i * 2 + 1)?,
+        ]))
+    };
+    let width = match short(0)? {
+        MAGIC_LEGACY => 2,
+        MAGIC_EXTENDED => 4,
+        _ => return None,
+    };
+    let (names, bools, numbers) = (
+        usize::from(short(1)?),
+        usize::from(short(2)?),
+        usize::from(short(3)?),
+    );
+    if index >= numbers {
+        return None;
+    }
+
+    // Numbers start on even offset after header, names and booleans.
+    let mut offset = 12 + names + bools;
+    offset += offset % 2;
+    let value = data.get(offset + index * width..offset + (index + 1) * width)?;
+    let value = match width {
+        2 => i32::from(i16::from_le_bytes([value[0], value[1]])),
+        _ => i32::from_le_bytes([value[0], value[1], value[2], value[3]]),
+    };
+    (value >= 0).then_some(value)
+}
+
+/// Return number of colors terminal entry declares, or `None` if entry is
+/// missing or declares none.
+pub(crate) fn colors(term: &str) -> Option<i32> {
+    number(&entry(term)?, COLORS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return compiled entry with given names and numbers, numbers are
+    /// written with `width` bytes each and `-1` marks absent ones.
+    fn compile(magic: u16, names: &str, bools: usize, numbers: &[i32], width: usize) -> Vec<u8> {
+        let names = format!("{}\0", names);
+        let mut data = Vec::new();
+        for short in [
+            magic,
+            names.len() as u16,
+            bools as u16,
+            numbers.len() as u16,
+            0,
+            0,
+        ] {
+            data.extend_from_slice(&short.to_le_bytes());
+        }
+        data.extend_from_slice(names.as_bytes());
+        data.resize(data.len() + bools, 1);
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        for &number in numbers {
+            data.extend_from_slice(&number.to_le_bytes()[..width]);
+        }
+        data
+    }
+
+    #[test]
+    fn read_legacy_colors() {
+        let mut numbers = [-1; 15];
+        numbers[COLORS] = 8;
+        // Odd length of names and booleans needs padding.
+        let data = compile(
+            MAGIC_LEGACY,
+            "xterm|xterm terminal emulator",
+            1,
+            &numbers,
+            2,
+        );
+        assert_eq!(number(&data, COLORS), Some(8));
+        assert_eq!(number(&data, 0), None);
+        assert_eq!(number(&data, 20), None);
+    }
+
+    #[test]
+    fn read_extended_colors() {
+        let mut numbers = [-1; 14];
+        numbers[COLORS] = 0x1000000;
+        let data = compile(MAGIC_EXTENDED, "xterm-direct", 3, &numbers, 4);
+        assert_eq!(number(&data, COLORS), Some(0x1000000));
+    }
+
+    #[test]
+    fn reject_invalid_entries() {
+        let mut numbers = [-1; 14];
+        numbers[COLORS] = 256;
+        let data = compile(0o1234, "bogus", 0, &numbers, 2);
+        assert_eq!(number(&data, COLORS), None);
+        let data = compile(MAGIC_LEGACY, "short", 0, &numbers, 2);
+        assert_eq!(number(&data[..data.len() - 2], COLORS), None);
+        assert_eq!(number(&[], COLORS), None);
+        assert_eq!(entry("../../etc/passwd"), None);
+    }
+}
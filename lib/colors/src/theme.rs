@@ -0,0 +1,278 @@
+//! Styles for semantic roles.
+//!
+//! A [`Theme`] maps roles like errors, headers and muted text to styles, so
+//! call sites use roles instead of hard coded colors and themes can be
+//! swapped without touching them. Fields are public, entries can be
+//! overridden using struct update syntax. [`Theme::apply`] makes components
+//! using [`Level`] styles follow the theme as well.
+//!
+//! With feature `toml-theme` end users can customize roles in `[theme]` table
+//! of a config file, typically `~/.config/<app>/colors.toml`, read by
+//! [`Theme::from_toml`]:
+//!
+//! ```toml
+//! [theme]
+//! error = "bright_red bold"
+//! header = "blue underline"
+//! ```
+//!
+//! High contrast mode is requested by setting `COLORS_HIGH_CONTRAST` to a
+//! value other than `0`, by `theme-variant = "high-contrast"` in user
+//! configuration or by a high contrast GTK theme. Level styles and
+//! [`Theme::detect`] follow it. [`Theme::detect`] also switches to
+//! [`Theme::light`] on light backgrounds, see [`BackgroundKind::detect`].
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let theme = colors::Theme {
+//!     header: colors::Style::new().fg(colors::Color::Magenta),
+//!     ..colors::Theme::detect()
+//! };
+//! println!("{}", theme.header.paint(&ansi, "Results"));
+//! println!("{} disk almost full", theme.warning.paint(&ansi, "warning:"));
+//! println!("{}", theme.annotate(&ansi, "skrot", "v0.1.0"));
+//! ```
+
+use std::env;
+#[cfg(feature = "toml-theme")]
+use std::fs;
+#[cfg(feature = "toml-theme")]
+use std::io;
+#[cfg(feature = "toml-theme")]
+use std::path::Path;
+
+#[cfg(feature = "toml-theme")]
+use crate::config::entries;
+#[cfg(feature = "toml-theme")]
+use crate::{parse_style, ParseConfigError};
+
+use crate::scheme::{self, Base16, Format, Palette, ParseSchemeError};
+use crate::{config, set_semantic, Attr, BackgroundKind, Codes, Color, Level, Style};
+
+/// Styles used for semantic roles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Theme {
+    pub error: Style,
+    pub warning: Style,
+    pub info: Style,
+    pub success: Style,
+    /// Suggestions, e.g. `help: try --force`.
+    pub hint: Style,
+    /// Titles of sections and tables.
+    pub header: Style,
+    /// Secondary text, e.g. versions, paths or durations next to primary text.
+    pub muted: Style,
+    /// Colors of terminal emulator the theme is designed for.
+    pub palette: Palette,
+}
+
+impl Theme {
+    /// Return built-in default theme.
+    pub const fn new() -> Theme {
+        Theme {
+            error: Style::new().fg(Color::Red).attr(Attr::Bold),
+            warning: Style::new().fg(Color::Yellow).attr(Attr::Bold),
+            info: Style::new().fg(Color::Cyan).attr(Attr::Bold),
+            success: Style::new().fg(Color::Green).attr(Attr::Bold),
+            hint: Style::new().fg(Color::Blue),
+            header: Style::new().attr(Attr::Bold),
+            muted: Style::new().attr(Attr::Dim),
+            palette: Palette::new(),
+        }
+    }
+
+    /// Return default theme, adjusted for light appearance and remapped for
+    /// high contrast if requested.
+    pub fn detect() -> Theme {
+        let theme = match BackgroundKind::detect() {
+            BackgroundKind::Light => Theme::new().light(),
+            _ => Theme::new(),
+        };
+        if high_contrast_requested() {
+            theme.high_contrast()
+        } else {
+            theme
+        }
+    }
+
+    /// Return theme with muted text in bright black instead of dim, which is
+    /// barely visible on some light backgrounds.
+    pub fn light(self) -> Theme {
+        Theme {
+            muted: Style::new().fg(Color::BrightBlack),
+            ..self
+        }
+    }
+
+    /// Return theme with all styles remapped, see [`Style::high_contrast`].
+    pub fn high_contrast(self) -> Theme {
+        Theme {
+            error: self.error.high_contrast(),
+            warning: self.warning.high_contrast(),
+            info: self.info.high_contrast(),
+            success: self.success.high_contrast(),
+            hint: self.hint.high_contrast(),
+            header: self.header.high_contrast(),
+            muted: self.muted.high_contrast(),
+            ..self
+        }
+    }
+
+    /// Return style of role matching level, verbose and debug messages are
+    /// muted.
+    pub fn level(&self, level: Level) -> Style {
+        match level {
+            Level::Error => self.error,
+            Level::Warning => self.warning,
+            Level::Info => self.info,
+            Level::Success => self.success,
+            Level::Hint => self.hint,
+            Level::Verbose | Level::Debug => self.muted,
+        }
+    }
+
+    /// Set level styles used by all components for whole program to styles of
+    /// theme, see [`set_semantic`].
+    pub fn apply(&self) {
+        for level in Level::ALL {
+            set_semantic(level, self.level(level));
+        }
+    }
+
+    /// Return default theme using palette imported from terminal emulator
+    /// scheme file, base16 schemes also map semantic roles, see
+    /// [`Base16::theme`].
+    pub fn import(text: &str, format: Format) -> Result<Theme, ParseSchemeError> {
+        if format == Format::Base16Yaml {
+            return Ok(Base16::parse(text)?.theme());
+        }
+        Ok(Theme {
+            palette: scheme::import(text, format)?,
+            ..Theme::new()
+        })
+    }
+
+    /// Parse theme from `role = "style"` lines in `[theme]` table of file
+    /// read by [`Config`](crate::Config), other lines are skipped. Styles are
+    /// parsed with [`parse_style`] and roles missing in text keep default
+    /// styles.
+    #[cfg(feature = "toml-theme")]
+    pub fn parse_config(text: &str) -> Result<Theme, ParseConfigError> {
+        let mut theme = Theme::new();
+        for entry in entries(text, "theme") {
+            let (line, key, value) = entry?;
+            let role = match key {
+                "error" => &mut theme.error,
+                "warning" => &mut theme.warning,
+                "info" => &mut theme.info,
+                "success" => &mut theme.success,
+                "hint" => &mut theme.hint,
+                "header" => &mut theme.header,
+                "muted" => &mut theme.muted,
+                key => {
+                    return Err(ParseConfigError {
+                        line,
+                        message: format!("unknown role '{}'", key),
+                    })
+                }
+            };
+            *role = parse_style(value).map_err(|e| ParseConfigError {
+                line,
+                message: e.to_string(),
+            })?;
+        }
+        Ok(theme)
+    }
+
+    /// Read theme from config file, see [`Theme::parse_config`]. Invalid
+    /// files give errors of kind [`io::ErrorKind::InvalidData`].
+    #[cfg(feature = "toml-theme")]
+    pub fn from_toml<P: AsRef<Path>>(path: P) -> io::Result<Theme> {
+        let text = fs::read_to_string(path)?;
+        Theme::parse_config(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Read theme from config file, see [`Theme::from_toml`].
+    #[cfg(feature = "toml-theme")]
+    #[deprecated(note = "use Theme::from_toml")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Theme> {
+        Theme::from_toml(path)
+    }
+
+    /// Return palette of theme as terminal emulator scheme file.
+    pub fn export(&self, format: Format) -> String {
+        scheme::export(&self.palette, format)
+    }
+
+    /// Return primary text followed by muted secondary text in parentheses,
+    /// e.g. `skrot (v0.1.0)`.
+    pub fn annotate(&self, codes: &Codes, primary: &str, secondary: &str) -> String {
+        let secondary = format!("({})", secondary);
+        format!("{} {}", primary, self.muted.paint(codes, &secondary))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::new()
+    }
+}
+
+/// Return primary text followed by muted secondary text in parentheses using
+/// default theme, see [`Theme::annotate`].
+pub fn annotate(codes: &Codes, primary: &str, secondary: &str) -> String {
+    Theme::new().annotate(codes, primary, secondary)
+}
+
+/// Check if high contrast is requested by `COLORS_HIGH_CONTRAST`, by user
+/// configuration or by a high contrast GTK theme.
+pub fn high_contrast_requested() -> bool {
+    if let Ok(value) = env::var("COLORS_HIGH_CONTRAST") {
+        return !value.is_empty() && value != "0";
+    }
+    if let Some(high_contrast) = config().high_contrast {
+        return high_contrast;
+    }
+    env::var("GTK_THEME").is_ok_and(|theme| theme.to_lowercase().contains("highcontrast"))
+}
+
+#[cfg(all(test, feature = "toml-theme"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config_theme_table() {
+        let text = "depth = 256\n[theme]\nerror = \"bright_red bold\"\nmuted = dim\n";
+        let theme = Theme::parse_config(text).unwrap();
+        assert_eq!(
+            theme.error,
+            Style::new().fg(Color::BrightRed).attr(Attr::Bold)
+        );
+        assert_eq!(theme.muted, Style::new().attr(Attr::Dim));
+        assert_eq!(theme.header, Theme::new().header);
+    }
+
+    #[test]
+    fn from_toml_file() {
+        let path = env::temp_dir().join(format!("colors-theme-{}.toml", std::process::id()));
+        fs::write(&path, "[theme]\nheader = \"blue underline\"\n").unwrap();
+        let theme = Theme::from_toml(&path);
+        fs::write(&path, "[theme]\nheader = plaid\n").unwrap();
+        let invalid = Theme::from_toml(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            theme.unwrap().header,
+            Style::new().fg(Color::Blue).attr(Attr::Underline)
+        );
+        assert_eq!(invalid.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn parse_config_errors() {
+        let error = Theme::parse_config("[theme]\nfooter = red").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: unknown role 'footer'");
+        assert!(Theme::parse_config("[theme]\nerror = chartreuse").is_err());
+    }
+}
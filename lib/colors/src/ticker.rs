@@ -0,0 +1,133 @@
+//! Frame pacing for animated components.
+//!
+//! A [`Ticker`] caps redraw rate to its own frame rate and to the process wide
+//! maximum set with [`set_max_fps`], and skips frames identical to the one
+//! drawn last. It never draws if animations are disabled: output is not a
+//! TTY, `TERM` is `dumb`, a screen reader is used, the host does not support
+//! cursor movement, or output profile or user configuration disallows
+//! animations. Components can then print their final state once instead.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let mut ticker = colors::Ticker::new(30);
+//! while !done() {
+//!     let frame = render();
+//!     if ticker.should_draw(&frame) {
+//!         draw(&frame)?;
+//!     }
+//!     ticker.wait();
+//! }
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::caps::screen_reader_env;
+use crate::profile;
+use crate::{config, is_tty, Host, Profile};
+
+static MAX_FPS: AtomicU32 = AtomicU32::new(60);
+
+/// Set maximum frame rate of every ticker, `0` is treated as `1`.
+pub fn set_max_fps(fps: u32) {
+    MAX_FPS.store(fps.max(1), Ordering::Relaxed);
+}
+
+/// Return maximum frame rate of every ticker, 60 by default.
+pub fn max_fps() -> u32 {
+    MAX_FPS.load(Ordering::Relaxed)
+}
+
+/// Check if animations may be drawn to standard output.
+pub(crate) fn animations_enabled() -> bool {
+    is_tty()
+        && env::var("TERM").map_or(true, |term| term != "dumb")
+        && !screen_reader_env()
+        && Host::detect().supports_cursor()
+        && profile::allows(Profile::animations)
+        && config().animations != Some(false)
+}
+
+/// Return hash of frame content.
+fn hash(frame: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    frame.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Redraw limiter for one animated component.
+#[derive(Clone, Debug)]
+pub struct Ticker {
+    interval: Duration,
+    enabled: bool,
+    next: Instant,
+    changed: Instant,
+    last: Option<u64>,
+}
+
+impl Ticker {
+    /// Return ticker drawing at most `fps` frames per second, limited by
+    /// [`max_fps`]. Whether animations are enabled is detected once here.
+    pub fn new(fps: u32) -> Ticker {
+        let fps = fps.clamp(1, max_fps());
+        let now = Instant::now();
+        Ticker {
+            interval: Duration::from_secs_f64(1.0 / f64::from(fps)),
+            enabled: animations_enabled(),
+            next: now,
+            changed: now,
+            last: None,
+        }
+    }
+
+    /// Check if frames are drawn at all.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Return time between frames.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Check if frame should be drawn now, i.e. animations are enabled, next
+    /// frame is due and content differs from last drawn frame. Frame is
+    /// recorded as drawn if `true` is returned.
+    pub fn should_draw(&mut self, frame: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let now = Instant::now();
+        if now < self.next {
+            return false;
+        }
+        let hash = hash(frame);
+        if self.last == Some(hash) {
+            return false;
+        }
+        self.last = Some(hash);
+        self.next = now + self.interval;
+        self.changed = now;
+        true
+    }
+
+    /// Return time since content last changed, e.g. to slow down or stop
+    /// polling while nothing happens.
+    pub fn idle(&self) -> Duration {
+        self.changed.elapsed()
+    }
+
+    /// Sleep until next frame is due, one interval if nothing was drawn yet.
+    pub fn wait(&mut self) {
+        let now = Instant::now();
+        if self.next <= now {
+            self.next = now + self.interval;
+        }
+        thread::sleep(self.next - now);
+    }
+}
@@ -0,0 +1,159 @@
+//! Timelines of labeled time spans.
+//!
+//! Spans are drawn as bars on a shared time axis scaled to terminal width,
+//! followed by their durations, e.g. for profiling and trace summaries. Time
+//! unit is up to the caller, durations are shown without unit. Bars use
+//! glyphs of the given capabilities.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let io = colors::Style::new().fg(colors::Color::Blue);
+//! let spans = [
+//!     colors::TimeSpan::new("fetch", 0.0, 120.0, io),
+//!     colors::TimeSpan::new("parse", 120.0, 180.0, io.fg(colors::Color::Green)),
+//! ];
+//! print!("{}", colors::timeline(&ansi, &colors::Capabilities::detect(), &spans));
+//! ```
+
+use crate::width::display_width;
+use crate::{fmt_float, term, Capabilities, Codes, Style};
+
+/// Width used if terminal size is unknown.
+const DEFAULT_WIDTH: usize = 80;
+
+/// Labeled span of time drawn as bar.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeSpan<'a> {
+    pub label: &'a str,
+    pub start: f64,
+    pub end: f64,
+    pub style: Style,
+}
+
+impl<'a> TimeSpan<'a> {
+    /// Return span from `start` to `end` drawn in style.
+    pub fn new(label: &'a str, start: f64, end: f64, style: Style) -> TimeSpan<'a> {
+        TimeSpan {
+            label,
+            start,
+            end,
+            style,
+        }
+    }
+}
+
+/// Return timeline scaled to terminal width, see [`timeline_width`].
+pub fn timeline(codes: &Codes, caps: &Capabilities, spans: &[TimeSpan]) -> String {
+    let width = term::size().map_or(DEFAULT_WIDTH, |(cols, _)| usize::from(cols));
+    timeline_width(codes, caps, spans, width)
+}
+
+/// Return one line per span with label, bar placed on time axis from
+/// earliest start to latest end, and duration, fitting in `width` columns.
+/// Every span gets at least one column.
+pub fn timeline_width(
+    codes: &Codes,
+    caps: &Capabilities,
+    spans: &[TimeSpan],
+    width: usize,
+) -> String {
+    let glyphs = caps.glyphs();
+    let first = spans.iter().map(|s| s.start).fold(f64::INFINITY, f64::min);
+    let last = spans
+        .iter()
+        .map(|s| s.end)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let total = if last > first { last - first } else { 1.0 };
+
+    let durations: Vec<String> = spans
+        .iter()
+        .map(|s| fmt_float(s.end - s.start, 1))
+        .collect();
+    let label_width = spans
+        .iter()
+        .map(|s| display_width(s.label))
+        .max()
+        .unwrap_or(0);
+    let duration_width = durations.iter().map(|d| d.len()).max().unwrap_or(0);
+    let axis = width
+        .saturating_sub(label_width + duration_width + 4)
+        .max(1);
+
+    let mut output = String::new();
+    for (span, duration) in spans.iter().zip(&durations) {
+        let position = |time: f64| (time - first) / total * axis as f64;
+        let start = (position(span.start).floor() as usize).min(axis - 1);
+        let end = (position(span.end).ceil() as usize).clamp(start + 1, axis);
+        let bar = glyphs.block_full.repeat(end - start);
+        let padding = " ".repeat(label_width - display_width(span.label));
+        output.push_str(&format!(
+            "{}{}  {}{}{}  {:>w$}\n",
+            span.label,
+            padding,
+            " ".repeat(start),
+            span.style.paint(codes, &bar),
+            " ".repeat(axis - end),
+            duration,
+            w = duration_width
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::degradation::Variant;
+    use crate::{init_off, init_on_with, Charset, Color, ColorSupport};
+
+    /// Return capabilities of color terminal with charset.
+    fn caps(charset: Charset) -> Capabilities {
+        Variant {
+            color: ColorSupport::Ansi16,
+            charset,
+            tty: true,
+        }
+        .capabilities()
+    }
+
+    #[test]
+    fn spans_share_axis() {
+        let spans = [
+            TimeSpan::new("a", 0.0, 50.0, Style::new()),
+            TimeSpan::new("bb", 50.0, 100.0, Style::new()),
+        ];
+        for charset in [Charset::Unicode, Charset::Ascii] {
+            let bar = charset.glyphs().block_full.repeat(5);
+            assert_eq!(
+                timeline_width(&init_off(), &caps(charset), &spans, 20),
+                format!("a   {}       50.0\nbb       {}  50.0\n", bar, bar)
+            );
+        }
+    }
+
+    #[test]
+    fn short_spans_get_one_column() {
+        let codes = init_on_with(ColorSupport::Ansi16);
+        let caps = caps(Charset::Unicode);
+        let style = Style::new().fg(Color::Blue);
+        let spans = [
+            TimeSpan::new("long", 0.0, 1000.0, style),
+            TimeSpan::new("tick", 1000.0, 1000.0, style),
+        ];
+        let output = timeline_width(&codes, &caps, &spans, 30);
+        let bar = caps.glyphs().block_full;
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[1].starts_with("tick  "));
+        assert!(lines[1].contains(&style.paint(&codes, bar)));
+        assert!(lines[1].ends_with("   0.0"));
+        assert!(lines.iter().all(|line| display_width(line) == 30));
+    }
+
+    #[test]
+    fn no_spans() {
+        let caps = caps(Charset::Unicode);
+        assert_eq!(timeline_width(&init_off(), &caps, &[], 40), "");
+    }
+}
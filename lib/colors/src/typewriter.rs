@@ -0,0 +1,52 @@
+//! Typewriter text effect.
+//!
+//! Prints text one character at a time. Escape sequences are written at once
+//! together with the character following them and never split, so styled text
+//! keeps its colors while being revealed.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto();
+//! let text = format!("{}Welcome!{}\n", ansi.attr.bold, ansi.attr.reset);
+//! colors::typewriter(&ansi, &text, Duration::from_millis(40))?;
+//! ```
+
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::ansi::{self, Segment};
+use crate::{max_fps, Codes, Ticker};
+
+/// Print styled text progressively, waiting `delay` after every visible
+/// character. Delays are never shorter than a frame at [`max_fps`].
+///
+/// If colors are disabled in `codes`, i.e. output is not interactive, or
+/// animations are disabled, see [`Ticker`], text is printed at once without
+/// delays.
+pub fn typewriter(codes: &Codes, text: &str, delay: Duration) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let delay = delay.max(Duration::from_secs_f64(1.0 / f64::from(max_fps())));
+
+    if !codes.is_enabled() || !Ticker::new(max_fps()).is_enabled() {
+        write!(stdout, "{}", text)?;
+        return stdout.flush();
+    }
+
+    for segment in ansi::segments(text) {
+        match segment {
+            Segment::Escape(seq) => write!(stdout, "{}", seq)?,
+            Segment::Text(text) => {
+                for c in text.chars() {
+                    write!(stdout, "{}", c)?;
+                    stdout.flush()?;
+                    if !c.is_whitespace() {
+                        thread::sleep(delay);
+                    }
+                }
+            }
+        }
+    }
+    stdout.flush()
+}
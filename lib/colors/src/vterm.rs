@@ -0,0 +1,321 @@
+//! Virtual terminal for testing output.
+//!
+//! Interprets text, SGR sequences, cursor movement and clears into a cell
+//! grid, so output of progress bars, prompts and live regions can be compared
+//! against expected screens. Unsupported sequences are ignored.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let mut vt = colors::VirtualTerm::new(80, 24);
+//! vt.feed(&rendered);
+//! assert_eq!(vt.line(0), "Status: OK");
+//! ```
+
+use std::io::{self, Write};
+
+use crate::ansi::{self, Segment};
+use crate::screen::Cell;
+use crate::width::char_width;
+use crate::Style;
+
+/// Character marking second column of wide character.
+const CONTINUATION: char = '\0';
+
+/// Cell grid interpreting terminal output.
+#[derive(Clone, Debug)]
+pub struct VirtualTerm {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    x: usize,
+    y: usize,
+    saved: (usize, usize),
+    style: Style,
+    cursor_visible: bool,
+    /// Bytes of incomplete character or escape sequence written last.
+    pending: Vec<u8>,
+}
+
+impl VirtualTerm {
+    /// Return blank terminal of given size with cursor at top left.
+    pub fn new(width: usize, height: usize) -> VirtualTerm {
+        VirtualTerm {
+            width: width.max(1),
+            height: height.max(1),
+            cells: vec![Cell::default(); width.max(1) * height.max(1)],
+            x: 0,
+            y: 0,
+            saved: (0, 0),
+            style: Style::new(),
+            cursor_visible: true,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Return cursor position as zero-based column and row.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// Check if cursor is visible.
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Return cell at position, or `None` if outside terminal.
+    pub fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells.get(y * self.width + x)
+    }
+
+    /// Return text of row without trailing spaces.
+    pub fn line(&self, y: usize) -> String {
+        if y >= self.height {
+            return String::new();
+        }
+        let row = &self.cells[y * self.width..(y + 1) * self.width];
+        let line: String = row
+            .iter()
+            .map(|cell| cell.ch)
+            .filter(|&ch| ch != CONTINUATION)
+            .collect();
+        line.trim_end().to_string()
+    }
+
+    /// Return text of all rows, without trailing spaces and trailing empty
+    /// rows, joined by newlines.
+    pub fn contents(&self) -> String {
+        let lines: Vec<String> = (0..self.height).map(|y| self.line(y)).collect();
+        let used = lines
+            .iter()
+            .rposition(|l| !l.is_empty())
+            .map_or(0, |i| i + 1);
+        lines[..used].join("\n")
+    }
+
+    /// Interpret output.
+    pub fn feed(&mut self, output: &str) {
+        for segment in ansi::segments(output) {
+            match segment {
+                Segment::Escape(seq) => self.escape(seq),
+                Segment::Text(text) => text.chars().for_each(|c| self.put(c)),
+            }
+        }
+    }
+
+    /// Move cursor to next line, scrolling if at bottom.
+    fn line_feed(&mut self) {
+        if self.y + 1 < self.height {
+            self.y += 1;
+        } else {
+            self.cells.drain(..self.width);
+            self.cells
+                .extend(std::iter::repeat_n(Cell::default(), self.width));
+        }
+    }
+
+    /// Blank other half of wide character at cell index, so that writing over
+    /// one half does not leave the other behind.
+    fn split_wide(&mut self, i: usize) {
+        let x = i % self.width;
+        if self.cells[i].ch == CONTINUATION && x > 0 {
+            self.cells[i - 1] = Cell::default();
+        }
+        if x + 1 < self.width && self.cells[i + 1].ch == CONTINUATION {
+            self.cells[i + 1] = Cell::default();
+        }
+    }
+
+    /// Write character at cursor and advance, wrapping at right edge.
+    fn put(&mut self, c: char) {
+        match c {
+            '\n' => {
+                // Terminals translate newline to carriage return and line feed.
+                self.x = 0;
+                return self.line_feed();
+            }
+            '\r' => {
+                self.x = 0;
+                return;
+            }
+            '\u{8}' => {
+                self.x = self.x.saturating_sub(1);
+                return;
+            }
+            '\t' => {
+                self.x = ((self.x / 8 + 1) * 8).min(self.width - 1);
+                return;
+            }
+            c if c.is_control() => return,
+            _ => (),
+        }
+
+        let width = char_width(c);
+        if width == 0 {
+            return;
+        }
+        // Characters wider than terminal are drawn in first column, clipped.
+        if self.x + width > self.width && self.x > 0 {
+            self.x = 0;
+            self.line_feed();
+        }
+        let i = self.y * self.width + self.x;
+        self.split_wide(i);
+        if width == 2 && self.x + 1 < self.width {
+            self.split_wide(i + 1);
+        }
+        self.cells[i] = Cell {
+            ch: c,
+            style: self.style,
+        };
+        if width == 2 && self.x + 1 < self.width {
+            self.cells[i + 1] = Cell {
+                ch: CONTINUATION,
+                style: self.style,
+            };
+        }
+        self.x += width;
+        if self.x >= self.width {
+            // Pending wrap, next character starts new line.
+            self.x = self.width;
+        }
+    }
+
+    /// Fill cells from start to end index with blanks.
+    fn erase(&mut self, start: usize, end: usize) {
+        let end = end.min(self.cells.len());
+        for cell in &mut self.cells[start.min(end)..end] {
+            *cell = Cell::default();
+        }
+    }
+
+    /// Interpret escape sequence.
+    fn escape(&mut self, seq: &str) {
+        match seq {
+            "\x1B7" => return self.saved = (self.x, self.y),
+            "\x1B8" => {
+                (self.x, self.y) = self.saved;
+                return;
+            }
+            _ => (),
+        }
+        let body = match seq.strip_prefix("\x1B[") {
+            Some(body) if !body.is_empty() => body,
+            _ => return,
+        };
+        let (params, last) = body.split_at(body.len() - 1);
+        if let Some(modes) = params.strip_prefix('?') {
+            if modes.split(';').any(|m| m == "25") {
+                self.cursor_visible = last == "h";
+            }
+            return;
+        }
+        let args: Vec<usize> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        let arg = |i: usize, default: usize| match args.get(i) {
+            Some(&0) | None => default,
+            Some(&n) => n,
+        };
+
+        let x = self.x.min(self.width - 1);
+        let row = self.y * self.width;
+        match last {
+            "m" => self.style = ansi::apply_sgr(self.style, seq),
+            "A" => self.y = self.y.saturating_sub(arg(0, 1)),
+            "B" => self.y = (self.y + arg(0, 1)).min(self.height - 1),
+            "C" => self.x = (x + arg(0, 1)).min(self.width - 1),
+            "D" => self.x = x.saturating_sub(arg(0, 1)),
+            "G" => self.x = (arg(0, 1) - 1).min(self.width - 1),
+            "H" | "f" => {
+                self.y = (arg(0, 1) - 1).min(self.height - 1);
+                self.x = (arg(1, 1) - 1).min(self.width - 1);
+            }
+            "K" => match args.first().copied().unwrap_or(0) {
+                0 => self.erase(row + x, row + self.width),
+                1 => self.erase(row, row + x + 1),
+                _ => self.erase(row, row + self.width),
+            },
+            "J" => match args.first().copied().unwrap_or(0) {
+                0 => self.erase(row + x, self.cells.len()),
+                1 => self.erase(0, row + x + 1),
+                _ => self.erase(0, self.cells.len()),
+            },
+            _ => (),
+        }
+    }
+}
+
+impl Write for VirtualTerm {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        // Keep incomplete UTF-8 and escape sequences until rest arrives.
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        let text = String::from_utf8_lossy(&self.pending[..valid]).into_owned();
+        let (complete, tail) = text.split_at(ansi::complete_len(&text));
+        self.feed(complete);
+        let mut pending = tail.as_bytes().to_vec();
+        pending.extend_from_slice(&self.pending[valid..]);
+        self.pending = pending;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_characters_wrap() {
+        let mut term = VirtualTerm::new(5, 2);
+        term.feed("abcd世界");
+        assert_eq!(term.contents(), "abcd\n世界");
+        assert_eq!(term.cell(1, 1).map(|cell| cell.ch), Some(CONTINUATION));
+        assert_eq!(term.cursor(), (4, 1));
+    }
+
+    #[test]
+    fn overwrite_wide_characters() {
+        let mut term = VirtualTerm::new(10, 1);
+        term.feed("世界\x1B[1Gx");
+        assert_eq!(term.line(0), "x 界");
+        term.feed("\x1B[4Gy");
+        assert_eq!(term.line(0), "x  y");
+        term.feed("\x1B[1G世");
+        assert_eq!(term.line(0), "世 y");
+        assert_eq!(term.cell(2, 0).map(|cell| cell.ch), Some(' '));
+    }
+
+    #[test]
+    fn write_split_sequences() {
+        let mut term = VirtualTerm::new(10, 1);
+        let output = "世\x1B[1mx".as_bytes();
+        for chunk in [&output[..1], &output[1..4], &output[4..6], &output[6..]] {
+            term.write_all(chunk).unwrap();
+        }
+        assert_eq!(term.line(0), "世x");
+        assert_eq!(
+            term.cell(2, 0).map(|cell| cell.style),
+            Some(Style::new().attr(crate::Attr::Bold))
+        );
+        term.write_all(b"\xFFz").unwrap();
+        assert_eq!(term.line(0), "世x\u{FFFD}z");
+    }
+
+    #[test]
+    fn wide_character_in_single_column() {
+        let mut term = VirtualTerm::new(1, 2);
+        term.feed("世界x");
+        assert_eq!(term.cell(0, 0).map(|cell| cell.ch), Some('界'));
+        assert_eq!(term.cell(0, 1).map(|cell| cell.ch), Some('x'));
+    }
+}
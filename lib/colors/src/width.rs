@@ -0,0 +1,678 @@
+//! Visible width of text in terminal columns.
+//!
+//! Escape sequences occupy no columns, East Asian wide and fullwidth
+//! characters and emoji occupy two columns, combining marks and other zero
+//! width characters occupy none. Text is measured per cluster following the
+//! basics of UAX #29, so emoji with variation selectors, skin tone modifiers,
+//! ZWJ sequences and flags are measured as the single glyph they render as.
+//!
+//! Characters classified as ambiguous by UAX #11, e.g. Greek, Cyrillic and box
+//! drawing, are narrow by default. Terminals configured for CJK environments
+//! usually render them wide, see [`set_ambiguous_width`].
+//!
+//! Truncation and wrapping work on styled text and never split clusters or
+//! escape sequences.
+//!
+//! Terminals disagree on widths, so the width backend can be swapped for the
+//! whole program with [`set_width_policy`]: built-in tables, libc wcwidth()
+//! following the system locale, or widths measured in the actual terminal,
+//! e.g. by [`calibrate`].
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::Once;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::ansi::{self, Segment};
+use crate::term::{query_cursor, RawTty};
+use crate::Style;
+
+static AMBIGUOUS_WIDE: AtomicBool = AtomicBool::new(false);
+static CUSTOM_POLICY: AtomicBool = AtomicBool::new(false);
+static POLICY: RwLock<Option<Arc<dyn WidthPolicy>>> = RwLock::new(None);
+#[cfg(unix)]
+static LOCALE_INIT: Once = Once::new();
+
+#[cfg(unix)]
+extern "C" {
+    fn wcwidth(c: libc::wchar_t) -> libc::c_int;
+}
+
+/// Return width of character reported by libc wcwidth(), or `None` if
+/// unknown.
+#[cfg(unix)]
+fn libc_width(c: char) -> Option<usize> {
+    match unsafe { wcwidth(c as libc::wchar_t) } {
+        n if n >= 0 => Some(n as usize),
+        _ => None,
+    }
+}
+
+/// Return `None`, C libraries outside Unix have no wcwidth().
+#[cfg(not(unix))]
+fn libc_width(_: char) -> Option<usize> {
+    None
+}
+
+/// Backend measuring number of columns occupied by clusters.
+pub trait WidthPolicy: Send + Sync {
+    /// Return number of columns occupied by cluster, see module
+    /// documentation for what makes a cluster.
+    fn cluster_width(&self, cluster: &str) -> usize;
+}
+
+/// Built-in Unicode tables, the default policy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Builtin;
+
+impl WidthPolicy for Builtin {
+    fn cluster_width(&self, cluster: &str) -> usize {
+        builtin_cluster_width(cluster)
+    }
+}
+
+/// Widths reported by libc wcwidth() for system locale, which matches
+/// terminals relying on the same C library. Emoji sequences, characters
+/// unknown to wcwidth() and all characters outside Unix are measured with
+/// built-in tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Wcwidth {
+    _private: (),
+}
+
+impl Wcwidth {
+    /// Return policy, on Unix setting `LC_CTYPE` locale from environment once
+    /// so wcwidth() knows about characters outside ASCII.
+    pub fn new() -> Wcwidth {
+        #[cfg(unix)]
+        LOCALE_INIT.call_once(|| unsafe {
+            libc::setlocale(libc::LC_CTYPE, b"\0".as_ptr() as *const libc::c_char);
+        });
+        Wcwidth { _private: () }
+    }
+}
+
+impl Default for Wcwidth {
+    fn default() -> Wcwidth {
+        Wcwidth::new()
+    }
+}
+
+impl WidthPolicy for Wcwidth {
+    fn cluster_width(&self, cluster: &str) -> usize {
+        let mut chars = cluster.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => libc_width(c).unwrap_or_else(|| builtin_cluster_width(cluster)),
+            _ => builtin_cluster_width(cluster),
+        }
+    }
+}
+
+/// Widths measured in actual terminal, other clusters are measured by
+/// fallback policy.
+pub struct Measured {
+    widths: HashMap<String, usize>,
+    fallback: Box<dyn WidthPolicy>,
+}
+
+impl Measured {
+    /// Return policy without measurements, falling back to built-in tables.
+    pub fn new() -> Measured {
+        Measured::with_fallback(Builtin)
+    }
+
+    /// Return policy without measurements, falling back to given policy.
+    pub fn with_fallback<P: WidthPolicy + 'static>(fallback: P) -> Measured {
+        Measured {
+            widths: HashMap::new(),
+            fallback: Box::new(fallback),
+        }
+    }
+
+    /// Add measured width of cluster.
+    pub fn insert(&mut self, cluster: &str, width: usize) {
+        self.widths.insert(cluster.to_string(), width);
+    }
+
+    /// Return measured width of cluster, if any.
+    pub fn get(&self, cluster: &str) -> Option<usize> {
+        self.widths.get(cluster).copied()
+    }
+}
+
+impl Default for Measured {
+    fn default() -> Measured {
+        Measured::new()
+    }
+}
+
+impl WidthPolicy for Measured {
+    fn cluster_width(&self, cluster: &str) -> usize {
+        match self.get(cluster) {
+            Some(width) => width,
+            None => self.fallback.cluster_width(cluster),
+        }
+    }
+}
+
+/// Set width policy used by all measurements for whole program.
+pub fn set_width_policy<P: WidthPolicy + 'static>(policy: P) {
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(policy));
+    CUSTOM_POLICY.store(true, Ordering::Release);
+}
+
+/// Restore built-in width policy.
+pub fn reset_width_policy() {
+    CUSTOM_POLICY.store(false, Ordering::Release);
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Clusters measured by [`calibrate`]: ambiguous width characters, emoji
+/// presentation variants, ZWJ sequences, skin tones and flags.
+const CALIBRATION_SAMPLES: &[&str] = &[
+    "\u{00B7}",
+    "\u{2500}",
+    "\u{03A9}",
+    "\u{2190}",
+    "\u{2605}",
+    "\u{2600}",
+    "\u{2600}\u{FE0F}",
+    "\u{2764}",
+    "\u{2764}\u{FE0F}",
+    "\u{1F600}",
+    "\u{1F44D}\u{1F3FD}",
+    "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}",
+    "\u{1F1F8}\u{1F1EA}",
+    "\u{4E16}",
+];
+
+/// Widths measured by [`calibrate`] during this session.
+static CALIBRATION: Mutex<Option<HashMap<String, usize>>> = Mutex::new(None);
+
+/// Measure how terminal renders widths of ambiguous characters and emoji
+/// sequences and use measured widths from now on, see [`Measured`].
+///
+/// Every sample is printed on the controlling terminal at the cursor
+/// position and measured with cursor position queries. The cursor is saved
+/// and restored around each sample and the line is cleared from the cursor
+/// on afterwards, so text before the cursor is kept. Results are cached for
+/// the session, later calls only install cached widths again. Fails if there
+/// is no controlling terminal or terminal does not reply.
+pub fn calibrate() -> io::Result<()> {
+    let mut cache = CALIBRATION.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.is_none() {
+        let mut tty = RawTty::open()?;
+        let (start, _) = query_cursor(&mut tty)?;
+        tty.write_str("\x1B7")?;
+        let widths = CALIBRATION_SAMPLES
+            .iter()
+            .map(|&sample| {
+                tty.write_str(&format!("\x1B8\x1B[K{}", sample))?;
+                let (column, _) = query_cursor(&mut tty)?;
+                Ok((
+                    sample.to_string(),
+                    usize::from(column.saturating_sub(start)),
+                ))
+            })
+            .collect::<io::Result<HashMap<_, _>>>();
+        tty.write_str("\x1B8\x1B[K")?;
+        *cache = Some(widths?);
+    }
+
+    let mut policy = Measured::new();
+    for (cluster, &width) in cache.iter().flatten() {
+        policy.insert(cluster, width);
+    }
+    if policy.get("\u{00B7}") == Some(2) {
+        set_ambiguous_width(AmbiguousWidth::Wide);
+    }
+    set_width_policy(policy);
+    Ok(())
+}
+
+/// Return policy set with [`set_width_policy`], if any.
+fn custom_policy() -> Option<Arc<dyn WidthPolicy>> {
+    if !CUSTOM_POLICY.load(Ordering::Acquire) {
+        return None;
+    }
+    POLICY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Width used for East Asian ambiguous characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AmbiguousWidth {
+    Narrow,
+    Wide,
+}
+
+/// Set width used for East Asian ambiguous characters for whole program.
+pub fn set_ambiguous_width(width: AmbiguousWidth) {
+    AMBIGUOUS_WIDE.store(width == AmbiguousWidth::Wide, Ordering::Relaxed);
+}
+
+/// Return width used for East Asian ambiguous characters.
+pub fn ambiguous_width() -> AmbiguousWidth {
+    if AMBIGUOUS_WIDE.load(Ordering::Relaxed) {
+        AmbiguousWidth::Wide
+    } else {
+        AmbiguousWidth::Narrow
+    }
+}
+
+/// Ranges of characters occupying two columns.
+#[rustfmt::skip]
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),   // Hangul Jamo
+    (0x231A, 0x231B),   // Watch, hourglass
+    (0x23E9, 0x23EC),   // Media buttons
+    (0x23F0, 0x23F0),   // Alarm clock
+    (0x23F3, 0x23F3),   // Hourglass with flowing sand
+    (0x25FD, 0x25FE),   // Medium small squares
+    (0x2614, 0x2615),   // Umbrella, hot beverage
+    (0x2648, 0x2653),   // Zodiac signs
+    (0x267F, 0x267F),   // Wheelchair
+    (0x2693, 0x2693),   // Anchor
+    (0x26A1, 0x26A1),   // High voltage
+    (0x26AA, 0x26AB),   // Medium circles
+    (0x26BD, 0x26BE),   // Soccer ball, baseball
+    (0x26C4, 0x26C5),   // Snowman, sun behind cloud
+    (0x26CE, 0x26CE),   // Ophiuchus
+    (0x26D4, 0x26D4),   // No entry
+    (0x26EA, 0x26EA),   // Church
+    (0x26F2, 0x26F3),   // Fountain, golf
+    (0x26F5, 0x26F5),   // Sailboat
+    (0x26FA, 0x26FA),   // Tent
+    (0x26FD, 0x26FD),   // Fuel pump
+    (0x2705, 0x2705),   // Check mark button
+    (0x270A, 0x270B),   // Raised fists
+    (0x2728, 0x2728),   // Sparkles
+    (0x274C, 0x274C),   // Cross mark
+    (0x274E, 0x274E),   // Cross mark button
+    (0x2753, 0x2755),   // Question and exclamation marks
+    (0x2757, 0x2757),   // Heavy exclamation mark
+    (0x2795, 0x2797),   // Heavy plus, minus and division
+    (0x27B0, 0x27B0),   // Curly loop
+    (0x27BF, 0x27BF),   // Double curly loop
+    (0x2B1B, 0x2B1C),   // Large squares
+    (0x2B50, 0x2B50),   // Star
+    (0x2B55, 0x2B55),   // Heavy large circle
+    (0x2E80, 0x303E),   // CJK Radicals .. CJK Symbols and Punctuation
+    (0x3041, 0x33FF),   // Hiragana .. CJK Compatibility
+    (0x3400, 0x4DBF),   // CJK Unified Ideographs Extension A
+    (0x4E00, 0x9FFF),   // CJK Unified Ideographs
+    (0xA000, 0xA4CF),   // Yi
+    (0xAC00, 0xD7A3),   // Hangul Syllables
+    (0xF900, 0xFAFF),   // CJK Compatibility Ideographs
+    (0xFE30, 0xFE4F),   // CJK Compatibility Forms
+    (0xFF00, 0xFF60),   // Fullwidth Forms
+    (0xFFE0, 0xFFE6),   // Fullwidth Signs
+    (0x1F004, 0x1F004), // Mahjong tile
+    (0x1F0CF, 0x1F0CF), // Joker
+    (0x1F18E, 0x1F18E), // AB button
+    (0x1F191, 0x1F19A), // Squared words
+    (0x1F200, 0x1F251), // Enclosed Ideographic Supplement
+    (0x1F300, 0x1F64F), // Miscellaneous Symbols and Pictographs .. Emoticons
+    (0x1F680, 0x1F6FF), // Transport and Map Symbols
+    (0x1F7E0, 0x1F7EB), // Large colored circles and squares
+    (0x1F90C, 0x1F9FF), // Supplemental Symbols and Pictographs
+    (0x1FA70, 0x1FAFF), // Symbols and Pictographs Extended-A
+    (0x20000, 0x3FFFD), // CJK Unified Ideographs Extension B ..
+];
+
+/// Ranges of East Asian ambiguous characters, most common subset.
+#[rustfmt::skip]
+const AMBIGUOUS: &[(u32, u32)] = &[
+    (0x00A1, 0x00A1), (0x00A4, 0x00A4), (0x00A7, 0x00A8), (0x00AA, 0x00AA),
+    (0x00AD, 0x00AE), (0x00B0, 0x00B4), (0x00B6, 0x00BA), (0x00BC, 0x00BF),
+    (0x00C6, 0x00C6), (0x00D0, 0x00D0), (0x00D7, 0x00D8), (0x00DE, 0x00E1),
+    (0x00E6, 0x00E6), (0x00E8, 0x00EA), (0x00EC, 0x00ED), (0x00F0, 0x00F0),
+    (0x00F2, 0x00F3), (0x00F7, 0x00FA), (0x00FC, 0x00FC), (0x00FE, 0x00FE),
+    (0x0391, 0x03A9), // Greek capital letters
+    (0x03B1, 0x03C9), // Greek small letters
+    (0x0401, 0x0401), (0x0410, 0x044F), (0x0451, 0x0451), // Cyrillic
+    (0x2010, 0x2010), (0x2013, 0x2016), (0x2018, 0x2019), (0x201C, 0x201D),
+    (0x2020, 0x2022), (0x2024, 0x2027), (0x2030, 0x2030), (0x2032, 0x2033),
+    (0x2035, 0x2035), (0x203B, 0x203B), (0x2103, 0x2103), (0x2116, 0x2116),
+    (0x2121, 0x2122), (0x2160, 0x216B), (0x2190, 0x2199), (0x2460, 0x24E9),
+    (0x2500, 0x257F), // Box Drawing
+    (0x2580, 0x258F), // Block Elements
+    (0x25A0, 0x25A1), (0x25B2, 0x25B3), (0x25C6, 0x25C8), (0x25CB, 0x25CB),
+    (0x25CF, 0x25CF), (0x2605, 0x2606), (0x2640, 0x2640), (0x2642, 0x2642),
+    (0xE000, 0xF8FF), // Private Use Area
+    (0xFFFD, 0xFFFD), // Replacement character
+];
+
+/// Ranges of characters occupying no columns.
+#[rustfmt::skip]
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F),   // Combining Diacritical Marks
+    (0x200B, 0x200F),   // Zero width space, joiners and marks
+    (0x20D0, 0x20FF),   // Combining Diacritical Marks for Symbols
+    (0xFE00, 0xFE0F),   // Variation Selectors
+    (0xFE20, 0xFE2F),   // Combining Half Marks
+    (0xE0020, 0xE007F), // Tags
+    (0xE0100, 0xE01EF), // Variation Selectors Supplement
+];
+
+const ZWJ: char = '\u{200D}';
+const VS_TEXT: char = '\u{FE0E}';
+const VS_EMOJI: char = '\u{FE0F}';
+
+fn in_table(table: &[(u32, u32)], c: char) -> bool {
+    let c = c as u32;
+    table.iter().any(|&(start, end)| c >= start && c <= end)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+fn is_skin_tone(c: char) -> bool {
+    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+/// Return number of columns occupied by character on its own.
+pub(crate) fn char_width(c: char) -> usize {
+    match custom_policy() {
+        Some(policy) => policy.cluster_width(c.encode_utf8(&mut [0; 4])),
+        None => builtin_char_width(c),
+    }
+}
+
+/// Return number of columns occupied by character according to built-in
+/// tables.
+fn builtin_char_width(c: char) -> usize {
+    if c.is_control() || in_table(ZERO, c) {
+        0
+    } else if in_table(WIDE, c)
+        || (ambiguous_width() == AmbiguousWidth::Wide && in_table(AMBIGUOUS, c))
+    {
+        2
+    } else {
+        1
+    }
+}
+
+/// Iterator over clusters of plain text, see [`clusters`].
+pub(crate) struct Clusters<'a> {
+    text: &'a str,
+}
+
+/// Return iterator splitting plain text into clusters rendered as single
+/// glyph: base character with combining marks, variation selectors, skin tone
+/// modifiers and tags, ZWJ joined sequences and regional indicator pairs.
+pub(crate) fn clusters(text: &str) -> Clusters<'_> {
+    Clusters { text }
+}
+
+impl<'a> Iterator for Clusters<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut chars = self.text.char_indices();
+        let (_, first) = chars.next()?;
+        let mut end = first.len_utf8();
+        let mut joined = false;
+        let mut pair = is_regional_indicator(first);
+
+        if first == '\r' && self.text[end..].starts_with('\n') {
+            end += 1;
+        } else {
+            for (i, c) in chars {
+                let extend = joined
+                    || in_table(ZERO, c)
+                    || is_skin_tone(c)
+                    || (pair && is_regional_indicator(c));
+                if !extend {
+                    break;
+                }
+                if is_regional_indicator(c) {
+                    pair = false;
+                }
+                joined = c == ZWJ;
+                end = i + c.len_utf8();
+            }
+        }
+
+        let (cluster, rest) = self.text.split_at(end);
+        self.text = rest;
+        Some(cluster)
+    }
+}
+
+/// Return number of columns occupied by cluster.
+pub(crate) fn cluster_width(cluster: &str) -> usize {
+    match custom_policy() {
+        Some(policy) => policy.cluster_width(cluster),
+        None => builtin_cluster_width(cluster),
+    }
+}
+
+/// Return number of columns occupied by cluster according to built-in
+/// tables.
+fn builtin_cluster_width(cluster: &str) -> usize {
+    let mut chars = cluster.chars();
+    let base = match chars.next() {
+        Some(c) => c,
+        None => return 0,
+    };
+    if is_regional_indicator(base) {
+        return if cluster.chars().count() > 1 { 2 } else { 1 };
+    }
+
+    let width = builtin_char_width(base);
+    if width == 0 {
+        return 0;
+    }
+    if cluster.contains(VS_EMOJI) {
+        return 2;
+    }
+    if cluster.contains(VS_TEXT) {
+        return 1;
+    }
+    width
+}
+
+/// Return number of columns occupied by plain text without escape sequences.
+pub(crate) fn text_width(text: &str) -> usize {
+    clusters(text).map(cluster_width).sum()
+}
+
+/// Return number of columns occupied by text, ignoring escape sequences, e.g.
+/// to align columns containing styled text. `display_width("Hello, 世界")` is
+/// 11.
+pub fn display_width(text: &str) -> usize {
+    ansi::segments(text)
+        .map(|segment| match segment {
+            Segment::Text(text) => text_width(text),
+            Segment::Escape(_) => 0,
+        })
+        .sum()
+}
+
+/// Iterator over visible clusters of styled text, see [`graphemes_visible`].
+pub struct GraphemesVisible<'a> {
+    segments: ansi::Segments<'a>,
+    text: Clusters<'a>,
+    style: Style,
+}
+
+/// Return iterator yielding every visible cluster of styled text together
+/// with the style active at that point, e.g. for custom renderers.
+///
+/// Only SGR sequences affect the style, other escape sequences are skipped.
+pub fn graphemes_visible(text: &str) -> GraphemesVisible<'_> {
+    GraphemesVisible {
+        segments: ansi::segments(text),
+        text: clusters(""),
+        style: Style::new(),
+    }
+}
+
+impl<'a> Iterator for GraphemesVisible<'a> {
+    type Item = (&'a str, Style);
+
+    fn next(&mut self) -> Option<(&'a str, Style)> {
+        loop {
+            if let Some(cluster) = self.text.next() {
+                return Some((cluster, self.style));
+            }
+            match self.segments.next()? {
+                Segment::Text(text) => self.text = clusters(text),
+                Segment::Escape(seq) => self.style = ansi::apply_sgr(self.style, seq),
+            }
+        }
+    }
+}
+
+/// Return styled text cut to at most `max` columns, ending with `ellipsis` if
+/// anything was removed.
+///
+/// All escape sequences are kept, including those after the cut, so styles
+/// stay balanced and trailing resets are not lost.
+pub fn truncate(text: &str, max: usize, ellipsis: &str) -> String {
+    if display_width(text) <= max {
+        return text.to_string();
+    }
+
+    let budget = max.saturating_sub(display_width(ellipsis));
+    let mut output = String::with_capacity(text.len());
+    let mut used = 0;
+    let mut cut = false;
+    for segment in ansi::segments(text) {
+        match segment {
+            Segment::Escape(seq) => output.push_str(seq),
+            Segment::Text(_) if cut => (),
+            Segment::Text(text) => {
+                for cluster in clusters(text) {
+                    let width = cluster_width(cluster);
+                    if used + width > budget {
+                        output.push_str(ellipsis);
+                        cut = true;
+                        break;
+                    }
+                    output.push_str(cluster);
+                    used += width;
+                }
+            }
+        }
+    }
+    output
+}
+
+/// State of [`wrap`], current line and word being collected.
+struct Wrapper {
+    width: usize,
+    lines: Vec<String>,
+    line: String,
+    line_width: usize,
+    word: String,
+    word_width: usize,
+}
+
+impl Wrapper {
+    /// Move collected word onto current line, preceded by space if `space`,
+    /// starting new line if it does not fit.
+    fn flush_word(&mut self, space: bool) {
+        let space = space && self.line_width > 0;
+        let needed = usize::from(space) + self.word_width;
+        if self.line_width > 0 && self.line_width + needed > self.width {
+            self.break_line();
+        } else if space {
+            self.line.push(' ');
+            self.line_width += 1;
+        }
+        self.line.push_str(&self.word);
+        self.line_width += self.word_width;
+        self.word.clear();
+        self.word_width = 0;
+    }
+
+    fn break_line(&mut self) {
+        self.lines.push(std::mem::take(&mut self.line));
+        self.line_width = 0;
+    }
+}
+
+/// Return styled text wrapped into lines of at most `width` columns.
+///
+/// Lines are broken at spaces where possible, words longer than a line are
+/// broken between clusters. Existing newlines are kept.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut state = Wrapper {
+        width: width.max(1),
+        lines: Vec::new(),
+        line: String::new(),
+        line_width: 0,
+        word: String::new(),
+        word_width: 0,
+    };
+
+    for source in text.split('\n') {
+        let mut space = false;
+        for segment in ansi::segments(source) {
+            let text = match segment {
+                Segment::Escape(seq) => {
+                    state.word.push_str(seq);
+                    continue;
+                }
+                Segment::Text(text) => text,
+            };
+            for cluster in clusters(text) {
+                if cluster == " " {
+                    state.flush_word(space);
+                    space = true;
+                    continue;
+                }
+                let w = cluster_width(cluster);
+                if state.word_width + w > state.width {
+                    state.flush_word(space);
+                    state.break_line();
+                    space = false;
+                }
+                state.word.push_str(cluster);
+                state.word_width += w;
+            }
+        }
+        state.flush_word(space);
+        state.break_line();
+    }
+    state.lines
+}
+
+/// Return styled text with tabs replaced by spaces up to next multiple of
+/// `tab_stop` columns, counting visible columns only.
+pub fn expand_tabs(text: &str, tab_stop: usize) -> String {
+    let tab_stop = tab_stop.max(1);
+    let mut output = String::with_capacity(text.len());
+    let mut column = 0;
+    for segment in ansi::segments(text) {
+        let text = match segment {
+            Segment::Escape(seq) => {
+                output.push_str(seq);
+                continue;
+            }
+            Segment::Text(text) => text,
+        };
+        for cluster in clusters(text) {
+            match cluster {
+                "\t" => {
+                    let n = tab_stop - column % tab_stop;
+                    output.push_str(&" ".repeat(n));
+                    column += n;
+                }
+                "\n" | "\r\n" | "\r" => {
+                    output.push_str(cluster);
+                    column = 0;
+                }
+                _ => {
+                    output.push_str(cluster);
+                    column += cluster_width(cluster);
+                }
+            }
+        }
+    }
+    output
+}
@@ -0,0 +1,418 @@
+//! Windows console support.
+//!
+//! Consoles on Windows 10 and later interpret escape sequences once virtual
+//! terminal processing is enabled for the output handle. Legacy consoles
+//! refuse the mode, colors are disabled there.
+//!
+//! Pseudo consoles (ConPTY) of Windows 10 version 1809 and later run child
+//! processes for [`capture_pty`](crate::capture_pty).
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::env;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, Read};
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::os::windows::ffi::OsStrExt;
+use std::os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle};
+use std::os::windows::process::ExitStatusExt;
+use std::process::{Command, ExitStatus};
+use std::ptr;
+use std::thread;
+
+use crate::Sink;
+
+const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+const FILE_TYPE_DISK: u32 = 0x0001;
+const FILE_TYPE_PIPE: u32 = 0x0003;
+
+const CREATE_UNICODE_ENVIRONMENT: u32 = 0x0000_0400;
+const EXTENDED_STARTUPINFO_PRESENT: u32 = 0x0008_0000;
+const INFINITE: u32 = 0xFFFF_FFFF;
+const PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE: usize = 0x0002_0016;
+const STARTF_USESTDHANDLES: u32 = 0x0000_0100;
+
+type Handle = *mut c_void;
+type CreatePseudoConsole =
+    unsafe extern "system" fn(Coord, Handle, Handle, u32, *mut Handle) -> i32;
+type ClosePseudoConsole = unsafe extern "system" fn(Handle);
+
+#[repr(C)]
+#[derive(Default)]
+struct Coord {
+    x: i16,
+    y: i16,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct SmallRect {
+    left: i16,
+    top: i16,
+    right: i16,
+    bottom: i16,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct ConsoleScreenBufferInfo {
+    size: Coord,
+    cursor_position: Coord,
+    attributes: u16,
+    window: SmallRect,
+    maximum_window_size: Coord,
+}
+
+#[repr(C)]
+struct StartupInfoW {
+    cb: u32,
+    reserved: *mut u16,
+    desktop: *mut u16,
+    title: *mut u16,
+    x: u32,
+    y: u32,
+    x_size: u32,
+    y_size: u32,
+    x_count_chars: u32,
+    y_count_chars: u32,
+    fill_attribute: u32,
+    flags: u32,
+    show_window: u16,
+    reserved2_size: u16,
+    reserved2: *mut u8,
+    std_input: Handle,
+    std_output: Handle,
+    std_error: Handle,
+}
+
+#[repr(C)]
+struct StartupInfoExW {
+    startup_info: StartupInfoW,
+    attribute_list: *mut c_void,
+}
+
+#[repr(C)]
+struct ProcessInformation {
+    process: Handle,
+    thread: Handle,
+    process_id: u32,
+    thread_id: u32,
+}
+
+extern "system" {
+    fn CreatePipe(read: *mut Handle, write: *mut Handle, attributes: *mut c_void, size: u32)
+        -> i32;
+    fn CreateProcessW(
+        application: *const u16,
+        command_line: *mut u16,
+        process_attributes: *mut c_void,
+        thread_attributes: *mut c_void,
+        inherit_handles: i32,
+        flags: u32,
+        environment: *mut c_void,
+        current_directory: *const u16,
+        startup_info: *mut StartupInfoW,
+        process_information: *mut ProcessInformation,
+    ) -> i32;
+    fn DeleteProcThreadAttributeList(list: *mut c_void);
+    fn GetExitCodeProcess(process: Handle, code: *mut u32) -> i32;
+    fn GetModuleHandleW(name: *const u16) -> Handle;
+    fn GetProcAddress(module: Handle, name: *const c_char) -> *mut c_void;
+    fn InitializeProcThreadAttributeList(
+        list: *mut c_void,
+        count: u32,
+        flags: u32,
+        size: *mut usize,
+    ) -> i32;
+    fn UpdateProcThreadAttribute(
+        list: *mut c_void,
+        flags: u32,
+        attribute: usize,
+        value: *mut c_void,
+        size: usize,
+        previous: *mut c_void,
+        returned: *mut usize,
+    ) -> i32;
+    fn WaitForSingleObject(handle: Handle, milliseconds: u32) -> u32;
+    fn GetConsoleMode(handle: *mut c_void, mode: *mut u32) -> i32;
+    fn GetConsoleScreenBufferInfo(handle: *mut c_void, info: *mut ConsoleScreenBufferInfo) -> i32;
+    fn GetFileType(handle: *mut c_void) -> u32;
+    fn SetConsoleMode(handle: *mut c_void, mode: u32) -> i32;
+}
+
+/// Return console handle of C runtime file descriptor.
+fn handle(fd: libc::c_int) -> *mut c_void {
+    unsafe { libc::get_osfhandle(fd) as *mut c_void }
+}
+
+/// Return console mode of file descriptor, or `None` if not a console.
+fn console_mode(fd: libc::c_int) -> Option<u32> {
+    let mut mode = 0;
+    if unsafe { GetConsoleMode(handle(fd), &mut mode) } == 0 {
+        return None;
+    }
+    Some(mode)
+}
+
+/// Check if file descriptor is console, `_isatty()` alone also reports true
+/// for the `NUL` device.
+pub(crate) fn is_console(fd: libc::c_int) -> bool {
+    (unsafe { libc::isatty(fd) != 0 }) && console_mode(fd).is_some()
+}
+
+/// Return visible window size of console as columns and rows.
+pub(crate) fn size(fd: libc::c_int) -> Option<(u16, u16)> {
+    let mut info = ConsoleScreenBufferInfo::default();
+    if unsafe { GetConsoleScreenBufferInfo(handle(fd), &mut info) } == 0 {
+        return None;
+    }
+    let cols = info.window.right - info.window.left + 1;
+    let rows = info.window.bottom - info.window.top + 1;
+    Some((u16::try_from(cols).ok()?, u16::try_from(rows).ok()?))
+}
+
+/// Return kind of file descriptor that is not console.
+pub(crate) fn sink(fd: libc::c_int) -> Sink {
+    match unsafe { GetFileType(handle(fd)) } {
+        FILE_TYPE_DISK => Sink::File,
+        FILE_TYPE_PIPE => Sink::Pipe,
+        _ => Sink::Other,
+    }
+}
+
+/// Enable virtual terminal processing for console and return `false` if
+/// console does not support it.
+pub(crate) fn enable_vt(fd: libc::c_int) -> bool {
+    let mode = match console_mode(fd) {
+        Some(mode) => mode,
+        None => return false,
+    };
+    if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+        return true;
+    }
+    unsafe { SetConsoleMode(handle(fd), mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0 }
+}
+
+/// Pseudo console closed when dropped.
+struct PseudoConsole {
+    handle: Handle,
+    close: ClosePseudoConsole,
+}
+
+impl Drop for PseudoConsole {
+    fn drop(&mut self) {
+        unsafe { (self.close)(self.handle) }
+    }
+}
+
+/// Process thread attribute list deleted when dropped.
+struct AttributeList(Vec<u64>);
+
+impl AttributeList {
+    /// Return initialized list with room for one attribute.
+    fn new() -> io::Result<AttributeList> {
+        let mut size = 0;
+        unsafe { InitializeProcThreadAttributeList(ptr::null_mut(), 1, 0, &mut size) };
+        let mut list = vec![0u64; size.div_ceil(mem::size_of::<u64>())];
+        if unsafe { InitializeProcThreadAttributeList(list.as_mut_ptr().cast(), 1, 0, &mut size) }
+            == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(AttributeList(list))
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.0.as_mut_ptr().cast()
+    }
+}
+
+impl Drop for AttributeList {
+    fn drop(&mut self) {
+        unsafe { DeleteProcThreadAttributeList(self.as_mut_ptr()) }
+    }
+}
+
+/// Return function exported by kernel32.dll, or `None` if missing, e.g.
+/// ConPTY functions before Windows 10 version 1809.
+fn kernel32(name: &[u8]) -> Option<*mut c_void> {
+    let module: Vec<u16> = "kernel32.dll\0".encode_utf16().collect();
+    let module = unsafe { GetModuleHandleW(module.as_ptr()) };
+    if module.is_null() {
+        return None;
+    }
+    let function = unsafe { GetProcAddress(module, name.as_ptr().cast()) };
+    (!function.is_null()).then_some(function)
+}
+
+/// Return read and write end of new anonymous pipe.
+fn pipe() -> io::Result<(File, File)> {
+    let (mut read, mut write) = (ptr::null_mut(), ptr::null_mut());
+    if unsafe { CreatePipe(&mut read, &mut write, ptr::null_mut(), 0) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { (File::from_raw_handle(read), File::from_raw_handle(write)) })
+}
+
+/// Return environment block with variables changed by command, or `None` if
+/// it inherits environment unchanged.
+fn environment(command: &Command) -> Option<Vec<u16>> {
+    command.get_envs().next()?;
+    // Names are case-insensitive and the block is sorted by them.
+    let mut vars: BTreeMap<String, (OsString, OsString)> = env::vars_os()
+        .map(|(name, value)| (name.to_string_lossy().to_uppercase(), (name, value)))
+        .collect();
+    for (name, value) in command.get_envs() {
+        let key = name.to_string_lossy().to_uppercase();
+        match value {
+            Some(value) => vars.insert(key, (name.to_os_string(), value.to_os_string())),
+            None => vars.remove(&key),
+        };
+    }
+    let mut block = Vec::new();
+    for (name, value) in vars.values() {
+        block.extend(name.encode_wide());
+        block.push(u16::from(b'='));
+        block.extend(value.encode_wide());
+        block.push(0);
+    }
+    if block.is_empty() {
+        block.push(0);
+    }
+    block.push(0);
+    Some(block)
+}
+
+/// Run command line of command in new pseudo console of given size and
+/// return exit status and everything the console printed.
+///
+/// Environment changes and working directory of command are applied, its
+/// standard input, output and error settings are ignored.
+pub(crate) fn capture_conpty(
+    command: &Command,
+    mut line: Vec<u16>,
+    cols: u16,
+    rows: u16,
+) -> io::Result<(ExitStatus, Vec<u8>)> {
+    let unsupported = || {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "pseudo consoles need Windows 10 version 1809 or later",
+        )
+    };
+    let create = kernel32(b"CreatePseudoConsole\0").ok_or_else(unsupported)?;
+    let close = kernel32(b"ClosePseudoConsole\0").ok_or_else(unsupported)?;
+    let (create, close) = unsafe {
+        (
+            mem::transmute::<*mut c_void, CreatePseudoConsole>(create),
+            mem::transmute::<*mut c_void, ClosePseudoConsole>(close),
+        )
+    };
+
+    let (input_read, input_write) = pipe()?;
+    let (output_read, output_write) = pipe()?;
+    let size = Coord {
+        x: i16::try_from(cols).unwrap_or(i16::MAX),
+        y: i16::try_from(rows).unwrap_or(i16::MAX),
+    };
+    let mut handle = ptr::null_mut();
+    let result = unsafe {
+        create(
+            size,
+            input_read.as_raw_handle(),
+            output_write.as_raw_handle(),
+            0,
+            &mut handle,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::other(format!(
+            "CreatePseudoConsole failed with {:#010x}",
+            result
+        )));
+    }
+    let console = PseudoConsole { handle, close };
+    // Pseudo console keeps its own copies of these ends.
+    drop((input_read, output_write));
+
+    let mut list = AttributeList::new()?;
+    let ok = unsafe {
+        UpdateProcThreadAttribute(
+            list.as_mut_ptr(),
+            0,
+            PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE,
+            console.handle,
+            mem::size_of::<Handle>(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut info: StartupInfoExW = unsafe { mem::zeroed() };
+    info.startup_info.cb = mem::size_of::<StartupInfoExW>() as u32;
+    // Null standard handles, child would inherit those of this process
+    // instead of using the pseudo console if they are redirected.
+    info.startup_info.flags = STARTF_USESTDHANDLES;
+    info.attribute_list = list.as_mut_ptr();
+    let mut environment = environment(command);
+    let directory: Option<Vec<u16>> = command
+        .get_current_dir()
+        .map(|dir| dir.as_os_str().encode_wide().chain(Some(0)).collect());
+    line.push(0);
+
+    let mut process: ProcessInformation = unsafe { mem::zeroed() };
+    let ok = unsafe {
+        CreateProcessW(
+            ptr::null(),
+            line.as_mut_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            0,
+            EXTENDED_STARTUPINFO_PRESENT | CREATE_UNICODE_ENVIRONMENT,
+            environment
+                .as_mut()
+                .map_or(ptr::null_mut(), |block| block.as_mut_ptr().cast()),
+            directory.as_ref().map_or(ptr::null(), |dir| dir.as_ptr()),
+            &mut info.startup_info,
+            &mut process,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let (process, _thread) = unsafe {
+        (
+            OwnedHandle::from_raw_handle(process.process),
+            OwnedHandle::from_raw_handle(process.thread),
+        )
+    };
+
+    // Output only ends once pseudo console is closed, which may wait until
+    // pending output is read.
+    let mut output_read = output_read;
+    let reader = thread::spawn(move || {
+        let mut bytes = Vec::new();
+        match output_read.read_to_end(&mut bytes) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e),
+            _ => Ok(bytes),
+        }
+    });
+
+    unsafe { WaitForSingleObject(process.as_raw_handle(), INFINITE) };
+    let mut code = 0;
+    if unsafe { GetExitCodeProcess(process.as_raw_handle(), &mut code) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    drop(console);
+    drop(input_write);
+    let bytes = reader
+        .join()
+        .unwrap_or_else(|_| Err(io::Error::other("reading output failed")))?;
+    Ok((ExitStatus::from_raw(code), bytes))
+}
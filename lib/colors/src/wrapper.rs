@@ -0,0 +1,84 @@
+//! Wrapping of emitted escape sequences.
+//!
+//! Some environments need every escape sequence surrounded by extra bytes,
+//! e.g. tmux passthrough or shell prompts where the line editor must be told
+//! which bytes occupy no columns. Use [`Codes::wrapped`](crate::Codes::wrapped)
+//! to apply a wrapper to all codes and styles rendered through them.
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let ansi = colors::init_auto().wrapped(colors::CodeWrapper::Zsh);
+//! print!("{}%~{} %# ", ansi.fg.blue, ansi.attr.reset);
+//! ```
+
+use std::io::{self, Write};
+
+/// Strategy used for wrapping escape sequences.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CodeWrapper {
+    /// Sequences are emitted as is.
+    #[default]
+    None,
+    /// Sequences are surrounded by custom prefix and suffix.
+    Affix { prefix: String, suffix: String },
+    /// Sequences are passed through tmux to the outer terminal.
+    Tmux,
+    /// Sequences are surrounded by `\x01` and `\x02` as used by readline,
+    /// e.g. Bash prompts generated by command substitution.
+    Readline,
+    /// Sequences are surrounded by `%{` and `%}` as used in Zsh prompts.
+    Zsh,
+}
+
+impl CodeWrapper {
+    /// Return wrapped escape sequence, empty sequences are returned as is.
+    pub fn wrap(&self, seq: &str) -> String {
+        if seq.is_empty() {
+            return String::new();
+        }
+        match self {
+            CodeWrapper::None => seq.to_string(),
+            CodeWrapper::Affix { prefix, suffix } => format!("{}{}{}", prefix, seq, suffix),
+            CodeWrapper::Tmux => format!("\x1BPtmux;{}\x1B\\", seq.replace('\x1B', "\x1B\x1B")),
+            CodeWrapper::Readline => format!("\x01{}\x02", seq),
+            CodeWrapper::Zsh => format!("%{{{}%}}", seq),
+        }
+    }
+
+    /// Write wrapped escape sequence to writer without intermediate string,
+    /// empty sequences are not written.
+    pub fn write_to<W: Write>(&self, seq: &[u8], writer: &mut W) -> io::Result<()> {
+        if seq.is_empty() {
+            return Ok(());
+        }
+        match self {
+            CodeWrapper::None => writer.write_all(seq),
+            CodeWrapper::Affix { prefix, suffix } => {
+                writer.write_all(prefix.as_bytes())?;
+                writer.write_all(seq)?;
+                writer.write_all(suffix.as_bytes())
+            }
+            CodeWrapper::Tmux => {
+                writer.write_all(b"\x1BPtmux;")?;
+                for (i, part) in seq.split(|&b| b == 0x1B).enumerate() {
+                    if i > 0 {
+                        writer.write_all(b"\x1B\x1B")?;
+                    }
+                    writer.write_all(part)?;
+                }
+                writer.write_all(b"\x1B\\")
+            }
+            CodeWrapper::Readline => {
+                writer.write_all(b"\x01")?;
+                writer.write_all(seq)?;
+                writer.write_all(b"\x02")
+            }
+            CodeWrapper::Zsh => {
+                writer.write_all(b"%{")?;
+                writer.write_all(seq)?;
+                writer.write_all(b"%}")
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Colored output to any writer.
+//!
+//! [`ColoredWriter`] wraps files, sockets or in-memory buffers and writes
+//! escape sequences from [`Codes`] directly, instead of formatting styled
+//! strings first. Nothing but the text is written if colors are disabled.
+//! The current style is tracked so that styled writes can restore it. Writes
+//! are counted by [`stats`](crate::stats) as [`Source::Writer`].
+//!
+//! [`Source::Writer`]: crate::stats::Source::Writer
+//!
+//! Usage:
+//!
+//! ```rust,ignore
+//! let mut out = colors::ColoredWriter::new(colors::init_auto(), io::stdout().lock());
+//! out.set_fg(Color::Green)?;
+//! write!(out, "ok")?;
+//! out.reset()?;
+//! out.write_styled(&Style::new().attr(Attr::Dim), " (3 files)\n")?;
+//! ```
+
+use std::io::{self, Write};
+
+use crate::stats::Counted;
+use crate::{Attr, Bg, Codes, Color, Fg, Style};
+
+/// Writer emitting escape sequences only when colors are enabled.
+#[derive(Debug)]
+pub struct ColoredWriter<W: Write> {
+    codes: Codes,
+    writer: Counted<W>,
+    style: Style,
+}
+
+impl<W: Write> ColoredWriter<W> {
+    pub fn new(codes: Codes, writer: W) -> ColoredWriter<W> {
+        ColoredWriter {
+            codes,
+            writer: Counted::new(writer),
+            style: Style::new(),
+        }
+    }
+
+    /// Return codes used for escape sequences.
+    pub fn codes(&self) -> &Codes {
+        &self.codes
+    }
+
+    /// Return style set since last reset.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Return reference to wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.get_ref()
+    }
+
+    /// Return mutable reference to wrapped writer, writing through it
+    /// bypasses style tracking and counting.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
+
+    /// Return wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    /// Set foreground color.
+    pub fn set_fg(&mut self, color: Color) -> io::Result<()> {
+        self.style = self.style.fg(color);
+        self.writer
+            .write_all(Fg(color).code(&self.codes).as_bytes())
+    }
+
+    /// Set background color.
+    pub fn set_bg(&mut self, color: Color) -> io::Result<()> {
+        self.style = self.style.bg(color);
+        self.writer
+            .write_all(Bg(color).code(&self.codes).as_bytes())
+    }
+
+    /// Enable attribute.
+    pub fn set_attr(&mut self, attr: Attr) -> io::Result<()> {
+        self.style = self.style.attr(attr);
+        self.writer.write_all(attr.code(&self.codes).as_bytes())
+    }
+
+    /// Replace current style, resetting previous colors and attributes.
+    pub fn set_style(&mut self, style: &Style) -> io::Result<()> {
+        self.reset()?;
+        self.style = *style;
+        style.write_to(&self.codes, &mut self.writer)
+    }
+
+    /// Reset colors and attributes, nothing is written if no style is set.
+    pub fn reset(&mut self) -> io::Result<()> {
+        if self.style.is_plain() {
+            return Ok(());
+        }
+        self.style = Style::new();
+        self.codes.write_reset(&mut self.writer)
+    }
+
+    /// Write text in style, on top of current style, and restore current
+    /// style afterwards.
+    pub fn write_styled(&mut self, style: &Style, text: &str) -> io::Result<()> {
+        if !self.codes.is_enabled() || style.is_plain() {
+            return self.writer.write_all(text.as_bytes());
+        }
+        style.write_to(&self.codes, &mut self.writer)?;
+        self.writer.write_all(text.as_bytes())?;
+        self.codes.write_reset(&mut self.writer)?;
+        self.style.write_to(&self.codes, &mut self.writer)
+    }
+}
+
+impl<W: Write> Write for ColoredWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}